@@ -0,0 +1,154 @@
+#include "animation.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All live animation handles by ID
+static std::unordered_map<long, Animation*> animations;
+
+Animation::Animation(
+    string id, std::vector<Keyframe> keyframes, AnimationOptions opts)
+    : id(id)
+    , anim_id(id_counter++)
+    , keyframes(keyframes)
+    , opts(opts)
+{
+    animations[anim_id] = this;
+    if (!start()) {
+        const long a_id = anim_id;
+        defer([a_id]() {
+            if (animations.count(a_id)) {
+                animations.at(a_id)->start();
+            }
+        });
+    }
+}
+
+Animation::~Animation() { animations.erase(anim_id); }
+
+bool Animation::start()
+{
+    auto el = val::global("__bh_get")(id);
+    if (el.isNull() || el["animate"].isUndefined()) {
+        return false;
+    }
+
+    auto frames = val::array();
+    for (size_t i = 0; i < keyframes.size(); i++) {
+        auto f = val::object();
+        for (auto & [ k, v ] : keyframes[i]) {
+            if (k == "offset") {
+                f.set(k, std::stod(v));
+            } else {
+                f.set(k, v);
+            }
+        }
+        frames.set(i, f);
+    }
+    auto o = val::object();
+    o.set("duration", opts.duration);
+    o.set("delay", opts.delay);
+    o.set("iterations", opts.iterations);
+    o.set("easing", opts.easing);
+    o.set("fill", opts.fill);
+    o.set("direction", opts.direction);
+
+    anim = el.call<val>("animate", frames, o);
+    EM_ASM({
+        if (!window.__bh_on_animation_finish) {
+            window.__bh_on_animation_finish = function(anim, id)
+            {
+                anim.onfinish = function()
+                {
+                    Module._bh_animation_finished(id);
+                };
+            };
+        }
+    });
+    val::global("__bh_on_animation_finish")(anim, anim_id);
+    started = true;
+    return true;
+}
+
+void Animation::call(const char* method)
+{
+    if (started) {
+        anim.call<void>(method);
+    }
+}
+
+void Animation::play()
+{
+    is_finished = false;
+    call("play");
+}
+
+void Animation::pause() { call("pause"); }
+
+void Animation::reverse()
+{
+    is_finished = false;
+    call("reverse");
+}
+
+void Animation::finish() { call("finish"); }
+
+void Animation::cancel() { call("cancel"); }
+
+double Animation::current_time() const
+{
+    if (!started) {
+        return 0;
+    }
+    const auto t = anim["currentTime"];
+    return t.isNull() ? 0 : t.as<double>();
+}
+
+void Animation::set_current_time(double ms)
+{
+    if (started) {
+        anim.set("currentTime", ms);
+    }
+}
+
+void Animation::on_finish(std::function<void()> fn)
+{
+    finish_cb = fn;
+    if (is_finished) {
+        const long a_id = anim_id;
+        defer([a_id]() {
+            if (animations.count(a_id)) {
+                auto a = animations.at(a_id);
+                if (a->is_finished && a->finish_cb) {
+                    a->finish_cb();
+                }
+            }
+        });
+    }
+}
+
+void run_animation_finished(long id)
+{
+    if (!animations.count(id)) {
+        return;
+    }
+    auto a = animations.at(id);
+    a->is_finished = true;
+    if (a->finish_cb) {
+        a->finish_cb();
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_animation)
+{
+    emscripten::function("_bh_animation_finished", &run_animation_finished);
+}
+}
@@ -0,0 +1,88 @@
+#pragma once
+
+#include <emscripten/val.h>
+#include <functional>
+#include <map>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Keyframe mapping CSS properties in camelCase, as well as "offset" and
+// "easing", to values
+typedef std::map<std::string, std::string> Keyframe;
+
+// Options of an element.animate() call
+struct AnimationOptions {
+    // Duration and delay in milliseconds
+    double duration = 300, delay = 0;
+
+    // Number of iterations. Use INFINITY to repeat forever.
+    double iterations = 1;
+
+    // CSS easing function
+    std::string easing = "linear";
+
+    // "none", "forwards", "backwards" or "both"
+    std::string fill = "none";
+
+    // "normal", "reverse", "alternate" or "alternate-reverse"
+    std::string direction = "normal";
+};
+
+// Handle to a Web Animations API animation of an element.
+// If the element does not exist yet, the animation starts after the next
+// flush of DOM mutations. Destroying the handle does not stop the animation,
+// but no more callbacks are called.
+class Animation {
+public:
+    // ID of animated element
+    const std::string id;
+
+    Animation(std::string id, std::vector<Keyframe> keyframes,
+        AnimationOptions opts = {});
+    ~Animation();
+
+    Animation(const Animation&) = delete;
+    Animation& operator=(const Animation&) = delete;
+
+    void play();
+    void pause();
+    void reverse();
+
+    // Jump to the end of the animation
+    void finish();
+
+    // Stop the animation and remove its effects
+    void cancel();
+
+    // Returns the current time of the animation in milliseconds
+    double current_time() const;
+
+    // Seek to a time in milliseconds
+    void set_current_time(double ms);
+
+    // Call fn, when the animation finishes. Replaces any previous callback.
+    // Called immediately after the next flush, if already finished.
+    void on_finish(std::function<void()> fn);
+
+    // Returns, if the animation has finished
+    bool finished() const { return is_finished; }
+
+private:
+    const long anim_id;
+    const std::vector<Keyframe> keyframes;
+    const AnimationOptions opts;
+    emscripten::val anim;
+    bool started = false, is_finished = false;
+    std::function<void()> finish_cb;
+
+    // Start the animation. Returns false, if the element does not exist.
+    bool start();
+
+    // Call a method of the JS Animation object, if started
+    void call(const char* method);
+
+    friend void run_animation_finished(long);
+};
+}
@@ -0,0 +1,100 @@
+#include "announce.hh"
+#include <emscripten.h>
+
+namespace brunhild {
+
+// Install the JS announcer, if needed
+static void install()
+{
+    EM_ASM({
+        if (window.__bh_announcer) {
+            return;
+        }
+
+        // Delay between clearing a region and setting its content. Screen
+        // readers only pick up changes to the content of a region, so
+        // repeated messages need the region to be cleared first.
+        var delay = 100;
+
+        var regions = {};
+        var queue = [];
+        var timer = 0;
+
+        var region = function(politeness)
+        {
+            var el = regions[politeness];
+            if (el && el.isConnected) {
+                return el;
+            }
+            el = document.createElement("div");
+            el.id = "bh-announcer-" + politeness;
+            el.setAttribute("aria-live", politeness);
+            el.setAttribute("aria-atomic", "true");
+            el.setAttribute(
+                "role", politeness == "assertive" ? "alert" : "status");
+            el.style.cssText = "position:absolute;width:1px;height:1px;"
+                + "margin:-1px;padding:0;overflow:hidden;"
+                + "clip:rect(0,0,0,0);white-space:nowrap;border:0";
+            document.body.appendChild(el);
+            regions[politeness] = el;
+            return el;
+        };
+
+        var next = function()
+        {
+            timer = 0;
+            var a = queue.shift();
+            if (!a) {
+                return;
+            }
+            var el = region(a.politeness);
+            el.textContent = "";
+            timer = setTimeout(function() {
+                el.textContent = a.message;
+                timer = setTimeout(next, delay);
+            }, delay);
+        };
+
+        window.__bh_announcer = {
+            push : function(message, politeness)
+            {
+                queue.push({ message : message, politeness : politeness });
+                if (!timer) {
+                    next();
+                }
+            },
+            clear : function()
+            {
+                queue = [];
+                clearTimeout(timer);
+                timer = 0;
+                for (var k in regions) {
+                    regions[k].textContent = "";
+                }
+            }
+        };
+    });
+}
+
+void announce(const std::string& message, Live politeness)
+{
+    if (politeness == Live::off || message.empty()) {
+        return;
+    }
+    install();
+    EM_ASM_INT(
+        {
+            window.__bh_announcer.push(UTF8ToString($0), UTF8ToString($1));
+        },
+        message.c_str(), live_name(politeness));
+}
+
+void clear_announcements()
+{
+    EM_ASM({
+        if (window.__bh_announcer) {
+            window.__bh_announcer.clear();
+        }
+    });
+}
+}
@@ -0,0 +1,16 @@
+#pragma once
+
+#include "aria.hh"
+#include <string>
+
+namespace brunhild {
+
+// Announce a message to screen reader users through a visually hidden
+// aria-live region of the given politeness, that is created on first use.
+// Announcements are queued and spaced out, so each one is read, and repeated
+// identical messages are announced again. Live::off is ignored.
+void announce(const std::string& message, Live politeness = Live::polite);
+
+// Drop all queued announcements and clear the live regions
+void clear_announcements();
+}
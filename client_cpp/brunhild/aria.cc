@@ -0,0 +1,186 @@
+#include "aria.hh"
+#include <sstream>
+#include <unordered_set>
+
+namespace brunhild {
+
+const char* role_name(Role r)
+{
+    switch (r) {
+    case Role::alert:
+        return "alert";
+    case Role::alertdialog:
+        return "alertdialog";
+    case Role::application:
+        return "application";
+    case Role::article:
+        return "article";
+    case Role::banner:
+        return "banner";
+    case Role::button:
+        return "button";
+    case Role::cell:
+        return "cell";
+    case Role::checkbox:
+        return "checkbox";
+    case Role::columnheader:
+        return "columnheader";
+    case Role::combobox:
+        return "combobox";
+    case Role::complementary:
+        return "complementary";
+    case Role::contentinfo:
+        return "contentinfo";
+    case Role::dialog:
+        return "dialog";
+    case Role::document:
+        return "document";
+    case Role::feed:
+        return "feed";
+    case Role::figure:
+        return "figure";
+    case Role::form:
+        return "form";
+    case Role::grid:
+        return "grid";
+    case Role::gridcell:
+        return "gridcell";
+    case Role::group:
+        return "group";
+    case Role::heading:
+        return "heading";
+    case Role::img:
+        return "img";
+    case Role::link:
+        return "link";
+    case Role::list:
+        return "list";
+    case Role::listbox:
+        return "listbox";
+    case Role::listitem:
+        return "listitem";
+    case Role::log:
+        return "log";
+    case Role::main:
+        return "main";
+    case Role::marquee:
+        return "marquee";
+    case Role::menu:
+        return "menu";
+    case Role::menubar:
+        return "menubar";
+    case Role::menuitem:
+        return "menuitem";
+    case Role::menuitemcheckbox:
+        return "menuitemcheckbox";
+    case Role::menuitemradio:
+        return "menuitemradio";
+    case Role::navigation:
+        return "navigation";
+    case Role::none:
+        return "none";
+    case Role::note:
+        return "note";
+    case Role::option:
+        return "option";
+    case Role::presentation:
+        return "presentation";
+    case Role::progressbar:
+        return "progressbar";
+    case Role::radio:
+        return "radio";
+    case Role::radiogroup:
+        return "radiogroup";
+    case Role::region:
+        return "region";
+    case Role::row:
+        return "row";
+    case Role::rowgroup:
+        return "rowgroup";
+    case Role::rowheader:
+        return "rowheader";
+    case Role::scrollbar:
+        return "scrollbar";
+    case Role::search:
+        return "search";
+    case Role::searchbox:
+        return "searchbox";
+    case Role::separator:
+        return "separator";
+    case Role::slider:
+        return "slider";
+    case Role::spinbutton:
+        return "spinbutton";
+    case Role::status:
+        return "status";
+    case Role::switch_:
+        return "switch";
+    case Role::tab:
+        return "tab";
+    case Role::table:
+        return "table";
+    case Role::tablist:
+        return "tablist";
+    case Role::tabpanel:
+        return "tabpanel";
+    case Role::term:
+        return "term";
+    case Role::textbox:
+        return "textbox";
+    case Role::timer:
+        return "timer";
+    case Role::toolbar:
+        return "toolbar";
+    case Role::tooltip:
+        return "tooltip";
+    case Role::tree:
+        return "tree";
+    case Role::treegrid:
+        return "treegrid";
+    case Role::treeitem:
+        return "treeitem";
+    }
+    return "";
+}
+
+bool valid_role(const std::string& s)
+{
+    static const std::unordered_set<std::string> roles = {
+        "alert", "alertdialog", "application", "article", "banner", "button",
+        "cell", "checkbox", "columnheader", "combobox", "complementary",
+        "contentinfo", "dialog", "document", "feed", "figure", "form", "grid",
+        "gridcell", "group", "heading", "img", "link", "list", "listbox",
+        "listitem", "log", "main", "marquee", "menu", "menubar", "menuitem",
+        "menuitemcheckbox", "menuitemradio", "navigation", "none", "note",
+        "option", "presentation", "progressbar", "radio", "radiogroup",
+        "region", "row", "rowgroup", "rowheader", "scrollbar", "search",
+        "searchbox", "separator", "slider", "spinbutton", "status", "switch",
+        "tab", "table", "tablist", "tabpanel", "term", "textbox", "timer",
+        "toolbar", "tooltip", "tree", "treegrid", "treeitem",
+    };
+
+    std::istringstream ss(s);
+    std::string r;
+    bool any = false;
+    while (ss >> r) {
+        if (!roles.count(r)) {
+            return false;
+        }
+        any = true;
+    }
+    return any;
+}
+
+const char* live_name(Live l)
+{
+    switch (l) {
+    case Live::off:
+        return "off";
+    case Live::polite:
+        return "polite";
+    case Live::assertive:
+        return "assertive";
+    }
+    return "";
+}
+}
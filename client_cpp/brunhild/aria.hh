@@ -0,0 +1,93 @@
+#pragma once
+
+#include <string>
+
+// Typed values of ARIA attributes. Set with the aria_* methods of Attrs.
+namespace brunhild {
+
+// WAI-ARIA roles. Names with a trailing underscore avoid C++ keywords.
+enum class Role {
+    alert,
+    alertdialog,
+    application,
+    article,
+    banner,
+    button,
+    cell,
+    checkbox,
+    columnheader,
+    combobox,
+    complementary,
+    contentinfo,
+    dialog,
+    document,
+    feed,
+    figure,
+    form,
+    grid,
+    gridcell,
+    group,
+    heading,
+    img,
+    link,
+    list,
+    listbox,
+    listitem,
+    log,
+    main,
+    marquee,
+    menu,
+    menubar,
+    menuitem,
+    menuitemcheckbox,
+    menuitemradio,
+    navigation,
+    none,
+    note,
+    option,
+    presentation,
+    progressbar,
+    radio,
+    radiogroup,
+    region,
+    row,
+    rowgroup,
+    rowheader,
+    scrollbar,
+    search,
+    searchbox,
+    separator,
+    slider,
+    spinbutton,
+    status,
+    switch_,
+    tab,
+    table,
+    tablist,
+    tabpanel,
+    term,
+    textbox,
+    timer,
+    toolbar,
+    tooltip,
+    tree,
+    treegrid,
+    treeitem,
+};
+
+// Returns the attribute value of a role
+const char* role_name(Role);
+
+// Returns, if a string is a valid value of the "role" attribute. Multiple
+// space-separated fallback roles are allowed.
+bool valid_role(const std::string&);
+
+// Politeness of a live region
+enum class Live { off, polite, assertive };
+
+// Returns the attribute value of a politeness setting
+const char* live_name(Live);
+
+// Value of tri-state attributes like aria-checked and aria-pressed
+enum class Tristate { false_, true_, mixed };
+}
@@ -0,0 +1,115 @@
+#include "assets.hh"
+#include "promise.hh"
+#include <emscripten.h>
+#include <memory>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+// Number of mounted views using a stylesheet loaded by load_style() by URL
+static std::unordered_map<string, unsigned> style_users;
+
+// Returns the JS asset helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_assets) {
+            return;
+        }
+
+        // Load entries by kind and URL
+        var loads = {};
+
+        window.__bh_assets = {
+            load : function(kind, url)
+            {
+                var key = kind + ":" + url;
+                if (loads[key]) {
+                    return loads[key].promise;
+                }
+
+                var el;
+                if (kind == "script") {
+                    el = document.createElement("script");
+                    el.src = url;
+                    el.async = true;
+                } else {
+                    el = document.createElement("link");
+                    el.rel = "stylesheet";
+                    el.href = url;
+                }
+                var entry = { el : el };
+                entry.promise = new Promise(function(resolve, reject) {
+                    el.onload = function() { resolve(); };
+                    el.onerror = function()
+                    {
+                        if (loads[key] === entry) {
+                            delete loads[key];
+                        }
+                        el.remove();
+                        reject(new Error("failed to load " + url));
+                    };
+                });
+                loads[key] = entry;
+                document.head.appendChild(el);
+                return entry.promise;
+            },
+            remove : function(kind, url)
+            {
+                var key = kind + ":" + url;
+                if (loads[key]) {
+                    loads[key].el.remove();
+                    delete loads[key];
+                }
+            }
+        };
+    });
+    return val::global("__bh_assets");
+}
+
+val load_script(const string& url)
+{
+    return helpers().call<val>("load", string("script"), url);
+}
+
+val load_style(const string& url)
+{
+    return helpers().call<val>("load", string("style"), url);
+}
+
+// Call cb with the settled promise, unless the view is unmounted before
+static void settle_mounted(View& view, val promise, OnAssetLoad cb)
+{
+    auto mounted = std::make_shared<bool>(true);
+    view.on_next_unmount([mounted]() { *mounted = false; });
+    await(promise, [mounted, cb](bool ok, val err) {
+        if (!ok) {
+            val::global("console").call<void>("error", err);
+        }
+        if (*mounted && cb) {
+            cb(ok);
+        }
+    });
+}
+
+void load_script(View& view, const string& url, OnAssetLoad cb)
+{
+    settle_mounted(view, load_script(url), cb);
+}
+
+void load_style(View& view, const string& url, OnAssetLoad cb)
+{
+    style_users[url]++;
+    view.on_next_unmount([url]() {
+        auto it = style_users.find(url);
+        if (it != style_users.end() && !--it->second) {
+            style_users.erase(it);
+            helpers().call<void>("remove", string("style"), url);
+        }
+    });
+    settle_mounted(view, load_style(url), cb);
+}
+}
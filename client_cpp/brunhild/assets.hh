@@ -0,0 +1,33 @@
+#pragma once
+
+#include "view.hh"
+#include <emscripten/val.h>
+#include <functional>
+#include <string>
+
+// Loading of scripts and stylesheets at runtime, like for third-party widgets
+// initialized from View::on_mount(). Each URL is only requested once.
+// Concurrent and repeated loads of the same URL share the same request.
+// Failed loads are removed, so the next call retries them.
+namespace brunhild {
+
+// Receives, if the asset has loaded
+typedef std::function<void(bool ok)> OnAssetLoad;
+
+// Load a script by URL. Returns a JS promise, that resolves, when the script
+// has executed, and rejects on failure.
+emscripten::val load_script(const std::string& url);
+
+// Load a stylesheet by URL. Returns a JS promise, that resolves, when the
+// stylesheet has loaded, and rejects on failure.
+emscripten::val load_style(const std::string& url);
+
+// Load a script and call cb with the result, unless the view has been
+// unmounted in the meantime
+void load_script(View& view, const std::string& url, OnAssetLoad cb);
+
+// Load a stylesheet and call cb with the result, unless the view has been
+// unmounted in the meantime. cb is optional. The stylesheet is removed, once
+// all views it was loaded for have been unmounted.
+void load_style(View& view, const std::string& url, OnAssetLoad cb = nullptr);
+}
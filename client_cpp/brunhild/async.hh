@@ -0,0 +1,111 @@
+#pragma once
+
+#include "debug.hh"
+#include "mutations.hh"
+#include "node.hh"
+#include "view.hh"
+#include <functional>
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// Renders a placeholder, until the real view is loaded asynchronously. The
+// loader is started on mount and the placeholder is swapped for the loaded
+// view, once it resolves. Resolving after the Async view has been destroyed is
+// a NOP.
+// V: view type to load
+template <class V = View> class Async : public View {
+public:
+    // Callback to pass the loaded view to
+    typedef std::function<void(std::shared_ptr<V>)> Resolve;
+
+    // Starts loading a view and calls Resolve, when done. Can resolve
+    // synchronously or at any later point in time.
+    typedef std::function<void(Resolve)> Loader;
+
+    // Tag of root node
+    const std::string tag;
+
+    // Creates a new view, that renders placeholder, until loader resolves
+    Async(Loader loader, Node placeholder = { "span" }, std::string tag = "div",
+        std::string id = new_id())
+        : View(id)
+        , tag(tag)
+        , loader(loader)
+        , placeholder(placeholder)
+    {
+    }
+
+    ~Async() { *alive = false; }
+
+    void write_html(Rope& s)
+    {
+        if (!is_initialized) {
+            is_initialized = true;
+            schedule_mount();
+        }
+
+        s << '<' << tag << " id=\"" << id << '"';
+        debug::write_type_attr(s, typeid(*this));
+        s << '>';
+        if (loaded) {
+            loaded->write_html(s);
+        } else {
+            placeholder.write_html(s);
+        }
+        s << "</" << tag << '>';
+    }
+
+    // Patch the loaded view, if any
+    void patch()
+    {
+        if (loaded) {
+            loaded->patch();
+        }
+    }
+
+    // Returns the loaded view or NULL, if still loading
+    V* get() const { return loaded.get(); }
+
+protected:
+    // Start loading the view
+    void mount()
+    {
+        View::mount();
+        if (loaded || loading) {
+            return;
+        }
+
+        loading = true;
+        auto alive = this->alive;
+        loader([this, alive](std::shared_ptr<V> v) {
+            if (!*alive || !v) {
+                return;
+            }
+            loading = false;
+            loaded = v;
+            if (is_mounted()) {
+                set_inner_html(id, v->html());
+            }
+        });
+    }
+
+    void unmount()
+    {
+        if (loaded) {
+            unmount_child(*loaded);
+        }
+        View::unmount();
+    }
+
+private:
+    const Loader loader;
+    Node placeholder;
+    std::shared_ptr<V> loaded;
+    bool is_initialized = false, loading = false;
+
+    // Guards against resolving after the view's destruction
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+};
+}
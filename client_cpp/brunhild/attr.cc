@@ -0,0 +1,54 @@
+#include "attr.hh"
+#include "node.hh"
+#include "util.hh"
+
+using std::string;
+
+namespace brunhild {
+
+// Attribute with a text value
+static Attr text(const char* key, const string& val)
+{
+    return Attr::custom(key, val);
+}
+
+Attr Attr::id(const string& v) { return text("id", v); }
+Attr Attr::class_(const string& v) { return text("class", v); }
+Attr Attr::href(const string& v) { return text("href", v); }
+Attr Attr::src(const string& v) { return text("src", v); }
+Attr Attr::alt(const string& v) { return text("alt", v); }
+Attr Attr::title(const string& v) { return text("title", v); }
+Attr Attr::name(const string& v) { return text("name", v); }
+Attr Attr::type(const string& v) { return text("type", v); }
+Attr Attr::value_(const string& v) { return text("value", v); }
+Attr Attr::placeholder(const string& v) { return text("placeholder", v); }
+Attr Attr::target(const string& v) { return text("target", v); }
+Attr Attr::rel(const string& v) { return text("rel", v); }
+Attr Attr::for_(const string& v) { return text("for", v); }
+
+Attr Attr::tabindex(int i) { return custom("tabindex", std::to_string(i)); }
+
+Attr Attr::disabled(bool b) { return custom("disabled", b); }
+Attr Attr::checked(bool b) { return custom("checked", b); }
+Attr Attr::selected(bool b) { return custom("selected", b); }
+Attr Attr::hidden(bool b) { return custom("hidden", b); }
+Attr Attr::readonly(bool b) { return custom("readonly", b); }
+Attr Attr::required(bool b) { return custom("required", b); }
+Attr Attr::multiple(bool b) { return custom("multiple", b); }
+Attr Attr::autofocus(bool b) { return custom("autofocus", b); }
+
+Attr Attr::data(const string& key, const string& val)
+{
+    return custom(data_attr(key), val);
+}
+
+Attr Attr::custom(const string& key, const string& val)
+{
+    return Attr(key, val);
+}
+
+Attr Attr::custom(const string& key, bool b)
+{
+    return Attr(key, b ? std::optional<string>("") : std::nullopt);
+}
+}
@@ -0,0 +1,68 @@
+#pragma once
+
+#include <optional>
+#include <string>
+
+namespace brunhild {
+
+// Typed common HTML attribute. Set on Attrs with Attrs::set() or build Attrs
+// with Attrs::of():
+//
+//     Attrs::of({ Attr::class_("button"), Attr::disabled(!enabled),
+//         Attr::title(tooltip) })
+//
+// Values are stored unescaped and HTML-escaped, when serialized. Boolean
+// attributes set to false are omitted from the Attrs entirely, so diffing
+// removes them from the element.
+class Attr {
+public:
+    // Name of the attribute
+    const std::string key;
+
+    // Serialized value of the attribute. Empty optional, if the attribute
+    // should be absent.
+    const std::optional<std::string> value;
+
+    // Names with a trailing underscore avoid C++ keywords
+    static Attr id(const std::string&);
+    static Attr class_(const std::string&);
+    static Attr href(const std::string&);
+    static Attr src(const std::string&);
+    static Attr alt(const std::string&);
+    static Attr title(const std::string&);
+    static Attr name(const std::string&);
+    static Attr type(const std::string&);
+    static Attr value_(const std::string&);
+    static Attr placeholder(const std::string&);
+    static Attr target(const std::string&);
+    static Attr rel(const std::string&);
+    static Attr for_(const std::string&);
+    static Attr tabindex(int);
+
+    // Boolean attributes
+    static Attr disabled(bool);
+    static Attr checked(bool);
+    static Attr selected(bool);
+    static Attr hidden(bool);
+    static Attr readonly(bool);
+    static Attr required(bool);
+    static Attr multiple(bool);
+    static Attr autofocus(bool);
+
+    // data-* attribute. key can be in either camelCase or kebab-case.
+    static Attr data(const std::string& key, const std::string& val);
+
+    // Any other attribute
+    static Attr custom(const std::string& key, const std::string& val);
+
+    // Any other boolean attribute
+    static Attr custom(const std::string& key, bool);
+
+private:
+    Attr(std::string key, std::optional<std::string> value)
+        : key(std::move(key))
+        , value(std::move(value))
+    {
+    }
+};
+}
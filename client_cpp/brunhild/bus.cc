@@ -0,0 +1,69 @@
+#include "bus.hh"
+#include <vector>
+
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+Bus& bus()
+{
+    static Bus b;
+    return b;
+}
+
+long Bus::add(std::type_index type, string view_id, Erased fn)
+{
+    const long id = id_counter++;
+    subs[type][id] = { view_id, fn };
+    types.emplace(id, type);
+    return id;
+}
+
+void Bus::unsubscribe(long id)
+{
+    auto it = types.find(id);
+    if (it == types.end()) {
+        return;
+    }
+    auto& of_type = subs.at(it->second);
+    of_type.erase(id);
+    if (of_type.empty()) {
+        subs.erase(it->second);
+    }
+    types.erase(it);
+}
+
+void Bus::dispatch(std::type_index type, const void* msg)
+{
+    auto it = subs.find(type);
+    if (it == subs.end()) {
+        return;
+    }
+
+    // Subscribers might subscribe or unsubscribe
+    std::vector<long> ids;
+    ids.reserve(it->second.size());
+    for (auto & [ id, _ ] : it->second) {
+        ids.push_back(id);
+    }
+    for (auto id : ids) {
+        auto t = subs.find(type);
+        if (t == subs.end()) {
+            return;
+        }
+        auto s = t->second.find(id);
+        if (s == t->second.end()) {
+            continue;
+        }
+        const auto sub = s->second;
+        if (sub.fn) {
+            sub.fn(msg);
+        }
+        if (sub.view_id.size()) {
+            mark_dirty(sub.view_id);
+        }
+    }
+}
+}
@@ -0,0 +1,134 @@
+#pragma once
+
+#include "schedule.hh"
+#include "view.hh"
+#include <functional>
+#include <map>
+#include <string>
+#include <typeindex>
+#include <unordered_map>
+
+namespace brunhild {
+
+// Typed publish/subscribe message bus for views, that have no other way to
+// reach each other, like a post view notifying the reply form, that it has
+// been quoted. Messages are any copyable type and subscriptions are keyed by
+// it:
+//
+//     struct Quoted {
+//         unsigned long id;
+//     };
+//
+//     bus().subscribe<Quoted>(form, [&](const Quoted& q) { ... });
+//     bus().publish(Quoted{ 123 });
+//
+// Use the global bus() or separate Bus instances to scope messages to a part
+// of the application.
+class Bus {
+public:
+    Bus() = default;
+    Bus(const Bus&) = delete;
+    Bus& operator=(const Bus&) = delete;
+
+    // Subscribe a view to messages of type M. fn is optional and called with
+    // each published message. The view is then marked dirty and patched on the
+    // next flush, if mounted. Returns subscription ID.
+    template <class M>
+    long subscribe(View& view, std::function<void(const M&)> fn = nullptr)
+    {
+        return add(typeid(M), view.id, wrap(fn));
+    }
+
+    // Subscribe a function not tied to any view to messages of type M.
+    // Returns subscription ID.
+    template <class M> long subscribe(std::function<void(const M&)> fn)
+    {
+        return add(typeid(M), "", wrap(fn));
+    }
+
+    // Remove a subscription by ID
+    void unsubscribe(long id);
+
+    // Publish a message to all subscribers of its type. Subscribed functions
+    // are called synchronously.
+    template <class M> void publish(const M& msg)
+    {
+        dispatch(typeid(M), &msg);
+    }
+
+    // Returns the number of subscriptions to messages of type M
+    template <class M> size_t subscribers() const
+    {
+        auto it = subs.find(typeid(M));
+        return it == subs.end() ? 0 : it->second.size();
+    }
+
+private:
+    typedef std::function<void(const void*)> Erased;
+
+    struct Subscriber {
+        std::string view_id; // Empty, if not tied to a view
+        Erased fn;
+    };
+
+    // Subscribers by message type and subscription ID
+    std::unordered_map<std::type_index, std::map<long, Subscriber>> subs;
+
+    // Message types by subscription ID
+    std::unordered_map<long, std::type_index> types;
+
+    template <class M> static Erased wrap(std::function<void(const M&)> fn)
+    {
+        if (!fn) {
+            return nullptr;
+        }
+        return [fn](const void* msg) { fn(*static_cast<const M*>(msg)); };
+    }
+
+    long add(std::type_index, std::string view_id, Erased fn);
+    void dispatch(std::type_index, const void* msg);
+};
+
+// Returns the global message bus
+Bus& bus();
+
+// Subscribes to a bus for its lifetime, which must not exceed that of the
+// bus. Store as a View member to tie the
+// subscription to the View's lifecycle.
+class Subscription {
+public:
+    template <class M>
+    static Subscription to(
+        Bus& bus, View& view, std::function<void(const M&)> fn = nullptr)
+    {
+        return Subscription(bus, bus.subscribe<M>(view, fn));
+    }
+
+    Subscription(Subscription&& other)
+        : bus(other.bus)
+        , sub_id(other.sub_id)
+    {
+        other.sub_id = -1;
+    }
+
+    ~Subscription()
+    {
+        if (sub_id != -1) {
+            bus.unsubscribe(sub_id);
+        }
+    }
+
+    Subscription(const Subscription&) = delete;
+    Subscription& operator=(const Subscription&) = delete;
+
+private:
+    Bus& bus;
+    long sub_id;
+
+    Subscription(Bus& bus, long id)
+        : bus(bus)
+        , sub_id(id)
+    {
+    }
+};
+}
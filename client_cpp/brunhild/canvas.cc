@@ -0,0 +1,414 @@
+#include "canvas.hh"
+#include "mutations.hh"
+#include "resize.hh"
+#include <emscripten.h>
+#include <stdint.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+// Command opcodes. Must match the executor in Canvas2D::commit().
+enum Op {
+    op_save,
+    op_restore,
+    op_translate,
+    op_scale,
+    op_rotate,
+    op_set_transform,
+    op_fill_style,
+    op_stroke_style,
+    op_line_width,
+    op_global_alpha,
+    op_font,
+    op_text_align,
+    op_text_baseline,
+    op_begin_path,
+    op_close_path,
+    op_move_to,
+    op_line_to,
+    op_quadratic_curve_to,
+    op_bezier_curve_to,
+    op_arc,
+    op_rect,
+    op_fill,
+    op_stroke,
+    op_fill_rect,
+    op_stroke_rect,
+    op_clear_rect,
+    op_fill_text,
+    op_stroke_text,
+    op_draw_image,
+};
+
+Canvas2D& Canvas2D::push(int op, std::initializer_list<double> args)
+{
+    ops.push_back(op);
+    ops.insert(ops.end(), args);
+    return *this;
+}
+
+Canvas2D& Canvas2D::push(
+    int op, const string& s, std::initializer_list<double> args)
+{
+    ops.push_back(op);
+    ops.push_back(strings.size());
+    strings.push_back(s);
+    ops.insert(ops.end(), args);
+    return *this;
+}
+
+Canvas2D& Canvas2D::save() { return push(op_save); }
+Canvas2D& Canvas2D::restore() { return push(op_restore); }
+
+Canvas2D& Canvas2D::translate(double x, double y)
+{
+    return push(op_translate, { x, y });
+}
+
+Canvas2D& Canvas2D::scale(double x, double y)
+{
+    return push(op_scale, { x, y });
+}
+
+Canvas2D& Canvas2D::rotate(double angle) { return push(op_rotate, { angle }); }
+
+Canvas2D& Canvas2D::set_transform(
+    double a, double b, double c, double d, double e, double f)
+{
+    return push(op_set_transform, { a, b, c, d, e, f });
+}
+
+Canvas2D& Canvas2D::set_fill_style(const string& s)
+{
+    return push(op_fill_style, s);
+}
+
+Canvas2D& Canvas2D::set_stroke_style(const string& s)
+{
+    return push(op_stroke_style, s);
+}
+
+Canvas2D& Canvas2D::set_line_width(double w)
+{
+    return push(op_line_width, { w });
+}
+
+Canvas2D& Canvas2D::set_global_alpha(double a)
+{
+    return push(op_global_alpha, { a });
+}
+
+Canvas2D& Canvas2D::set_font(const string& s) { return push(op_font, s); }
+
+Canvas2D& Canvas2D::set_text_align(const string& s)
+{
+    return push(op_text_align, s);
+}
+
+Canvas2D& Canvas2D::set_text_baseline(const string& s)
+{
+    return push(op_text_baseline, s);
+}
+
+Canvas2D& Canvas2D::begin_path() { return push(op_begin_path); }
+Canvas2D& Canvas2D::close_path() { return push(op_close_path); }
+
+Canvas2D& Canvas2D::move_to(double x, double y)
+{
+    return push(op_move_to, { x, y });
+}
+
+Canvas2D& Canvas2D::line_to(double x, double y)
+{
+    return push(op_line_to, { x, y });
+}
+
+Canvas2D& Canvas2D::quadratic_curve_to(
+    double cpx, double cpy, double x, double y)
+{
+    return push(op_quadratic_curve_to, { cpx, cpy, x, y });
+}
+
+Canvas2D& Canvas2D::bezier_curve_to(
+    double cp1x, double cp1y, double cp2x, double cp2y, double x, double y)
+{
+    return push(op_bezier_curve_to, { cp1x, cp1y, cp2x, cp2y, x, y });
+}
+
+Canvas2D& Canvas2D::arc(double x, double y, double radius, double start_angle,
+    double end_angle, bool counterclockwise)
+{
+    return push(op_arc,
+        { x, y, radius, start_angle, end_angle, double(counterclockwise) });
+}
+
+Canvas2D& Canvas2D::rect(double x, double y, double width, double height)
+{
+    return push(op_rect, { x, y, width, height });
+}
+
+Canvas2D& Canvas2D::fill() { return push(op_fill); }
+Canvas2D& Canvas2D::stroke() { return push(op_stroke); }
+
+Canvas2D& Canvas2D::fill_rect(double x, double y, double width, double height)
+{
+    return push(op_fill_rect, { x, y, width, height });
+}
+
+Canvas2D& Canvas2D::stroke_rect(
+    double x, double y, double width, double height)
+{
+    return push(op_stroke_rect, { x, y, width, height });
+}
+
+Canvas2D& Canvas2D::clear_rect(double x, double y, double width, double height)
+{
+    return push(op_clear_rect, { x, y, width, height });
+}
+
+Canvas2D& Canvas2D::fill_text(const string& text, double x, double y)
+{
+    return push(op_fill_text, text, { x, y });
+}
+
+Canvas2D& Canvas2D::stroke_text(const string& text, double x, double y)
+{
+    return push(op_stroke_text, text, { x, y });
+}
+
+Canvas2D& Canvas2D::draw_image(
+    val image, double x, double y, double width, double height)
+{
+    ops.push_back(op_draw_image);
+    ops.push_back(images.size());
+    images.push_back(image);
+    ops.insert(ops.end(), { x, y, width, height });
+    return *this;
+}
+
+void Canvas2D::discard()
+{
+    ops.clear();
+    strings.clear();
+    images.clear();
+}
+
+void Canvas2D::commit()
+{
+    if (ops.empty()) {
+        return;
+    }
+
+    EM_ASM({
+        if (window.__bh_canvas) {
+            return;
+        }
+        window.__bh_canvas = function(id, ptr, len, strs, imgs)
+        {
+            var el = window.__bh_get(id);
+            if (!el) {
+                return;
+            }
+            var c = el.getContext("2d");
+            var a = HEAPF64.subarray(ptr >> 3, (ptr >> 3) + len);
+            var i = 0;
+            var n = function() { return a[i++]; };
+            var s = function() { return strs[a[i++]]; };
+            while (i < len) {
+                switch (n()) {
+                case 0:
+                    c.save();
+                    break;
+                case 1:
+                    c.restore();
+                    break;
+                case 2:
+                    c.translate(n(), n());
+                    break;
+                case 3:
+                    c.scale(n(), n());
+                    break;
+                case 4:
+                    c.rotate(n());
+                    break;
+                case 5:
+                    c.setTransform(n(), n(), n(), n(), n(), n());
+                    break;
+                case 6:
+                    c.fillStyle = s();
+                    break;
+                case 7:
+                    c.strokeStyle = s();
+                    break;
+                case 8:
+                    c.lineWidth = n();
+                    break;
+                case 9:
+                    c.globalAlpha = n();
+                    break;
+                case 10:
+                    c.font = s();
+                    break;
+                case 11:
+                    c.textAlign = s();
+                    break;
+                case 12:
+                    c.textBaseline = s();
+                    break;
+                case 13:
+                    c.beginPath();
+                    break;
+                case 14:
+                    c.closePath();
+                    break;
+                case 15:
+                    c.moveTo(n(), n());
+                    break;
+                case 16:
+                    c.lineTo(n(), n());
+                    break;
+                case 17:
+                    c.quadraticCurveTo(n(), n(), n(), n());
+                    break;
+                case 18:
+                    c.bezierCurveTo(n(), n(), n(), n(), n(), n());
+                    break;
+                case 19:
+                    c.arc(n(), n(), n(), n(), n(), !!n());
+                    break;
+                case 20:
+                    c.rect(n(), n(), n(), n());
+                    break;
+                case 21:
+                    c.fill();
+                    break;
+                case 22:
+                    c.stroke();
+                    break;
+                case 23:
+                    c.fillRect(n(), n(), n(), n());
+                    break;
+                case 24:
+                    c.strokeRect(n(), n(), n(), n());
+                    break;
+                case 25:
+                    c.clearRect(n(), n(), n(), n());
+                    break;
+                case 26:
+                    c.fillText(s(), n(), n());
+                    break;
+                case 27:
+                    c.strokeText(s(), n(), n());
+                    break;
+                case 28:
+                    c.drawImage(imgs[n()], n(), n(), n(), n());
+                    break;
+                }
+            }
+        };
+    });
+
+    val::global("__bh_canvas")(id, reinterpret_cast<uintptr_t>(ops.data()),
+        ops.size(), val::array(strings), val::array(images));
+    discard();
+}
+
+CanvasBase::CanvasBase(string id)
+    : VirtualView(id)
+{
+}
+
+CanvasBase::~CanvasBase() = default;
+
+double CanvasBase::pixel_ratio()
+{
+    return EM_ASM_DOUBLE({ return window.devicePixelRatio || 1; });
+}
+
+Node CanvasBase::render()
+{
+    auto attrs = this->attrs();
+    const double dpr = pixel_ratio();
+    attrs["width"] = std::to_string(long(css_size.width * dpr));
+    attrs["height"] = std::to_string(long(css_size.height * dpr));
+    if (!fitted) {
+        attrs["style"] = "width:" + std::to_string(css_size.width)
+            + "px;height:" + std::to_string(css_size.height) + "px";
+    }
+    return Node("canvas", attrs);
+}
+
+void CanvasBase::patch()
+{
+    VirtualView::patch();
+    redraw();
+}
+
+void CanvasBase::resize(Size s)
+{
+    if (s.width == css_size.width && s.height == css_size.height) {
+        return;
+    }
+    css_size = s;
+    if (is_mounted()) {
+        patch();
+    }
+}
+
+void CanvasBase::fit()
+{
+    if (fitted) {
+        return;
+    }
+    fitted = true;
+    observer = std::make_unique<Resize>(id, [this](Size s) { resize(s); });
+    if (is_mounted()) {
+        patch();
+    }
+}
+
+CanvasView::CanvasView(string id)
+    : CanvasBase(id)
+    , ctx(id)
+{
+}
+
+CanvasView::~CanvasView() { *alive = false; }
+
+void CanvasView::redraw()
+{
+    if (redraw_pending) {
+        return;
+    }
+    redraw_pending = true;
+    defer([this, alive = alive]() {
+        if (*alive) {
+            redraw_pending = false;
+            draw_now();
+        }
+    });
+}
+
+void CanvasView::mount()
+{
+    VirtualView::mount();
+    draw_now();
+}
+
+void CanvasView::draw_now()
+{
+    if (!is_mounted()) {
+        return;
+    }
+    ctx.discard();
+    const double dpr = pixel_ratio();
+    const auto s = size();
+    ctx.set_transform(1, 0, 0, 1, 0, 0)
+        .clear_rect(0, 0, s.width * dpr, s.height * dpr)
+        .set_transform(dpr, 0, 0, dpr, 0, 0);
+    draw(ctx);
+    ctx.commit();
+}
+}
@@ -0,0 +1,162 @@
+#pragma once
+
+#include "ref.hh"
+#include "view.hh"
+#include <emscripten/val.h>
+#include <memory>
+#include <optional>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Buffer of CanvasRenderingContext2D commands for a <canvas> element.
+// Commands are buffered and executed in a single call into JS on commit().
+// Methods mirror the CanvasRenderingContext2D methods and properties of the
+// same name and return the buffer for chaining.
+class Canvas2D {
+public:
+    // ID of the <canvas> element
+    const std::string id;
+
+    Canvas2D(std::string id)
+        : id(id)
+    {
+    }
+
+    Canvas2D& save();
+    Canvas2D& restore();
+
+    Canvas2D& translate(double x, double y);
+    Canvas2D& scale(double x, double y);
+    Canvas2D& rotate(double angle);
+    Canvas2D& set_transform(
+        double a, double b, double c, double d, double e, double f);
+
+    Canvas2D& set_fill_style(const std::string&);
+    Canvas2D& set_stroke_style(const std::string&);
+    Canvas2D& set_line_width(double);
+    Canvas2D& set_global_alpha(double);
+    Canvas2D& set_font(const std::string&);
+    Canvas2D& set_text_align(const std::string&);
+    Canvas2D& set_text_baseline(const std::string&);
+
+    Canvas2D& begin_path();
+    Canvas2D& close_path();
+    Canvas2D& move_to(double x, double y);
+    Canvas2D& line_to(double x, double y);
+    Canvas2D& quadratic_curve_to(double cpx, double cpy, double x, double y);
+    Canvas2D& bezier_curve_to(double cp1x, double cp1y, double cp2x,
+        double cp2y, double x, double y);
+    Canvas2D& arc(double x, double y, double radius, double start_angle,
+        double end_angle, bool counterclockwise = false);
+    Canvas2D& rect(double x, double y, double width, double height);
+    Canvas2D& fill();
+    Canvas2D& stroke();
+
+    Canvas2D& fill_rect(double x, double y, double width, double height);
+    Canvas2D& stroke_rect(double x, double y, double width, double height);
+    Canvas2D& clear_rect(double x, double y, double width, double height);
+
+    Canvas2D& fill_text(const std::string& text, double x, double y);
+    Canvas2D& stroke_text(const std::string& text, double x, double y);
+
+    // Draw an image, canvas or ImageBitmap scaled to the destination
+    // rectangle
+    Canvas2D& draw_image(emscripten::val image, double x, double y,
+        double width, double height);
+
+    // Execute all buffered commands. No-op, if the element does not exist.
+    void commit();
+
+    // Discard all buffered commands
+    void discard();
+
+private:
+    // Opcodes and numeric arguments of buffered commands
+    std::vector<double> ops;
+
+    // String and image arguments referenced by index from ops
+    std::vector<std::string> strings;
+    std::vector<emscripten::val> images;
+
+    Canvas2D& push(int op, std::initializer_list<double> args = {});
+    Canvas2D& push(int op, const std::string& s,
+        std::initializer_list<double> args = {});
+};
+
+class Resize;
+
+// Base class of views with a <canvas> root element. The backing store is
+// scaled by window.devicePixelRatio and the element sized in CSS pixels.
+class CanvasBase : public VirtualView {
+public:
+    ~CanvasBase();
+
+    Node render();
+
+    // Patch the attributes of the root element and redraw
+    void patch();
+
+    // Set the size of the canvas in CSS pixels and redraw
+    void resize(Size);
+
+    // Resize the canvas to the CSS content box size of its element, whenever
+    // that changes. The element must be sized by CSS.
+    void fit();
+
+    // Returns the size of the canvas in CSS pixels
+    Size size() const { return css_size; }
+
+    // Redraw the canvas contents
+    virtual void redraw() = 0;
+
+protected:
+    CanvasBase(std::string id);
+
+    // Returns the attributes of the root element. Any width and height are
+    // overridden.
+    virtual Attrs attrs() { return {}; }
+
+    // Returns the current ratio of physical to CSS pixels
+    static double pixel_ratio();
+
+private:
+    Size css_size = { 300, 150 };
+
+    // Size is set by CSS and observed
+    bool fitted = false;
+
+    std::unique_ptr<Resize> observer;
+};
+
+// View with a <canvas> root element drawn to with Canvas2D.
+// Drawing coordinates are always in CSS pixels.
+class CanvasView : public CanvasBase {
+public:
+    CanvasView(std::string id = new_id());
+    ~CanvasView();
+
+    // Redraw the canvas after the next flush of DOM mutations
+    void redraw();
+
+protected:
+    // Draw the canvas contents. The canvas is cleared and the transform reset
+    // before each call. Commands are committed after draw() returns.
+    virtual void draw(Canvas2D&) = 0;
+
+    void mount();
+
+private:
+    // Redraw is already scheduled
+    bool redraw_pending = false;
+
+    Canvas2D ctx;
+
+    // Guards deferred functions against running after destruction
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Clear the canvas and call draw()
+    void draw_now();
+};
+}
@@ -0,0 +1,130 @@
+#include "clipboard.hh"
+#include "promise.hh"
+#include <emscripten.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::clipboard {
+
+// Returns the JS clipboard helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_clipboard) {
+            return;
+        }
+
+        // Copy through a temporary selection for older browsers and
+        // insecure contexts
+        var exec = function(text, html)
+        {
+            var copy = function(e)
+            {
+                e.clipboardData.setData("text/plain", text);
+                if (html) {
+                    e.clipboardData.setData("text/html", html);
+                }
+                e.preventDefault();
+            };
+            document.addEventListener("copy", copy);
+            try {
+                if (!document.execCommand("copy")) {
+                    throw new Error("execCommand copy failed");
+                }
+            } finally {
+                document.removeEventListener("copy", copy);
+            }
+        };
+
+        var cb = navigator.clipboard;
+        window.__bh_clipboard = {
+            write_text : function(text)
+            {
+                if (cb && cb.writeText) {
+                    return cb.writeText(text);
+                }
+                return new Promise(function(resolve) {
+                    exec(text, null);
+                    resolve();
+                });
+            },
+            write_html : function(html, text)
+            {
+                if (cb && cb.write && window.ClipboardItem) {
+                    return cb.write([new ClipboardItem({
+                        "text/html" : new Blob([html], { type : "text/html" }),
+                        "text/plain" : new Blob([text], { type : "text/plain" })
+                    })]);
+                }
+                return new Promise(function(resolve) {
+                    exec(text, html);
+                    resolve();
+                });
+            },
+            read_text : function()
+            {
+                if (cb && cb.readText) {
+                    return cb.readText();
+                }
+                return Promise.reject(new Error("clipboard not available"));
+            }
+        };
+    });
+    return val::global("__bh_clipboard");
+}
+
+static Settle on_written(WriteCallback cb)
+{
+    return [cb](bool ok, val) {
+        if (cb) {
+            cb(ok);
+        }
+    };
+}
+
+void write_text(const string& text, WriteCallback cb)
+{
+    await(helpers().call<val>("write_text", text), on_written(cb));
+}
+
+void write_html(const string& html, const string& text, WriteCallback cb)
+{
+    await(helpers().call<val>("write_html", html, text), on_written(cb));
+}
+
+void read_text(ReadCallback cb)
+{
+    await(helpers().call<val>("read_text"), [cb](bool ok, val v) {
+        if (ok) {
+            cb(v.as<string>());
+        } else {
+            cb(std::nullopt);
+        }
+    });
+}
+
+PasteData paste_data(val& event)
+{
+    PasteData d;
+    const auto dt = event["clipboardData"];
+    if (dt.isNull() || dt.isUndefined()) {
+        return d;
+    }
+    d.text = dt.call<string>("getData", string("text/plain"));
+    d.html = dt.call<string>("getData", string("text/html"));
+    d.files = files_from(dt);
+    return d;
+}
+
+long on_paste(std::function<void(PasteData&)> handler, string selector)
+{
+    return register_handler(
+        "paste",
+        [handler](val& event) {
+            auto d = paste_data(event);
+            handler(d);
+        },
+        selector);
+}
+}
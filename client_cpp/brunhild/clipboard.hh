@@ -0,0 +1,50 @@
+#pragma once
+
+#include "events.hh"
+#include "file.hh"
+#include <functional>
+#include <optional>
+#include <string>
+#include <vector>
+
+// Asynchronous clipboard access. Falls back to document.execCommand() for
+// writing, where the Clipboard API is not available, like on pages not served
+// over HTTPS. Writing and reading usually requires a user gesture, so call
+// these from event handlers.
+namespace brunhild::clipboard {
+
+// Receives, if the operation succeeded
+typedef std::function<void(bool)> WriteCallback;
+
+// Receives the read text or std::nullopt on failure
+typedef std::function<void(std::optional<std::string>)> ReadCallback;
+
+// Write plain text to the clipboard
+void write_text(const std::string& text, WriteCallback cb = nullptr);
+
+// Write HTML to the clipboard together with a plain text alternative for
+// targets not accepting HTML
+void write_html(const std::string& html, const std::string& text,
+    WriteCallback cb = nullptr);
+
+// Read plain text from the clipboard. Has no fallback and fails, where the
+// Clipboard API is not available or permission is denied.
+void read_text(ReadCallback cb);
+
+// Contents of a paste event's clipboardData
+struct PasteData {
+    // Pasted plain text and HTML. Empty, if not present.
+    std::string text, html;
+
+    // Pasted files, including images like screenshots
+    std::vector<File> files;
+};
+
+// Extract pasted data from a paste event
+PasteData paste_data(emscripten::val& event);
+
+// Register a persistent handler for paste events on elements matching
+// selector. Returns handler ID for unregister_handler().
+long on_paste(
+    std::function<void(PasteData&)> handler, std::string selector = "");
+}
@@ -0,0 +1,92 @@
+#include "code_block.hh"
+#include <emscripten.h>
+
+using std::string;
+
+namespace brunhild {
+
+static Highlighter highlighter;
+
+static string js_highlighter;
+
+void set_highlighter(Highlighter fn) { highlighter = fn; }
+
+void set_js_highlighter(const string& fn) { js_highlighter = fn; }
+
+Node CodeBlock::render()
+{
+    if (!highlighted) {
+        Attrs attrs = { { "id", id + "-code" } };
+        if (lang.size()) {
+            attrs["class"] = "language-" + lang;
+            attrs.set_data("lang", lang);
+        }
+        std::optional<std::vector<Node>> children;
+        if (highlighter) {
+            children = highlighter(code, lang);
+        }
+        if (children) {
+            highlighted = Node("code", attrs, std::move(*children));
+        } else {
+            highlighted = Node("code", attrs, code, true);
+        }
+    }
+    return Node("pre", { { "class", "bh-code" } }, { *highlighted });
+}
+
+void CodeBlock::set(string code, string lang)
+{
+    if (code == this->code && lang == this->lang) {
+        return;
+    }
+    this->code = std::move(code);
+    this->lang = std::move(lang);
+    highlighted = std::nullopt;
+    if (!is_mounted()) {
+        return;
+    }
+    patch();
+
+    // Highlight, after the new code has been written to the DOM
+    if (js_highlighter.size()) {
+        defer([this, alive = alive]() {
+            if (*alive && is_mounted()) {
+                run_js_highlighter();
+            }
+        });
+    }
+}
+
+void CodeBlock::mount()
+{
+    VirtualView::mount();
+    run_js_highlighter();
+}
+
+void CodeBlock::run_js_highlighter()
+{
+    if (js_highlighter.empty()) {
+        return;
+    }
+    EM_ASM_INT(
+        {
+            var el = window.__bh_get(UTF8ToString($0));
+            var path = UTF8ToString($1).split(".");
+            var ctx = window;
+            var fn = window;
+            for (var i = 0; i < path.length && fn; i++) {
+                ctx = fn;
+                fn = fn[path[i]];
+            }
+            if (!el || typeof fn != "function") {
+                return;
+            }
+            try {
+                fn.call(ctx, el);
+            } catch (e) {
+                console.error(e);
+            }
+        },
+        (id + "-code").c_str(), js_highlighter.c_str());
+}
+}
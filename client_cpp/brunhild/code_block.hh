@@ -0,0 +1,69 @@
+#pragma once
+
+#include "view.hh"
+#include <functional>
+#include <memory>
+#include <optional>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Highlights code of a language. Returns the highlighted children of the
+// <code> element or std::nullopt, if the language is not supported.
+typedef std::function<std::optional<std::vector<Node>>(
+    const std::string& code, const std::string& lang)>
+    Highlighter;
+
+// Set the highlighter run by CodeBlock views on render
+void set_highlighter(Highlighter);
+
+// Set a global JS function to run on the <code> element of CodeBlock views,
+// after it is mounted or its code changes, like "hljs.highlightElement".
+// Dot-separated paths are resolved from window. Empty to unset.
+// As the JS highlighter mutates the DOM directly, CodeBlock views only
+// rerender their <code> element, when their code or language changes.
+void set_js_highlighter(const std::string& fn);
+
+// Renders escaped code in a <pre><code> block with the language set as a
+// "language-*" class and a data-lang attribute. The code is highlighted by the
+// highlighters set with set_highlighter() and set_js_highlighter(), if any.
+// Highlighting is only rerun, when the code or language changes.
+class CodeBlock : public VirtualView {
+public:
+    CodeBlock(
+        std::string code, std::string lang = "", std::string id = new_id())
+        : VirtualView(id)
+        , code(code)
+        , lang(lang)
+    {
+    }
+
+    ~CodeBlock() { *alive = false; }
+
+    Node render();
+
+    // Set the code and language and patch the view, if either changed
+    void set(std::string code, std::string lang);
+
+    // Set the code and patch the view, if it changed
+    void set_code(std::string code) { set(code, lang); }
+
+    const std::string& get_code() const { return code; }
+    const std::string& get_lang() const { return lang; }
+
+protected:
+    void mount();
+
+private:
+    std::string code, lang;
+
+    // Cached highlighted <code> element
+    std::optional<Node> highlighted;
+
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Run the JS highlighter, if any
+    void run_js_highlighter();
+};
+}
@@ -0,0 +1,127 @@
+#pragma once
+
+#include "node.hh"
+#include <functional>
+#include <initializer_list>
+#include <optional>
+#include <string>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+// Helpers for control flow in rendering children. Each returns a vector of
+// Nodes, that can be combined with concat():
+//
+//     Node n("ul", {},
+//         concat(when(loading, [] { return Node("li", "Loading..."); }),
+//             keyed_map("post", posts,
+//                 [](auto& p) { return std::pair(p.id, render_post(p)); })));
+namespace brunhild {
+
+namespace detail {
+    inline void append_children(std::vector<Node>& out, Node&& n)
+    {
+        out.push_back(std::move(n));
+    }
+
+    inline void append_children(std::vector<Node>& out, const Node& n)
+    {
+        out.push_back(n);
+    }
+
+    inline void append_children(std::vector<Node>& out, std::vector<Node>&& v)
+    {
+        out.reserve(out.size() + v.size());
+        for (auto& n : v) {
+            out.push_back(std::move(n));
+        }
+    }
+
+    inline void append_children(std::vector<Node>& out, std::optional<Node>&& n)
+    {
+        if (n) {
+            out.push_back(std::move(*n));
+        }
+    }
+
+    // Convert the result of a render function to children
+    template <class T> std::vector<Node> to_children(T&& v)
+    {
+        std::vector<Node> out;
+        append_children(out, std::forward<T>(v));
+        return out;
+    }
+
+    inline std::string key_string(const std::string& k) { return k; }
+    inline std::string key_string(const char* k) { return k; }
+
+    template <class T> std::string key_string(const T& k)
+    {
+        return std::to_string(k);
+    }
+}
+
+// Concatenate Nodes, vectors of Nodes and optional Nodes into children
+template <class... Parts> std::vector<Node> concat(Parts&&... parts)
+{
+    std::vector<Node> out;
+    (detail::append_children(out, std::forward<Parts>(parts)), ...);
+    return out;
+}
+
+// Returns the Node or Nodes rendered by fn, if cond is true, or no children
+template <class F> std::vector<Node> when(bool cond, F fn)
+{
+    if (!cond) {
+        return {};
+    }
+    return detail::to_children(fn());
+}
+
+// Same as when(), but renders otherwise, if cond is false
+template <class F, class G>
+std::vector<Node> when(bool cond, F fn, G otherwise)
+{
+    return cond ? detail::to_children(fn()) : detail::to_children(otherwise());
+}
+
+// Render a Node for each item of a range. fn returns a pair of the item's key
+// and Node. The key must be unique in the range and is used to derive a
+// stable element ID from prefix, which must be unique in the document.
+//
+// Nodes with the same key at the same position are patched, while nodes with
+// a different key are replaced, so the element state of an item, like focus
+// or an input value, is never transferred to another item on change.
+template <class R, class F>
+std::vector<Node> keyed_map(const std::string& prefix, const R& range, F fn)
+{
+    std::vector<Node> out;
+    for (auto& item : range) {
+        auto[key, node] = fn(item);
+        node.attrs["id"] = prefix + '-' + detail::key_string(key);
+        out.push_back(std::move(node));
+    }
+    return out;
+}
+
+// Case of switch_on()
+template <class T> using Case = std::pair<T, std::function<Node()>>;
+
+// Render the case matching value or fallback, if none does. fallback is
+// optional and renders no children, if unset.
+template <class T>
+std::vector<Node> switch_on(const T& value,
+    std::initializer_list<Case<T>> cases,
+    std::function<Node()> fallback = nullptr)
+{
+    for (auto& c : cases) {
+        if (c.first == value) {
+            return { c.second() };
+        }
+    }
+    if (fallback) {
+        return { fallback() };
+    }
+    return {};
+}
+}
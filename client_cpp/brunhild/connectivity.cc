@@ -0,0 +1,52 @@
+#include "connectivity.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// Registered handlers by ID
+static std::map<long, std::function<void(bool)>> handlers;
+
+bool is_online()
+{
+    return (bool)EM_ASM_INT({ return navigator.onLine ? 1 : 0; });
+}
+
+long on_connectivity_change(std::function<void(bool)> handler)
+{
+    if (handlers.empty()) {
+        EM_ASM({
+            if (window.__bh_connectivity) {
+                return;
+            }
+            window.__bh_connectivity = function(e)
+            {
+                Module._bh_connectivity_changed(e.type == "online");
+            };
+            window.addEventListener("online", window.__bh_connectivity);
+            window.addEventListener("offline", window.__bh_connectivity);
+        });
+    }
+    const long id = id_counter++;
+    handlers[id] = handler;
+    return id;
+}
+
+void remove_connectivity_handler(long id) { handlers.erase(id); }
+
+static void connectivity_changed(bool online)
+{
+    auto copy = handlers; // Handler might invalidate this iterator
+    for (auto & [ _, h ] : copy) {
+        h(online);
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_connectivity)
+{
+    emscripten::function("_bh_connectivity_changed", &connectivity_changed);
+}
+}
@@ -0,0 +1,18 @@
+#pragma once
+
+#include <functional>
+
+// Network connectivity detection through navigator.onLine and the "online" and
+// "offline" window events
+namespace brunhild {
+
+// Returns, if the browser considers itself online
+bool is_online();
+
+// Register a handler called with the new connectivity state, whenever the
+// browser goes online or offline. Returns handler ID.
+long on_connectivity_change(std::function<void(bool)> handler);
+
+// Remove a connectivity change handler by ID
+void remove_connectivity_handler(long id);
+}
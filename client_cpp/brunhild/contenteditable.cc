@@ -0,0 +1,126 @@
+#include "contenteditable.hh"
+#include "events.hh"
+#include "mutations.hh"
+#include "perf.hh"
+#include <emscripten.h>
+
+using std::string;
+
+namespace brunhild {
+
+ContentEditable::ContentEditable(string tag, string id)
+    : VirtualView(id)
+    , tag(tag)
+    , input_handler(register_handler("input",
+          [this](emscripten::val& event) {
+              auto html = event["target"]["innerHTML"].as<string>();
+              saved.inner_html = html;
+              on_input(html);
+          },
+          '#' + id))
+{
+}
+
+ContentEditable::~ContentEditable() { unregister_handler(input_handler); }
+
+Node ContentEditable::render()
+{
+    auto attrs = this->attrs();
+    attrs["contenteditable"] = "true";
+    return Node(tag, attrs, content());
+}
+
+void ContentEditable::patch()
+{
+    perf::Measure m("diff", id);
+    auto node = render_root(&saved);
+    perf::Sample s(perf::Phase::diff, typeid(*this));
+    saved.attrs.patch(std::move(node.attrs));
+    saved.patch_props(std::move(node.props));
+    if (saved.inner_html != node.inner_html) {
+        saved.inner_html = node.inner_html;
+        replace_content(*node.inner_html);
+    }
+}
+
+string ContentEditable::text()
+{
+    auto el = View::el();
+    if (el.isNull()) {
+        return "";
+    }
+    return el["textContent"].as<string>();
+}
+
+void ContentEditable::replace_content(const string& html)
+{
+    // Store the selection as offsets into the text content, as the text
+    // nodes it points to are about to be replaced
+    const bool focused = EM_ASM_INT(
+        {
+            var el = window.__bh_get(UTF8ToString($0));
+            var sel = window.getSelection();
+            if (!el || !sel.rangeCount || !el.contains(sel.anchorNode)) {
+                return 0;
+            }
+            var offset = function(node, off)
+            {
+                var r = document.createRange();
+                r.selectNodeContents(el);
+                r.setEnd(node, off);
+                return r.toString().length;
+            };
+            var r = sel.getRangeAt(0);
+            if (!window.__bh_carets) {
+                window.__bh_carets = {};
+            }
+            window.__bh_carets[el.id] = [
+                offset(r.startContainer, r.startOffset),
+                offset(r.endContainer, r.endOffset)
+            ];
+            return 1;
+        },
+        id.c_str());
+
+    set_inner_html(id, html);
+    if (!focused) {
+        return;
+    }
+    defer([id = id]() {
+        EM_ASM_INT(
+            {
+                var id = UTF8ToString($0);
+                var el = window.__bh_get(id);
+                var c = window.__bh_carets[id];
+                delete window.__bh_carets[id];
+                if (!el || !c) {
+                    return;
+                }
+
+                // Find the text node and offset of a text content offset
+                var locate = function(target)
+                {
+                    var w = document.createTreeWalker(el, NodeFilter.SHOW_TEXT);
+                    var pos = 0;
+                    var n;
+                    while ((n = w.nextNode())) {
+                        if (pos + n.length >= target) {
+                            return [ n, target - pos ];
+                        }
+                        pos += n.length;
+                    }
+                    return [ el, el.childNodes.length ];
+                };
+                var start = locate(c[0]);
+                var end = locate(c[1]);
+                var r = document.createRange();
+                r.setStart(start[0], start[1]);
+                r.setEnd(end[0], end[1]);
+                var sel = window.getSelection();
+                sel.removeAllRanges();
+                sel.addRange(r);
+            },
+            id.c_str());
+    });
+}
+}
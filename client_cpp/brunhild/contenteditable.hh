@@ -0,0 +1,50 @@
+#pragma once
+
+#include "view.hh"
+#include <string>
+
+namespace brunhild {
+
+// View rendering an element with the contenteditable attribute. The inner
+// HTML is owned by the user while editing: edits are passed to on_input() to
+// be stored in application state and the element's contents are only replaced
+// by patch(), if content() differs from what the user has typed. The caret
+// and selection are preserved across such replacements.
+// Do not call watch_foreign_mutations() on this view, as user edits would be
+// reconciled as foreign mutations.
+class ContentEditable : public VirtualView {
+public:
+    // Tag of root node
+    const std::string tag;
+
+    ContentEditable(std::string tag = "div", std::string id = new_id());
+    ~ContentEditable();
+
+    Node render();
+
+    // Patch the attributes of the root element and replace its contents, if
+    // content() no longer matches the DOM
+    void patch();
+
+    // Returns the current plain text contents of the element
+    std::string text();
+
+protected:
+    // Returns the attributes of the root element. The contenteditable
+    // attribute is always set.
+    virtual Attrs attrs() { return {}; }
+
+    // Returns the inner HTML of the element from application state
+    virtual std::string content() = 0;
+
+    // Called with the new inner HTML of the element after each user edit
+    virtual void on_input(const std::string& html) = 0;
+
+private:
+    // ID of the input event handler
+    const long input_handler;
+
+    // Replace the inner HTML of the element, preserving the caret
+    void replace_content(const std::string& html);
+};
+}
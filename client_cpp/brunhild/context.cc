@@ -0,0 +1,86 @@
+#include "context.hh"
+#include <unordered_map>
+#include <vector>
+
+namespace brunhild {
+
+// Providers in the current render by value type, innermost last
+static std::unordered_map<std::type_index, std::vector<ContextBase*>> stacks;
+
+// Providers each view is subscribed to
+static std::unordered_map<View*, std::unordered_set<ContextBase*>>
+    subscriptions;
+
+ContextBase::~ContextBase()
+{
+    for (auto v : consumers) {
+        if (subscriptions.count(v)) {
+            auto& subs = subscriptions.at(v);
+            subs.erase(this);
+            if (subs.empty()) {
+                subscriptions.erase(v);
+            }
+        }
+    }
+}
+
+void ContextBase::push(std::type_index t) { stacks[t].push_back(this); }
+
+void ContextBase::pop(std::type_index t)
+{
+    auto& stack = stacks.at(t);
+    stack.pop_back();
+    if (stack.empty()) {
+        stacks.erase(t);
+    }
+}
+
+void ContextBase::subscribe(View* v)
+{
+    consumers.insert(v);
+    subscriptions[v].insert(this);
+}
+
+void ContextBase::patch_consumers()
+{
+    // Patching may create or destroy consumers
+    auto copy = consumers;
+    for (auto v : copy) {
+        if (consumers.count(v) && v->is_mounted()) {
+            v->patch();
+        }
+    }
+}
+
+ContextBase* ContextBase::current(std::type_index t)
+{
+    if (!stacks.count(t)) {
+        return nullptr;
+    }
+    return stacks.at(t).back();
+}
+
+ContextBase* ContextBase::subscribed(View* v, std::type_index t)
+{
+    if (!subscriptions.count(v)) {
+        return nullptr;
+    }
+    for (auto p : subscriptions.at(v)) {
+        if (p->type() == t) {
+            return p;
+        }
+    }
+    return nullptr;
+}
+
+void forget_consumer(View* v)
+{
+    if (!subscriptions.count(v)) {
+        return;
+    }
+    for (auto p : subscriptions.at(v)) {
+        p->consumers.erase(v);
+    }
+    subscriptions.erase(v);
+}
+}
@@ -0,0 +1,147 @@
+#pragma once
+
+#include "view.hh"
+#include <typeindex>
+#include <unordered_set>
+#include <utility>
+
+namespace brunhild {
+
+// Type-erased base of all context providers. Do not use directly.
+class ContextBase {
+public:
+    virtual ~ContextBase();
+
+protected:
+    // Views, that read the provided value
+    std::unordered_set<View*> consumers;
+
+    // Push the provider onto the stack of its type for the duration of a
+    // render
+    void push(std::type_index);
+
+    // Pop the provider from the stack of its type
+    void pop(std::type_index);
+
+    // Pushes a provider onto the stack of its type and pops it on destruction,
+    // even if the render throws
+    class Scope {
+    public:
+        Scope(ContextBase& provider, std::type_index type)
+            : provider(provider)
+            , type(type)
+        {
+            provider.push(type);
+        }
+
+        Scope(const Scope&) = delete;
+        Scope& operator=(const Scope&) = delete;
+
+        ~Scope() { provider.pop(type); }
+
+    private:
+        ContextBase& provider;
+        const std::type_index type;
+    };
+
+    // Subscribe a view to changes of the provided value
+    void subscribe(View*);
+
+    // Patch all mounted consumers
+    void patch_consumers();
+
+    // Returns the innermost provider of a type in the current render or NULL
+    static ContextBase* current(std::type_index);
+
+    // Returns a provider of a type the view has previously subscribed to or
+    // NULL
+    static ContextBase* subscribed(View*, std::type_index);
+
+    // Returns the type of the provided value
+    virtual std::type_index type() const = 0;
+
+    friend void forget_consumer(View*);
+};
+
+// Provides a typed value to all views rendered inside the subtree of the
+// providing view, without passing it through every child view constructor.
+template <class T> class Provider : public ContextBase {
+public:
+    Provider(T value = T())
+        : value(std::move(value))
+    {
+    }
+
+    // Returns the provided value
+    const T& get() const { return value; }
+
+    // Set a new value and patch all mounted consumers
+    void set(T val)
+    {
+        value = std::move(val);
+        scope([this]() { patch_consumers(); });
+    }
+
+    // Run fn with the value provided to all views rendered during its
+    // execution
+    template <class F> void scope(F fn)
+    {
+        Scope s(*this, type());
+        fn();
+    }
+
+    // Read the provided value from the innermost provider enclosing the
+    // current render and subscribe the view to its changes. Outside a render
+    // of the provider's subtree, returns the value of the provider the view
+    // has previously subscribed to. Returns NULL, if none.
+    static const T* use(View* consumer)
+    {
+        auto p = current(typeid(T));
+        if (p) {
+            static_cast<Provider<T>*>(p)->subscribe(consumer);
+        } else {
+            p = subscribed(consumer, typeid(T));
+        }
+        if (!p) {
+            return nullptr;
+        }
+        return &static_cast<Provider<T>*>(p)->value;
+    }
+
+protected:
+    std::type_index type() const { return typeid(T); }
+
+private:
+    T value;
+};
+
+// Shorthand for Provider<T>::use()
+template <class T> inline const T* use_context(View* consumer)
+{
+    return Provider<T>::use(consumer);
+}
+
+// Unsubscribe a view from all providers. Called on view destruction.
+void forget_consumer(View*);
+
+// Adapter, that provides a context value of type T to all views rendered
+// inside the subtree of view V
+template <class T, class V> class Providing : public V {
+public:
+    using V::V;
+
+    void write_html(Rope& s)
+    {
+        context.scope([&]() { V::write_html(s); });
+    }
+
+    void patch()
+    {
+        context.scope([this]() { V::patch(); });
+    }
+
+protected:
+    // Value provided to the subtree
+    Provider<T> context;
+};
+}
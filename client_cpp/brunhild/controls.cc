@@ -0,0 +1,47 @@
+#include "controls.hh"
+#include <stdlib.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+Node checkbox(bool checked, Attrs attrs)
+{
+    attrs["type"] = "checkbox";
+    if (checked) {
+        attrs["checked"] = "";
+    } else {
+        attrs.erase("checked");
+    }
+    Node n("input", attrs);
+    n.props["checked"] = val(checked);
+    return n;
+}
+
+bool event_checked(val& event) { return event["target"]["checked"].as<bool>(); }
+
+namespace detail {
+    long parse_index(const string& s, size_t size)
+    {
+        char* end;
+        const long i = strtol(s.c_str(), &end, 10);
+        if (s.empty() || *end || i < 0 || size_t(i) >= size) {
+            return -1;
+        }
+        return i;
+    }
+
+    std::vector<string> selected_values(val el)
+    {
+        auto opts = el["selectedOptions"];
+        const auto len = opts["length"].as<size_t>();
+        std::vector<string> values;
+        values.reserve(len);
+        for (size_t i = 0; i < len; i++) {
+            values.push_back(opts[i]["value"].as<string>());
+        }
+        return values;
+    }
+}
+}
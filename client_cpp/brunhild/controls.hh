@@ -0,0 +1,176 @@
+#pragma once
+
+#include "node.hh"
+#include <emscripten/val.h>
+#include <optional>
+#include <set>
+#include <string>
+#include <vector>
+
+// Controlled <select>, checkbox and radio inputs. The checked and selected
+// state is set through Node properties, so it is only written to the DOM,
+// when the rendered state changes, and never resets user input between
+// renders of an unchanged state. Update the state from change event handlers
+// with the readers below.
+//
+// Options of <select> and radio groups are backed by arbitrary values, like
+// enum members. Their value attributes are indexes into the option list.
+namespace brunhild {
+
+// Option of a <select> or radio group
+template <class T> struct Choice {
+    T value;
+    std::string label;
+    bool disabled = false;
+};
+
+template <class T> using Choices = std::vector<Choice<T>>;
+
+// Checkbox checked, if checked is true
+Node checkbox(bool checked, Attrs attrs = {});
+
+// Checkbox checked, if value is in set
+template <class T>
+Node checkbox(const std::set<T>& set, const T& value, Attrs attrs = {})
+{
+    return checkbox(set.count(value), attrs);
+}
+
+// Returns, if the target of a change or input event is checked
+bool event_checked(emscripten::val& event);
+
+// Add or remove value from set according to the checked state of the target
+// of a change event. Returns, if checked.
+template <class T>
+bool toggle_in_set(emscripten::val& event, std::set<T>& set, const T& value)
+{
+    const bool checked = event_checked(event);
+    if (checked) {
+        set.insert(value);
+    } else {
+        set.erase(value);
+    }
+    return checked;
+}
+
+namespace detail {
+    // Render an <option> for each choice. selected is called with each index
+    // and returns, if the option is selected.
+    template <class T, class F>
+    std::vector<Node> options(const Choices<T>& choices, F selected)
+    {
+        std::vector<Node> opts;
+        opts.reserve(choices.size());
+        for (size_t i = 0; i < choices.size(); i++) {
+            Attrs a = { { "value", std::to_string(i) } };
+            if (choices[i].disabled) {
+                a["disabled"] = "";
+            }
+            Node n("option", a, choices[i].label, true);
+            const bool sel = selected(i);
+            if (sel) {
+                n.attrs["selected"] = "";
+            }
+            n.props["selected"] = emscripten::val(sel);
+            opts.push_back(std::move(n));
+        }
+        return opts;
+    }
+
+    // Returns the index of a choice with the value or -1
+    template <class T> long index_of(const Choices<T>& choices, const T& value)
+    {
+        for (size_t i = 0; i < choices.size(); i++) {
+            if (choices[i].value == value) {
+                return i;
+            }
+        }
+        return -1;
+    }
+
+    // Parse an option index. Returns -1, if invalid.
+    long parse_index(const std::string& s, size_t size);
+
+    // Returns the values of the selected options of a <select> element
+    std::vector<std::string> selected_values(emscripten::val el);
+}
+
+// <select> with one of choices selected
+template <class T>
+Node select(const Choices<T>& choices, const T& selected, Attrs attrs = {})
+{
+    const long sel = detail::index_of(choices, selected);
+    return Node("select", attrs,
+        detail::options(choices, [=](size_t i) { return long(i) == sel; }));
+}
+
+// <select multiple> with all choices in selected selected
+template <class T>
+Node select_multiple(
+    const Choices<T>& choices, const std::set<T>& selected, Attrs attrs = {})
+{
+    attrs["multiple"] = "";
+    return Node("select", attrs, detail::options(choices, [&](size_t i) {
+        return selected.count(choices[i].value) > 0;
+    }));
+}
+
+// Returns the choice selected in the target <select> of a change event
+template <class T>
+std::optional<T> event_choice(const Choices<T>& choices, emscripten::val& event)
+{
+    const auto i = detail::parse_index(
+        event["target"]["value"].template as<std::string>(), choices.size());
+    if (i < 0) {
+        return std::nullopt;
+    }
+    return choices[i].value;
+}
+
+// Returns the choices selected in the target <select multiple> of a change
+// event
+template <class T>
+std::set<T> event_choices(const Choices<T>& choices, emscripten::val& event)
+{
+    std::set<T> set;
+    for (auto& v : detail::selected_values(event["target"])) {
+        const auto i = detail::parse_index(v, choices.size());
+        if (i >= 0) {
+            set.insert(choices[i].value);
+        }
+    }
+    return set;
+}
+
+// Radio group of choices with one selected. Each radio button is rendered
+// inside a <label> with the choice's label. All buttons share name, which
+// must be unique in the document.
+template <class T>
+Node radio_group(const std::string& name, const Choices<T>& choices,
+    const T& selected, Attrs attrs = {})
+{
+    const long sel = detail::index_of(choices, selected);
+    attrs.role(Role::radiogroup);
+    Node group("div", attrs);
+    group.children.reserve(choices.size());
+    for (size_t i = 0; i < choices.size(); i++) {
+        Attrs a = {
+            { "type", "radio" },
+            { "name", name },
+            { "value", std::to_string(i) },
+        };
+        if (choices[i].disabled) {
+            a["disabled"] = "";
+        }
+        const bool checked = long(i) == sel;
+        if (checked) {
+            a["checked"] = "";
+        }
+        Node input("input", a);
+        input.props["checked"] = emscripten::val(checked);
+        group.children.push_back(Node("label", {},
+            { input, Node("span", {}, choices[i].label, true) }));
+    }
+    return group;
+}
+}
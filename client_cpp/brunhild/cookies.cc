@@ -0,0 +1,115 @@
+#include "cookies.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::cookies {
+
+// Returns all cookies as a JS object, decoding names and values. Cookies,
+// that fail to decode, are skipped.
+static val read()
+{
+    EM_ASM({
+        if (window.__bh_read_cookies) {
+            return;
+        }
+        window.__bh_read_cookies = function()
+        {
+            var out = {};
+            document.cookie.split(";").forEach(function(pair) {
+                var i = pair.indexOf("=");
+                if (i == -1) {
+                    return;
+                }
+                try {
+                    var name = decodeURIComponent(pair.slice(0, i).trim());
+                    out[name] = decodeURIComponent(pair.slice(i + 1).trim());
+                } catch (e) {
+                }
+            });
+            return out;
+        };
+    });
+    return val::global("__bh_read_cookies")();
+}
+
+std::optional<string> get(const string& name)
+{
+    auto v = read()[name];
+    if (!v.isString()) {
+        return std::nullopt;
+    }
+    return v.as<string>();
+}
+
+std::map<string, string> all()
+{
+    auto obj = read();
+    auto keys = emscripten::vecFromJSArray<string>(
+        val::global("Object").call<val>("keys", obj));
+    std::map<string, string> m;
+    for (auto& k : keys) {
+        m[k] = obj[k].as<string>();
+    }
+    return m;
+}
+
+// Write a raw cookie string to the document
+static void write(const string& name, const string& value, const string& attrs)
+{
+    EM_ASM_INT(
+        {
+            document.cookie = encodeURIComponent(UTF8ToString($0)) + "="
+                + encodeURIComponent(UTF8ToString($1)) + UTF8ToString($2);
+        },
+        name.c_str(), value.c_str(), attrs.c_str());
+}
+
+// Format path and domain attributes
+static string location_attrs(const string& path, const string& domain)
+{
+    string s;
+    if (path.size()) {
+        s += "; path=" + path;
+    }
+    if (domain.size()) {
+        s += "; domain=" + domain;
+    }
+    return s;
+}
+
+void set(const string& name, const string& value, const CookieOptions& opts)
+{
+    auto attrs = location_attrs(opts.path, opts.domain);
+    if (opts.max_age) {
+        attrs += "; max-age=" + std::to_string(*opts.max_age);
+    }
+    switch (opts.same_site) {
+    case SameSite::unset:
+        break;
+    case SameSite::lax:
+        attrs += "; samesite=lax";
+        break;
+    case SameSite::strict:
+        attrs += "; samesite=strict";
+        break;
+    case SameSite::none:
+        attrs += "; samesite=none";
+        break;
+    }
+    if (opts.secure) {
+        attrs += "; secure";
+    }
+    write(name, value, attrs);
+}
+
+void remove(const string& name, const string& path, const string& domain)
+{
+    write(name, "",
+        location_attrs(path, domain)
+            + "; expires=Thu, 01 Jan 1970 00:00:00 GMT");
+}
+}
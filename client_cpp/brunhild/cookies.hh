@@ -0,0 +1,48 @@
+#pragma once
+
+#include <map>
+#include <optional>
+#include <string>
+
+// Reading and writing of document cookies. Names and values are
+// percent-encoded when written and decoded when read.
+namespace brunhild::cookies {
+
+// SameSite attribute of a cookie
+enum class SameSite {
+    unset, // Browser default
+    lax,
+    strict,
+    none, // Requires CookieOptions::secure
+};
+
+struct CookieOptions {
+    // Lifetime in seconds. Session cookie, if unset.
+    std::optional<long long> max_age;
+
+    // Path the cookie is sent for
+    std::string path = "/";
+
+    // Domain the cookie is sent for. Current host only, if empty.
+    std::string domain;
+
+    SameSite same_site = SameSite::lax;
+
+    // Only send the cookie over HTTPS
+    bool secure = false;
+};
+
+// Returns the value of a cookie or std::nullopt, if not set
+std::optional<std::string> get(const std::string& name);
+
+// Returns all cookies readable by the document by name
+std::map<std::string, std::string> all();
+
+// Set a cookie
+void set(const std::string& name, const std::string& value,
+    const CookieOptions& opts = {});
+
+// Delete a cookie. path and domain must match those the cookie was set with.
+void remove(const std::string& name, const std::string& path = "/",
+    const std::string& domain = "");
+}
@@ -0,0 +1,138 @@
+#include "custom_element.hh"
+#include "mutations.hh"
+#include "node.hh"
+#include "util.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <memory>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+// Registered custom element view factories by tag
+static std::unordered_map<string, ElementFactory> factories;
+
+// Views of connected custom element instances by host element ID
+static std::unordered_map<string, std::unique_ptr<View>> instances;
+
+void define_element(
+    string tag, ElementFactory factory, std::vector<string> observed_attrs)
+{
+    factories[tag] = factory;
+
+    Rope s;
+    for (size_t i = 0; i < observed_attrs.size(); i++) {
+        if (i) {
+            s << ',';
+        }
+        s << observed_attrs[i];
+    }
+
+    EM_ASM_INT(
+        {
+            var tag = UTF8ToString($0);
+            var observed = UTF8ToString($1);
+            observed = observed ? observed.split(',') : [];
+
+            class BrunhildElement extends HTMLElement {
+                static get observedAttributes() { return observed; }
+
+                connectedCallback()
+                {
+                    Module._bh_element_connected(tag, this);
+                }
+
+                disconnectedCallback()
+                {
+                    Module._bh_element_disconnected(this.id);
+                }
+
+                attributeChangedCallback()
+                {
+                    if (this.isConnected) {
+                        Module._bh_element_changed(this.id);
+                    }
+                }
+            }
+
+            customElements.define(tag, BrunhildElement);
+        },
+        tag.c_str(), s.str().c_str());
+}
+
+View* element_view(const string& host_id)
+{
+    if (!instances.count(host_id)) {
+        return nullptr;
+    }
+    return instances.at(host_id).get();
+}
+
+static void element_connected(string tag, val host)
+{
+    if (!factories.count(tag)) {
+        return;
+    }
+
+    auto id = host["id"].as<string>();
+    if (id == "") {
+        id = new_id();
+        host.set("id", id);
+    } else if (instances.count(id)) {
+        // Element moved inside the document. Keep the current view.
+        return;
+    }
+
+    auto v = factories.at(tag)(host);
+    instances[id] = std::unique_ptr<View>(v);
+    set_inner_html(id, v->html());
+}
+
+static void element_disconnected(string id)
+{
+    if (!instances.count(id)) {
+        return;
+    }
+
+    // Element might just be moving to a different location in the document.
+    // Only destroy the view, if it is still detached after the current task.
+    EM_ASM_INT(
+        {
+            var id = UTF8ToString($0);
+            setTimeout(function() {
+                var el = document.getElementById(id);
+                if (!el || !el.isConnected) {
+                    Module._bh_element_destroy(id);
+                }
+            });
+        },
+        id.c_str());
+}
+
+static void element_destroy(string id)
+{
+    if (!instances.count(id)) {
+        return;
+    }
+    instances.at(id)->remove();
+    instances.erase(id);
+}
+
+static void element_changed(string id)
+{
+    if (auto v = element_view(id); v && v->is_mounted()) {
+        v->patch();
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_custom_element)
+{
+    emscripten::function("_bh_element_connected", &element_connected);
+    emscripten::function("_bh_element_disconnected", &element_disconnected);
+    emscripten::function("_bh_element_destroy", &element_destroy);
+    emscripten::function("_bh_element_changed", &element_changed);
+}
+}
@@ -0,0 +1,30 @@
+#pragma once
+
+#include "view.hh"
+#include <emscripten/val.h>
+#include <functional>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Creates a view to be rendered inside a custom element instance. Receives the
+// host element as the only argument.
+typedef std::function<View*(emscripten::val& host)> ElementFactory;
+
+// Define a custom element backed by a view, so brunhild views can be embedded
+// into pages not managed by brunhild.
+// tag: custom element tag name. Must contain a hyphen.
+// factory: creates a new view for each connected element instance
+// observed_attrs: host attributes, which cause the view to be patched on change
+//
+// The view is created and mounted inside the host element, when the element is
+// connected to the document, and removed and destroyed, when it is
+// disconnected. Host elements without an ID are assigned one on connection.
+void define_element(std::string tag, ElementFactory factory,
+    std::vector<std::string> observed_attrs = {});
+
+// Returns the view backing a custom element instance by host element ID or
+// NULL, if none.
+View* element_view(const std::string& host_id);
+}
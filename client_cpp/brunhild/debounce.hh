@@ -0,0 +1,242 @@
+#pragma once
+
+#include "events.hh"
+#include "timer.hh"
+#include "view.hh"
+#include <functional>
+#include <memory>
+#include <optional>
+#include <tuple>
+#include <type_traits>
+
+// Rate limiting of event handlers and other callbacks with timers
+namespace brunhild {
+
+// Edges a rate limited function is called on
+struct RateOptions {
+    // Call on the first call of a burst
+    bool leading = false;
+
+    // Call with the arguments of the last call at the end of a burst
+    bool trailing = true;
+};
+
+namespace detail {
+    template <class... Args> struct RateState {
+        std::function<void(Args...)> fn;
+        unsigned ms;
+        RateOptions opts;
+        long timer = -1;
+        std::optional<std::tuple<std::decay_t<Args>...>> pending;
+
+        ~RateState() { cancel(); }
+
+        void cancel()
+        {
+            if (timer != -1) {
+                clear_timer(timer);
+                timer = -1;
+            }
+            pending.reset();
+        }
+
+        // Call fn with the pending arguments, if any
+        void run_pending()
+        {
+            if (!pending) {
+                return;
+            }
+            auto args = std::move(*pending);
+            pending.reset();
+            auto f = fn; // fn might destroy the state
+            std::apply(f, args);
+        }
+    };
+
+    // Shared implementation of Debounced and Throttled
+    template <class... Args> class RateLimited {
+    public:
+        RateLimited(
+            std::function<void(Args...)> fn, unsigned ms, RateOptions opts)
+            : state(std::make_shared<State>())
+        {
+            state->fn = fn;
+            state->ms = ms;
+            state->opts = opts;
+        }
+
+        // Cancel any pending call, when the view is next unmounted
+        void cancel_on_unmount(View& view)
+        {
+            view.on_next_unmount([s = std::weak_ptr<State>(state)]() {
+                if (auto st = s.lock()) {
+                    st->cancel();
+                }
+            });
+        }
+
+        // Cancel the pending trailing call, if any, and end the burst
+        void cancel() { state->cancel(); }
+
+        // Make the pending trailing call now, if any, and end the burst
+        void flush()
+        {
+            auto s = state;
+            if (s->timer != -1) {
+                clear_timer(s->timer);
+                s->timer = -1;
+            }
+            s->run_pending();
+        }
+
+        // Returns, if a trailing call is pending
+        bool pending() const { return state->pending.has_value(); }
+
+    protected:
+        typedef RateState<Args...> State;
+
+        // Shared with copies of the wrapper, so it can be passed by value as
+        // a handler
+        std::shared_ptr<State> state;
+
+        // Start the timer of the burst. on_end is called, when it expires.
+        void start_timer(void (*on_end)(std::shared_ptr<State>)) const
+        {
+            state->timer = set_timeout(
+                [s = std::weak_ptr<State>(state), on_end]() {
+                    if (auto st = s.lock()) {
+                        st->timer = -1;
+                        on_end(st);
+                    }
+                },
+                state->ms);
+        }
+    };
+}
+
+// Wraps fn, so it is only called, once calls have stopped for ms
+// milliseconds. Copies share the same timer.
+template <class... Args> class Debounced : public detail::RateLimited<Args...> {
+    typedef detail::RateLimited<Args...> Base;
+    using typename Base::State;
+    using Base::state;
+
+public:
+    Debounced(std::function<void(Args...)> fn, unsigned ms,
+        RateOptions opts = {})
+        : Base(fn, ms, opts)
+    {
+    }
+
+    // Same as above, but cancels any pending call, when view is next
+    // unmounted
+    Debounced(View& view, std::function<void(Args...)> fn, unsigned ms,
+        RateOptions opts = {})
+        : Base(fn, ms, opts)
+    {
+        this->cancel_on_unmount(view);
+    }
+
+    void operator()(Args... args) const
+    {
+        auto s = state;
+        const bool idle = s->timer == -1;
+        if (!idle) {
+            clear_timer(s->timer);
+            s->timer = -1;
+        }
+        if (s->opts.trailing && !(idle && s->opts.leading)) {
+            s->pending.emplace(args...);
+        }
+        this->start_timer(&end);
+        if (idle && s->opts.leading) {
+            auto f = s->fn;
+            f(args...);
+        }
+    }
+
+private:
+    static void end(std::shared_ptr<State> s)
+    {
+        if (s->opts.trailing) {
+            s->run_pending();
+        }
+    }
+};
+
+// Wraps fn, so it is called at most once every ms milliseconds. Copies share
+// the same timer.
+template <class... Args> class Throttled : public detail::RateLimited<Args...> {
+    typedef detail::RateLimited<Args...> Base;
+    using typename Base::State;
+    using Base::state;
+
+public:
+    // Throttling defaults to calling on both edges
+    Throttled(std::function<void(Args...)> fn, unsigned ms,
+        RateOptions opts = { true, true })
+        : Base(fn, ms, opts)
+    {
+    }
+
+    // Same as above, but cancels any pending call, when view is next
+    // unmounted
+    Throttled(View& view, std::function<void(Args...)> fn, unsigned ms,
+        RateOptions opts = { true, true })
+        : Base(fn, ms, opts)
+    {
+        this->cancel_on_unmount(view);
+    }
+
+    void operator()(Args... args) const
+    {
+        auto s = state;
+        if (s->timer != -1) {
+            if (s->opts.trailing) {
+                s->pending.emplace(args...);
+            }
+            return;
+        }
+        this->start_timer(&end);
+        if (s->opts.leading) {
+            auto f = s->fn;
+            f(args...);
+        } else if (s->opts.trailing) {
+            s->pending.emplace(args...);
+        }
+    }
+
+private:
+    // Make the trailing call and keep throttling calls following it
+    static void end(std::shared_ptr<State> s)
+    {
+        if (!s->opts.trailing || !s->pending) {
+            return;
+        }
+        s->timer = set_timeout(
+            [w = std::weak_ptr<State>(s)]() {
+                if (auto st = w.lock()) {
+                    st->timer = -1;
+                    end(st);
+                }
+            },
+            s->ms);
+        s->run_pending();
+    }
+};
+
+// Debounce an event handler. Trailing calls receive the event after its
+// dispatch has finished, so they can not prevent its default action or
+// propagation.
+inline Handler debounce(Handler fn, unsigned ms, RateOptions opts = {})
+{
+    return Debounced<emscripten::val&>(fn, ms, opts);
+}
+
+// Throttle an event handler. See debounce().
+inline Handler throttle(
+    Handler fn, unsigned ms, RateOptions opts = { true, true })
+{
+    return Throttled<emscripten::val&>(fn, ms, opts);
+}
+}
@@ -0,0 +1,187 @@
+#include "debug.hh"
+#include "log.hh"
+#include "util.hh"
+#include <algorithm>
+#include <cxxabi.h>
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <stdlib.h>
+#include <unordered_map>
+
+using std::string;
+
+namespace brunhild::debug {
+
+static bool overlay_enabled = false;
+
+// Number of renders of a view
+struct RenderCount {
+    const char* type;
+    unsigned long count = 0;
+};
+
+// Render counts by view ID since the overlay was enabled
+static std::unordered_map<string, RenderCount> render_counts;
+
+// Render counts have changed since the last panel refresh
+static bool counts_changed = false;
+
+void set_render_overlay(bool enable)
+{
+    overlay_enabled = enable;
+    if (!enable) {
+        render_counts.clear();
+        EM_ASM({
+            var el = document.getElementById('bh-debug-overlay');
+            if (el) {
+                el.remove();
+            }
+        });
+    } else {
+        counts_changed = true;
+    }
+}
+
+bool render_overlay() { return overlay_enabled; }
+
+void count_render(const string& id, const char* type)
+{
+    if (!overlay_enabled) {
+        return;
+    }
+    auto& c = render_counts[id];
+    c.type = type;
+    c.count++;
+    counts_changed = true;
+}
+
+void forget_renders(const string& id)
+{
+    if (render_counts.erase(id)) {
+        counts_changed = true;
+    }
+}
+
+// Render the panel with the most rendered views
+static void refresh_panel()
+{
+    std::vector<std::pair<const string*, const RenderCount*>> sorted;
+    sorted.reserve(render_counts.size());
+    for (auto& [ id, c ] : render_counts) {
+        sorted.push_back({ &id, &c });
+    }
+    std::sort(sorted.begin(), sorted.end(), [](auto& a, auto& b) {
+        return a.second->count > b.second->count;
+    });
+    if (sorted.size() > 20) {
+        sorted.resize(20);
+    }
+
+    Rope s;
+    s << "<table><tr><th>renders</th><th>view</th><th>id</th></tr>";
+    for (auto [ id, c ] : sorted) {
+        s << "<tr><td>" << c->count << "</td><td>";
+        write_escaped(s, c->type) << "</td><td>";
+        write_escaped(s, *id) << "</td></tr>";
+    }
+    s << "</table>";
+
+    EM_ASM_INT(
+        {
+            var el = document.getElementById('bh-debug-overlay');
+            if (!el) {
+                el = document.createElement('div');
+                el.id = 'bh-debug-overlay';
+                el.style.cssText = 'position:fixed;bottom:0;right:0;'
+                    + 'z-index:2147483647;max-height:50vh;overflow:auto;'
+                    + 'background:rgba(0,0,0,.8);color:#fff;'
+                    + 'font:11px monospace;padding:4px;pointer-events:none';
+                document.body.appendChild(el);
+            }
+            el.innerHTML = UTF8ToString($0);
+        },
+        s.str().c_str());
+}
+
+void show_patched(const std::vector<string>& ids)
+{
+    for (auto& id : ids) {
+        EM_ASM_INT(
+            {
+                var el = window.__bh_get(UTF8ToString($0));
+                if (!el || !el.style) {
+                    return;
+                }
+                if (el.__bh_outline === undefined) {
+                    el.__bh_outline = el.style.outline;
+                }
+                el.style.outline = '2px solid rgba(255,0,0,.8)';
+                clearTimeout(el.__bh_outline_timer);
+                el.__bh_outline_timer = setTimeout(function() {
+                    el.style.outline = el.__bh_outline;
+                    delete el.__bh_outline;
+                }, 300);
+            },
+            id.c_str());
+    }
+
+    if (counts_changed) {
+        counts_changed = false;
+        refresh_panel();
+    }
+}
+
+string type_name(const std::type_info& type)
+{
+    int status;
+    char* name = abi::__cxa_demangle(type.name(), nullptr, nullptr, &status);
+    if (!name) {
+        return type.name();
+    }
+    string re(name);
+    free(name);
+    return re;
+}
+
+#ifndef NDEBUG
+// Cached demangled view type names
+static std::unordered_map<const std::type_info*, string> type_names;
+
+static const string& cached_type_name(const std::type_info& type)
+{
+    auto it = type_names.find(&type);
+    if (it == type_names.end()) {
+        it = type_names.insert({ &type, type_name(type) }).first;
+    }
+    return it->second;
+}
+
+void stamp_type(Attrs& attrs, const std::type_info& type)
+{
+    attrs["data-bh-view"] = cached_type_name(type);
+}
+
+void write_type_attr(Rope& s, const std::type_info& type)
+{
+    s << " data-bh-view=\"" << cached_type_name(type) << '"';
+}
+
+static bool log_patches = false;
+
+void set_patch_logging(bool enable) { log_patches = enable; }
+
+bool patch_logging() { return log_patches; }
+#endif
+
+void log_patch(const string& op, const string& id, const string& old_val,
+    const string& new_val)
+{
+    BH_DEBUG("patch ", op, " #", id, ": ", old_val, " -> ", new_val);
+}
+
+EMSCRIPTEN_BINDINGS(module_debug)
+{
+    emscripten::function("_bh_set_render_overlay", &set_render_overlay);
+    emscripten::function("_bh_set_patch_logging", &set_patch_logging);
+}
+}
@@ -0,0 +1,66 @@
+#pragma once
+
+#include "node.hh"
+#include "util.hh"
+#include <string>
+#include <typeinfo>
+#include <vector>
+
+// Development tools for inspecting rendering activity
+namespace brunhild::debug {
+
+// Toggle the render activity overlay. When enabled, elements are outlined,
+// when patched, and a panel with render counts of mounted views since the
+// overlay was enabled is shown. Can also be toggled from the browser console
+// with Module._bh_set_render_overlay(bool).
+void set_render_overlay(bool);
+
+// Returns, if the render activity overlay is enabled
+bool render_overlay();
+
+// Record a render of a view, if the overlay is enabled. Called by brunhild
+// views on each render.
+// type: type name of the view
+void count_render(const std::string& id, const char* type);
+
+// Drop the render count of a view. Called by brunhild views on unmount.
+void forget_renders(const std::string& id);
+
+// Toggle logging of every DOM mutation with the element ID, old and new value
+// to the console. Compiled out, if NDEBUG is defined. Can also be toggled from
+// the browser console with Module._bh_set_patch_logging(bool).
+#ifndef NDEBUG
+void set_patch_logging(bool);
+bool patch_logging();
+#else
+inline void set_patch_logging(bool) {}
+inline bool patch_logging() { return false; }
+#endif
+
+// Log a DOM mutation, if patch logging is enabled
+void log_patch(const std::string& op, const std::string& id,
+    const std::string& old_val, const std::string& new_val);
+
+// Returns the demangled name of a type
+std::string type_name(const std::type_info&);
+
+// In debug builds, set the "data-bh-view" attribute to the type name of the
+// view, so elements can be traced back to the view in browser devtools.
+// Compiled out, if NDEBUG is defined.
+#ifndef NDEBUG
+void stamp_type(Attrs& attrs, const std::type_info& type);
+#else
+inline void stamp_type(Attrs&, const std::type_info&) {}
+#endif
+
+// Same as stamp_type(), but writes the attribute directly to an opening tag
+#ifndef NDEBUG
+void write_type_attr(Rope& s, const std::type_info& type);
+#else
+inline void write_type_attr(Rope&, const std::type_info&) {}
+#endif
+
+// Outline patched elements and refresh the render count panel. Called by
+// brunhild after flushing mutations, if the overlay is enabled.
+void show_patched(const std::vector<std::string>& ids);
+}
@@ -0,0 +1,137 @@
+#include "drag.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All active drop zones by ID
+static std::unordered_map<long, DropZone*> zones;
+
+DropData drop_data(val& event)
+{
+    DropData d;
+    d.x = event["clientX"].as<double>();
+    d.y = event["clientY"].as<double>();
+    const auto dt = event["dataTransfer"];
+    if (dt.isNull() || dt.isUndefined()) {
+        return d;
+    }
+    d.text = dt.call<string>("getData", string("text/plain"));
+    d.uri_list = dt.call<string>("getData", string("text/uri-list"));
+    d.html = dt.call<string>("getData", string("text/html"));
+    d.files = files_from(dt);
+    return d;
+}
+
+void set_drag_data(val& event, const string& type, const string& data)
+{
+    event["dataTransfer"].call<void>("setData", type, data);
+}
+
+long on_drag_start(Handler handler, string selector)
+{
+    return register_handler("dragstart", handler, selector);
+}
+
+DropZone::DropZone(string selector, std::function<void(DropData&)> on_drop,
+    std::function<void(bool)> on_hover)
+    : selector(selector)
+    , zone_id(id_counter++)
+    , on_drop(on_drop)
+    , on_hover(on_hover)
+{
+    zones[zone_id] = this;
+    EM_ASM_INT(
+        {
+            if (!window.__bh_drop_zones) {
+                window.__bh_drop_zones = {};
+            }
+            var id = $0;
+            var sel = UTF8ToString($1);
+            var z = { over : null, listeners : {} };
+
+            var target = function(e)
+            {
+                var t = e.composedPath ? e.composedPath()[0] : e.target;
+                return t && t.closest ? t.closest(sel) : null;
+            };
+            var hover = function(el)
+            {
+                if (el === z.over) {
+                    return;
+                }
+                z.over = el;
+                Module._bh_drop_event(id, false, !!el);
+            };
+
+            z.listeners.dragover = function(e)
+            {
+                var el = target(e);
+                if (el) {
+                    e.preventDefault();
+                }
+                hover(el);
+            };
+            z.listeners.dragleave = function(e)
+            {
+                if (z.over && !(e.relatedTarget
+                                  && z.over.contains(e.relatedTarget))) {
+                    hover(null);
+                }
+            };
+            z.listeners.drop = function(e)
+            {
+                hover(null);
+                if (target(e)) {
+                    e.preventDefault();
+                    Module._bh_drop_event(id, true, e);
+                }
+            };
+            for (var type in z.listeners) {
+                document.addEventListener(type, z.listeners[type]);
+            }
+            window.__bh_drop_zones[id] = z;
+        },
+        zone_id, selector.c_str());
+}
+
+DropZone::~DropZone()
+{
+    zones.erase(zone_id);
+    EM_ASM_INT(
+        {
+            var z = window.__bh_drop_zones[$0];
+            for (var type in z.listeners) {
+                document.removeEventListener(type, z.listeners[type]);
+            }
+            delete window.__bh_drop_zones[$0];
+        },
+        zone_id);
+}
+
+// arg is either the hover state for hover changes or the drop event
+void run_drop_handler(long id, bool is_drop, val arg)
+{
+    if (!zones.count(id)) {
+        return;
+    }
+    auto z = zones.at(id);
+    if (is_drop) {
+        auto d = drop_data(arg);
+        z->on_drop(d);
+    } else if (z->on_hover) {
+        z->on_hover(arg.as<bool>());
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_drag)
+{
+    emscripten::function("_bh_drop_event", &run_drop_handler);
+}
+}
@@ -0,0 +1,64 @@
+#pragma once
+
+#include "events.hh"
+#include "file.hh"
+#include <functional>
+#include <string>
+#include <vector>
+
+// Drag-and-drop support
+namespace brunhild {
+
+// Contents of a drop event's dataTransfer
+struct DropData {
+    // Dropped "text/plain", "text/uri-list" and "text/html" payloads.
+    // Empty, if not present.
+    std::string text, uri_list, html;
+
+    // Dropped files
+    std::vector<File> files;
+
+    // Drop position relative to the viewport
+    double x, y;
+};
+
+// Extract dropped data from a drop event
+DropData drop_data(emscripten::val& event);
+
+// Set a drag payload of a MIME type. Only valid inside dragstart handlers.
+void set_drag_data(
+    emscripten::val& event, const std::string& type, const std::string& data);
+
+// Register a persistent handler for dragstart events on elements matching
+// selector. Use set_drag_data() inside the handler to set the payload.
+// Returns handler ID for unregister_handler().
+long on_drag_start(Handler handler, std::string selector = "");
+
+// Makes elements matching a CSS selector accept drops for its lifetime.
+// Unlike the delegated handlers of register_handler(), a DropZone calls
+// preventDefault() on dragover and drop events, which browsers require to
+// allow dropping.
+class DropZone {
+public:
+    // CSS selector of accepting elements
+    const std::string selector;
+
+    // on_drop: called with the dropped data
+    // on_hover: optional and called with true, when a drag enters an
+    // accepting element, and false, when it leaves or drops
+    DropZone(std::string selector, std::function<void(DropData&)> on_drop,
+        std::function<void(bool)> on_hover = nullptr);
+
+    ~DropZone();
+
+    DropZone(const DropZone&) = delete;
+    DropZone& operator=(const DropZone&) = delete;
+
+private:
+    const long zone_id;
+    std::function<void(DropData&)> on_drop;
+    std::function<void(bool)> on_hover;
+
+    friend void run_drop_handler(long, bool, emscripten::val);
+};
+}
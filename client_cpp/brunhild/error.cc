@@ -0,0 +1,18 @@
+#include "error.hh"
+
+namespace brunhild {
+
+std::optional<Error> try_run(std::function<void()> fn)
+{
+    try {
+        fn();
+    } catch (Error& e) {
+        return e;
+    } catch (std::exception& e) {
+        return Error(e.what());
+    } catch (const char* s) {
+        return Error(s);
+    }
+    return std::nullopt;
+}
+}
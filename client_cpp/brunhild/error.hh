@@ -0,0 +1,29 @@
+#pragma once
+
+#include <functional>
+#include <optional>
+#include <stdexcept>
+#include <string>
+
+namespace brunhild {
+
+// Error raised by brunhild during rendering or patching
+class Error : public std::runtime_error {
+public:
+    // ID of the view the error occurred in. Empty, if not applicable.
+    const std::string view_id;
+
+    Error(std::string msg, std::string view_id = "")
+        : std::runtime_error(msg)
+        , view_id(view_id)
+    {
+    }
+};
+
+// Run fn and return any error raised by it instead of propagating it.
+// Exceptions other than Error are converted to Error.
+// Note that catching requires the module to be compiled with exception
+// catching enabled (-s DISABLE_EXCEPTION_CATCHING=0), which is only the default
+// in debug builds. Without it any error still aborts the program.
+std::optional<Error> try_run(std::function<void()> fn);
+}
@@ -19,51 +19,92 @@ public:
     }
 };
 
-// All registered event handlers
-static std::unordered_map<Key, std::unordered_map<long, Handler>, pairhash>
+// Registered event handler
+struct Registered {
+    Handler fn;
+    bool once;
+};
+
+// All registered event handlers by listener key and selector
+static std::unordered_map<Key, std::unordered_map<long, Registered>, pairhash>
     handlers;
 
 static long id_counter = 0;
 
-long register_handler(string type, Handler handler, string selector)
+// Returns the key of the delegated listener for an event type and options
+static string listener_key(const string& type, const ListenerOptions& opts)
+{
+    string key = type;
+    if (opts.capture) {
+        key += ":capture";
+    }
+    if (!opts.passive) {
+        key += ":active";
+    }
+    return key;
+}
+
+long register_handler(
+    string type, Handler handler, string selector, ListenerOptions opts)
 {
-    const Key key = { type, selector };
+    const Key key = { listener_key(type, opts), selector };
 
     if (!handlers.count(key)) {
         EM_ASM_INT(
             {
-                var type = UTF8ToString($0);
+                var key = UTF8ToString($0);
                 var sel = UTF8ToString($1);
+                var type = UTF8ToString($2);
+                var opts = { passive : !!$3, capture : !!$4 };
 
                 if (!window.__bh_handlers) {
                     window.__bh_handlers = {};
+                    window.__bh_listeners = {};
                 }
 
-                // Pool event handlers of one event type together
-                if (!window.__bh_handlers[type]) {
-                    window.__bh_handlers[type] = {};
-                    document.addEventListener(type,
-                        function(e) {
-                            var t = e.target;
-                            if (!t.tagName) { // Not an element
+                // Pool event handlers of one event type and options together
+                if (!window.__bh_handlers[key]) {
+                    window.__bh_handlers[key] = {};
+                    var listener = function(e)
+                    {
+                        // Record immediate propagation stops, so the rest of
+                        // the pooled handlers are skipped
+                        var stop = e.stopImmediatePropagation;
+                        e.stopImmediatePropagation = function()
+                        {
+                            e.__bh_stopped = true;
+                            stop.call(e);
+                        };
+
+                        // Events from inside shadow roots are retargeted to
+                        // the host element
+                        var t = e.composedPath ? e.composedPath()[0]
+                                               : e.target;
+                        if (!t.tagName) { // Not an element
+                            return;
+                        }
+
+                        for (var sel in window.__bh_handlers[key]) {
+                            if (e.__bh_stopped) {
                                 return;
                             }
-
-                            for (var sel in window.__bh_handlers[type]) {
-                                if (!sel || t.matches(sel)) {
-                                    Module._run_event_handlers(type, sel, e);
-                                }
+                            if (!sel || t.matches(sel)) {
+                                Module._run_event_handlers(key, sel, e);
                             }
-                        },
-                        { passive : true });
+                        }
+                    };
+                    window.__bh_listeners[key]
+                        = { type : type, fn : listener, opts : opts };
+                    document.addEventListener(type, listener, opts);
                 }
-                window.__bh_handlers[type][sel] = true;
+                window.__bh_handlers[key][sel] = true;
             },
-            type.c_str(), selector.c_str());
+            key.first.c_str(), selector.c_str(), type.c_str(), opts.passive,
+            opts.capture);
     }
 
     const long id = id_counter++;
-    handlers[key][id] = handler;
+    handlers[key][id] = { handler, opts.once };
     return id;
 }
 
@@ -89,18 +130,55 @@ void unregister_handler(long id)
     }
 }
 
-static void run_event_handlers(string type, string sel, emscripten::val event)
+void remove_all_handlers()
 {
-    const Key key = { type, sel };
-    if (!handlers.count(key)) {
+    handlers.clear();
+    EM_ASM({
+        if (!window.__bh_listeners) {
+            return;
+        }
+        for (var key in window.__bh_listeners) {
+            var l = window.__bh_listeners[key];
+            document.removeEventListener(l.type, l.fn, l.opts);
+        }
+        delete window.__bh_listeners;
+        delete window.__bh_handlers;
+    });
+}
+
+static void run_event_handlers(string key, string sel, emscripten::val event)
+{
+    const Key k = { key, sel };
+    if (!handlers.count(k)) {
         return;
     }
-    auto copy = handlers.at(key); // Handler might invalidate this iterator
+    auto copy = handlers.at(k); // Handler might invalidate this iterator
     for (auto & [ id, h ] : copy) {
-        h(event);
+        if (event["__bh_stopped"].as<bool>()) {
+            return;
+        }
+        if (h.once) {
+            unregister_handler(id);
+        }
+        h.fn(event);
     }
 }
 
+Handler directed(DirectedHandler fn)
+{
+    return [fn](emscripten::val& event) {
+        const auto d = fn(event);
+        if (d.prevent_default) {
+            event.call<void>("preventDefault");
+        }
+        if (d.stop_immediate_propagation) {
+            event.call<void>("stopImmediatePropagation");
+        } else if (d.stop_propagation) {
+            event.call<void>("stopPropagation");
+        }
+    };
+}
+
 EMSCRIPTEN_BINDINGS(module_events)
 {
     emscripten::function("_run_event_handlers", &run_event_handlers);
@@ -11,13 +11,58 @@ namespace brunhild {
 // argument
 typedef std::function<void(emscripten::val&)> Handler;
 
+// Controls the default action and propagation of an event. Applied
+// synchronously before the delegated listener returns.
+struct Directive {
+    // Call Event.preventDefault(). Requires a handler registered with
+    // ListenerOptions::passive set to false, like for intercepting link
+    // clicks.
+    bool prevent_default = false;
+
+    // Call Event.stopPropagation(). The event still reaches other handlers
+    // delegated to the same listener, as they all match the same target.
+    bool stop_propagation = false;
+
+    // Call Event.stopImmediatePropagation(). Also skips any remaining
+    // handlers of the same listener.
+    bool stop_immediate_propagation = false;
+};
+
+// Handler returning a Directive
+typedef std::function<Directive(emscripten::val&)> DirectedHandler;
+
+// Wrap a handler returning a Directive and apply it to the event. Handlers
+// can also call the methods of the Event object directly with the same
+// effect.
+Handler directed(DirectedHandler fn);
+
+// Options of a DOM event handler mapping to those of addEventListener().
+// Handlers with the same options share a delegated document listener.
+struct ListenerOptions {
+    // Unregister the handler after its first call
+    bool once = false;
+
+    // The handler never calls preventDefault(), so the browser can scroll
+    // without waiting for it. Set to false to prevent the default action.
+    bool passive = true;
+
+    // Dispatch during the capture phase, before handlers of the bubbling
+    // phase. Needed for events that do not bubble, like focus or scroll on
+    // elements.
+    bool capture = false;
+};
+
 // Register a persistent global event handler.
 // type: DOM event type (click, hover, ...).
 // selector: any CSS selector the event target should be matched against
+// opts: options of the listener
 // Returns handler ID
-long register_handler(
-    std::string type, Handler handler, std::string selector = "");
+long register_handler(std::string type, Handler handler,
+    std::string selector = "", ListenerOptions opts = {});
 
 // Remove a global event handler by ID
 void unregister_handler(long id);
+
+// Remove all event handlers and the DOM event listeners dispatching to them
+void remove_all_handlers();
 }
@@ -0,0 +1,151 @@
+#include "file.hh"
+#include "promise.hh"
+#include <emscripten.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+File::File(val handle)
+    : handle(handle)
+{
+    const auto n = handle["name"];
+    name = n.isString() ? n.as<string>() : "";
+    type = handle["type"].as<string>();
+    size = handle["size"].as<double>();
+}
+
+// Returns the JS file reading helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_files) {
+            return;
+        }
+        window.__bh_files = {
+            read : function(blob, as_url)
+            {
+                return new Promise(function(resolve, reject) {
+                    var r = new FileReader();
+                    r.onload = function()
+                    {
+                        resolve(as_url ? r.result : new Uint8Array(r.result));
+                    };
+                    r.onerror = function() { reject(r.error); };
+                    if (as_url) {
+                        r.readAsDataURL(blob);
+                    } else {
+                        r.readAsArrayBuffer(blob);
+                    }
+                });
+            },
+            copy : function(arr, ptr) { HEAPU8.set(arr, ptr); }
+        };
+    });
+    return val::global("__bh_files");
+}
+
+void File::read_bytes(
+    std::function<void(std::optional<std::vector<uint8_t>>)> cb) const
+{
+    await(helpers().call<val>("read", handle, false), [cb](bool ok, val arr) {
+        if (!ok) {
+            cb(std::nullopt);
+            return;
+        }
+        std::vector<uint8_t> buf(arr["length"].as<size_t>());
+        if (buf.size()) {
+            // Copy directly into the Wasm heap
+            helpers().call<void>(
+                "copy", arr, reinterpret_cast<uintptr_t>(buf.data()));
+        }
+        cb(std::move(buf));
+    });
+}
+
+void File::read_data_url(
+    std::function<void(std::optional<string>)> cb) const
+{
+    await(helpers().call<val>("read", handle, true), [cb](bool ok, val url) {
+        if (ok) {
+            cb(url.as<string>());
+        } else {
+            cb(std::nullopt);
+        }
+    });
+}
+
+ObjectURL::ObjectURL(const File& file)
+    : url(val::global("URL")
+              .call<val>("createObjectURL", file.handle)
+              .as<string>())
+{
+}
+
+ObjectURL::~ObjectURL()
+{
+    val::global("URL").call<void>("revokeObjectURL", url);
+}
+
+std::vector<File> selected_files(val& event)
+{
+    std::vector<File> files;
+    const auto list = event["target"]["files"];
+    if (list.isNull() || list.isUndefined()) {
+        return files;
+    }
+    const unsigned len = list["length"].as<unsigned>();
+    for (unsigned i = 0; i < len; i++) {
+        files.emplace_back(list[i]);
+    }
+    return files;
+}
+
+long on_file_select(
+    std::function<void(std::vector<File>&)> handler, string selector)
+{
+    return register_handler(
+        "change",
+        [handler](val& event) {
+            auto files = selected_files(event);
+            handler(files);
+        },
+        selector);
+}
+
+std::vector<File> files_from(val dt)
+{
+    std::vector<File> files;
+    if (dt.isNull() || dt.isUndefined()) {
+        return files;
+    }
+
+    const auto list = dt["files"];
+    const unsigned len = list["length"].as<unsigned>();
+    for (unsigned i = 0; i < len; i++) {
+        files.emplace_back(list[i]);
+    }
+    if (files.size()) {
+        return files;
+    }
+
+    // Some browsers only expose pasted images through items
+    const auto items = dt["items"];
+    if (items.isUndefined()) {
+        return files;
+    }
+    const unsigned items_len = items["length"].as<unsigned>();
+    for (unsigned i = 0; i < items_len; i++) {
+        const auto item = items[i];
+        if (item["kind"].as<string>() != "file") {
+            continue;
+        }
+        const auto f = item.call<val>("getAsFile");
+        if (!f.isNull()) {
+            files.emplace_back(f);
+        }
+    }
+    return files;
+}
+}
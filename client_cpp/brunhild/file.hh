@@ -0,0 +1,67 @@
+#pragma once
+
+#include "events.hh"
+#include <cstdint>
+#include <emscripten/val.h>
+#include <functional>
+#include <optional>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// File selected, pasted or dropped by the user
+struct File {
+    // File name. Pasted images usually have a generic name like "image.png".
+    std::string name;
+
+    // MIME type. Empty, if unknown.
+    std::string type;
+
+    // Size in bytes
+    double size;
+
+    // Handle to the underlying JS File object
+    emscripten::val handle;
+
+    // Create from a JS File or Blob object
+    File(emscripten::val handle);
+
+    // Read the file contents with FileReader. cb receives std::nullopt on
+    // failure.
+    void read_bytes(
+        std::function<void(std::optional<std::vector<uint8_t>>)> cb) const;
+
+    // Read the file as a data URL with FileReader. cb receives std::nullopt on
+    // failure.
+    void read_data_url(
+        std::function<void(std::optional<std::string>)> cb) const;
+};
+
+// Object URL referencing a file, usable as the "src" of an <img> or <video>
+// for previews. Revoked on destruction, so keep it alive, as long as
+// the URL is rendered.
+class ObjectURL {
+public:
+    // Blob URL string
+    const std::string url;
+
+    ObjectURL(const File& file);
+    ~ObjectURL();
+
+    ObjectURL(const ObjectURL&) = delete;
+    ObjectURL& operator=(const ObjectURL&) = delete;
+};
+
+// Extract all files from a DataTransfer object, like the clipboardData of a
+// paste event or the dataTransfer of a drop event
+std::vector<File> files_from(emscripten::val data_transfer);
+
+// Extract the selected files from a change event of an <input type=file>
+std::vector<File> selected_files(emscripten::val& event);
+
+// Register a persistent handler for file selection on <input type=file>
+// elements matching selector. Returns handler ID for unregister_handler().
+long on_file_select(
+    std::function<void(std::vector<File>&)> handler, std::string selector);
+}
@@ -0,0 +1,73 @@
+#include "flip.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static void install()
+{
+    EM_ASM({
+        if (window.__bh_flip) {
+            return;
+        }
+        window.__bh_flip = {
+            // First: record positions
+            first : function(ids)
+            {
+                var rects = {};
+                for (var i = 0; i < ids.length; i++) {
+                    var el = window.__bh_get(ids[i]);
+                    if (el) {
+                        rects[ids[i]] = el.getBoundingClientRect();
+                    }
+                }
+                return rects;
+            },
+
+            // Last, Invert, Play
+            play : function(rects, duration, easing)
+            {
+                for (var id in rects) {
+                    var el = window.__bh_get(id);
+                    if (!el || !el.animate) {
+                        continue;
+                    }
+                    var r = el.getBoundingClientRect();
+                    var dx = rects[id].left - r.left;
+                    var dy = rects[id].top - r.top;
+                    if (!dx && !dy) {
+                        continue;
+                    }
+                    el.animate(
+                        [
+                            {
+                                transform :
+                                    "translate(" + dx + "px," + dy + "px)"
+                            },
+                            { transform : "none" }
+                        ],
+                        { duration : duration, easing : easing });
+                }
+            }
+        };
+    });
+}
+
+void flip(const std::vector<string>& ids, FlipOptions opts)
+{
+    install();
+    auto arr = val::array();
+    for (size_t i = 0; i < ids.size(); i++) {
+        arr.set(i, ids[i]);
+    }
+    auto rects = val::global("__bh_flip").call<val>("first", arr);
+    defer([rects, opts]() {
+        val::global("__bh_flip")
+            .call<void>("play", rects, opts.duration, opts.easing);
+    });
+}
+}
@@ -0,0 +1,23 @@
+#pragma once
+
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Animation options of FLIP transitions
+struct FlipOptions {
+    // Duration in milliseconds
+    unsigned duration = 300;
+
+    // CSS easing function
+    std::string easing = "ease";
+};
+
+// Animate elements from their current positions to their positions after the
+// next flush of DOM mutations using the FLIP technique: positions are recorded
+// now, inverse transforms applied after the mutations are flushed and animated
+// back to identity. Must be called before the mutations moving the elements
+// are flushed.
+void flip(const std::vector<std::string>& ids, FlipOptions opts = {});
+}
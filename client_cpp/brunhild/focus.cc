@@ -0,0 +1,57 @@
+#include "focus.hh"
+#include <emscripten.h>
+#include <stdlib.h>
+
+using std::string;
+
+namespace brunhild {
+
+bool focus(const string& id)
+{
+    return (bool)EM_ASM_INT(
+        {
+            var el = window.__bh_get(UTF8ToString($0));
+            if (!el) {
+                return 0;
+            }
+            el.focus();
+            return 1;
+        },
+        id.c_str());
+}
+
+void blur(const string& id)
+{
+    EM_ASM_INT(
+        {
+            var el = window.__bh_get(UTF8ToString($0));
+            if (el) {
+                el.blur();
+            }
+        },
+        id.c_str());
+}
+
+std::optional<string> active_element_id()
+{
+    char* s = (char*)EM_ASM_INT({
+        var el = document.activeElement;
+        while (el && el.shadowRoot && el.shadowRoot.activeElement) {
+            el = el.shadowRoot.activeElement;
+        }
+        if (!el || !el.id || el === document.body) {
+            return 0;
+        }
+        var len = lengthBytesUTF8(el.id) + 1;
+        var buf = Module._malloc(len);
+        stringToUTF8(el.id, buf, len);
+        return buf;
+    });
+    if (!s) {
+        return std::nullopt;
+    }
+    string id(s);
+    free(s);
+    return id;
+}
+}
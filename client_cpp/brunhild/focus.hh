@@ -0,0 +1,20 @@
+#pragma once
+
+#include <optional>
+#include <string>
+
+// Focus management. All functions operate on the current state of the DOM, so
+// elements inserted by pending mutations can only be focused after the next
+// flush. Use View::autofocus to focus elements of a newly rendered view.
+namespace brunhild {
+
+// Focus an element by ID. Returns false, if the element does not exist.
+bool focus(const std::string& id);
+
+// Remove focus from an element by ID, if it is focused
+void blur(const std::string& id);
+
+// Returns the ID of the currently focused element, if any and it has an ID.
+// Looks inside shadow roots.
+std::optional<std::string> active_element_id();
+}
@@ -0,0 +1,166 @@
+#include "focus_trap.hh"
+#include <emscripten.h>
+
+namespace brunhild {
+
+FocusTrap::FocusTrap(
+    std::shared_ptr<View> child, std::string tag, std::string id)
+    : View(id)
+    , tag(tag)
+    , child(child)
+{
+}
+
+FocusTrap::~FocusTrap() { release(false); }
+
+void FocusTrap::write_html(Rope& s)
+{
+    if (!is_initialized) {
+        is_initialized = true;
+        schedule_mount();
+    }
+    auto a = attrs();
+    a["id"] = id;
+    s << '<' << tag;
+    a.write_html(s);
+    s << '>';
+    child->write_html(s);
+    s << "</" << tag << '>';
+}
+
+void FocusTrap::patch()
+{
+    if (is_initialized) {
+        child->patch();
+    }
+}
+
+void FocusTrap::mount()
+{
+    EM_ASM({
+        if (window.__bh_focus_traps) {
+            return;
+        }
+
+        // Active traps, innermost last
+        var stack = [];
+        var selector = 'a[href],area[href],button:not([disabled]),'
+            + 'input:not([disabled]):not([type="hidden"]),'
+            + 'select:not([disabled]),textarea:not([disabled]),iframe,'
+            + 'audio[controls],video[controls],'
+            + '[contenteditable]:not([contenteditable="false"]),'
+            + '[tabindex]:not([tabindex="-1"])';
+
+        var focusable = function(root)
+        {
+            return Array.prototype.filter.call(
+                root.querySelectorAll(selector), function(el) {
+                    return el.getClientRects().length
+                        && el.getAttribute("tabindex") != "-1";
+                });
+        };
+
+        var focusFirst = function(root)
+        {
+            var els = focusable(root);
+            if (els.length) {
+                els[0].focus();
+            } else {
+                // Make the container itself focusable as a last resort
+                if (!root.hasAttribute("tabindex")) {
+                    root.setAttribute("tabindex", "-1");
+                }
+                root.focus();
+            }
+        };
+
+        var top = function()
+        {
+            while (stack.length && !stack[stack.length - 1].el.isConnected) {
+                stack.pop();
+            }
+            return stack[stack.length - 1];
+        };
+
+        document.addEventListener("keydown", function(e) {
+            var t = top();
+            if (!t || e.key != "Tab" || e.defaultPrevented) {
+                return;
+            }
+            var els = focusable(t.el);
+            if (!els.length) {
+                e.preventDefault();
+                return;
+            }
+            var first = els[0];
+            var last = els[els.length - 1];
+            var active = document.activeElement;
+            if (e.shiftKey && (active === first || !t.el.contains(active))) {
+                e.preventDefault();
+                last.focus();
+            } else if (!e.shiftKey
+                && (active === last || !t.el.contains(active))) {
+                e.preventDefault();
+                first.focus();
+            }
+        });
+        document.addEventListener("focusin", function(e) {
+            var t = top();
+            if (t && !t.el.contains(e.target)) {
+                focusFirst(t.el);
+            }
+        });
+
+        window.__bh_focus_traps = {
+            push : function(id)
+            {
+                var el = window.__bh_get(id);
+                if (!el) {
+                    return;
+                }
+                stack.push({ id : id, el : el, prev : document.activeElement });
+                if (!el.contains(document.activeElement)) {
+                    focusFirst(el);
+                }
+            },
+            remove : function(id, restore)
+            {
+                for (var i = stack.length - 1; i >= 0; i--) {
+                    if (stack[i].id != id) {
+                        continue;
+                    }
+                    var t = stack.splice(i, 1)[0];
+                    var prev = t.prev;
+                    if (restore && prev && prev.isConnected && prev.focus) {
+                        prev.focus();
+                    }
+                    return;
+                }
+            }
+        };
+    });
+    EM_ASM_INT({ window.__bh_focus_traps.push(UTF8ToString($0)); }, id.c_str());
+    View::mount();
+}
+
+void FocusTrap::unmount()
+{
+    release(true);
+    if (is_initialized) {
+        unmount_child(*child);
+    }
+    View::unmount();
+}
+
+void FocusTrap::release(bool restore)
+{
+    if (!is_mounted()) {
+        return;
+    }
+    EM_ASM_INT(
+        {
+            window.__bh_focus_traps.remove(UTF8ToString($0), !!$1);
+        },
+        id.c_str(), restore);
+}
+}
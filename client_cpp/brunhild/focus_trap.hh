@@ -0,0 +1,51 @@
+#pragma once
+
+#include "view.hh"
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// Wraps a child view in a container, that confines keyboard focus to its
+// subtree, while mounted. Tab and Shift+Tab cycle through the focusable
+// elements of the subtree and focus moving outside, like by clicking the
+// page, is returned to it. Traps can be nested, in which case only the
+// innermost one is active.
+// On mount the previously focused element is remembered and the first
+// focusable element of the subtree focused. Focus is restored to the
+// remembered element on unmount.
+class FocusTrap : public View {
+public:
+    // Creates a new trap wrapping child in a container with the given tag
+    FocusTrap(std::shared_ptr<View> child, std::string tag = "div",
+        std::string id = new_id());
+
+    ~FocusTrap();
+
+    void write_html(Rope&);
+
+    // Patch the child view
+    void patch();
+
+    // Returns the child view
+    View& get_child() { return *child; }
+
+protected:
+    // Attributes of the container. Any "id" is overridden.
+    virtual Attrs attrs() { return {}; }
+
+    // Activate the trap
+    void mount();
+
+    // Deactivate the trap and restore focus
+    void unmount();
+
+private:
+    const std::string tag;
+    std::shared_ptr<View> child;
+    bool is_initialized = false;
+
+    // Deactivate the trap. Restores focus, if restore is set.
+    void release(bool restore);
+};
+}
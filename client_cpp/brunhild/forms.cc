@@ -0,0 +1,207 @@
+#include "forms.hh"
+#include <emscripten.h>
+#include <stdlib.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+std::optional<string> FormValues::get(const string& name) const
+{
+    for (auto & [ k, v ] : entries) {
+        if (k == name) {
+            return v;
+        }
+    }
+    return std::nullopt;
+}
+
+std::vector<string> FormValues::get_all(const string& name) const
+{
+    std::vector<string> vals;
+    for (auto & [ k, v ] : entries) {
+        if (k == name) {
+            vals.push_back(v);
+        }
+    }
+    return vals;
+}
+
+std::vector<File> FormValues::get_files(const string& name) const
+{
+    std::vector<File> vals;
+    for (auto & [ k, f ] : files) {
+        if (k == name) {
+            vals.push_back(f);
+        }
+    }
+    return vals;
+}
+
+bool FormValues::has(const string& name) const
+{
+    for (auto & [ k, _ ] : entries) {
+        if (k == name) {
+            return true;
+        }
+    }
+    return false;
+}
+
+// Returns the JS form helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_forms) {
+            return;
+        }
+
+        // Returns [name, value] pairs of all successful controls.
+        // File values are File objects.
+        var collect = function(root)
+        {
+            var out = [];
+            var els = root.querySelectorAll("input,select,textarea");
+            for (var i = 0; i < els.length; i++) {
+                var el = els[i];
+                if (!el.name || el.disabled
+                    || el.closest("fieldset:disabled")) {
+                    continue;
+                }
+                switch (el.type) {
+                case "submit":
+                case "button":
+                case "reset":
+                case "image":
+                    continue;
+                case "checkbox":
+                case "radio":
+                    if (el.checked) {
+                        out.push([ el.name, el.value ]);
+                    }
+                    continue;
+                case "file":
+                    for (var j = 0; j < el.files.length; j++) {
+                        out.push([ el.name, el.files[j] ]);
+                    }
+                    continue;
+                case "select-multiple":
+                    for (var j = 0; j < el.options.length; j++) {
+                        if (el.options[j].selected) {
+                            out.push([ el.name, el.options[j].value ]);
+                        }
+                    }
+                    continue;
+                default:
+                    out.push([ el.name, el.value ]);
+                }
+            }
+            return out;
+        };
+
+        window.__bh_forms = {
+            collect : function(id)
+            {
+                var el = window.__bh_get(id);
+                return el ? collect(el) : [];
+            },
+            formData : function(id)
+            {
+                var el = window.__bh_get(id);
+                if (el && el.tagName == "FORM") {
+                    return new FormData(el);
+                }
+                var fd = new FormData();
+                if (el) {
+                    collect(el).forEach(function(e) { fd.append(e[0], e[1]); });
+                }
+                return fd;
+            }
+        };
+    });
+    return val::global("__bh_forms");
+}
+
+FormValues collect_form(const string& id)
+{
+    FormValues v;
+    auto arr = helpers().call<val>("collect", id);
+    const auto len = arr["length"].as<size_t>();
+    for (size_t i = 0; i < len; i++) {
+        auto e = arr[i];
+        auto name = e[0].as<string>();
+        auto value = e[1];
+        if (value.isString()) {
+            v.entries.push_back({ name, value.as<string>() });
+        } else {
+            v.files.push_back({ name, File(value) });
+        }
+    }
+    return v;
+}
+
+val form_data(const string& id) { return helpers().call<val>("formData", id); }
+
+bool FormDecoder::has_file(const string& name) const
+{
+    for (auto & [ k, _ ] : values.files) {
+        if (k == name) {
+            return true;
+        }
+    }
+    return false;
+}
+
+void FormDecoder::operator()(const string& name, string& field)
+{
+    if (auto v = values.get(name)) {
+        field = *v;
+    }
+}
+
+void FormDecoder::operator()(const string& name, bool& field)
+{
+    field = values.has(name);
+}
+
+void FormDecoder::operator()(const string& name, long long& field)
+{
+    const auto v = values.get(name);
+    if (!v) {
+        return;
+    }
+    char* end;
+    const auto n = strtoll(v->c_str(), &end, 10);
+    if (v->empty() || *end) {
+        errors.push_back(name);
+        return;
+    }
+    field = n;
+}
+
+void FormDecoder::operator()(const string& name, double& field)
+{
+    const auto v = values.get(name);
+    if (!v) {
+        return;
+    }
+    char* end;
+    const auto n = strtod(v->c_str(), &end);
+    if (v->empty() || *end) {
+        errors.push_back(name);
+        return;
+    }
+    field = n;
+}
+
+void FormDecoder::operator()(const string& name, File& field)
+{
+    for (auto & [ k, f ] : values.files) {
+        if (k == name) {
+            field = f;
+            return;
+        }
+    }
+}
+}
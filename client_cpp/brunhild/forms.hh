@@ -0,0 +1,175 @@
+#pragma once
+
+#include "file.hh"
+#include <emscripten/val.h>
+#include <limits>
+#include <optional>
+#include <string>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+namespace brunhild {
+
+// Current values of the controls in a form, as they would be submitted
+class FormValues {
+public:
+    // Text values by control name in document order
+    std::vector<std::pair<std::string, std::string>> entries;
+
+    // Files selected in file inputs by control name in document order
+    std::vector<std::pair<std::string, File>> files;
+
+    // Returns the first value of a control
+    std::optional<std::string> get(const std::string& name) const;
+
+    // Returns all values of a control, like of a <select multiple> or of
+    // checkboxes sharing a name
+    std::vector<std::string> get_all(const std::string& name) const;
+
+    // Returns all files selected in file inputs of a name
+    std::vector<File> get_files(const std::string& name) const;
+
+    // Returns, if the form has any value of a control
+    bool has(const std::string& name) const;
+};
+
+// Collect the current values of all named controls in the subtree of an
+// element by ID, following the rules of HTML form submission: disabled
+// controls, unchecked checkboxes and radio buttons and buttons are skipped.
+// The element does not need to be a <form>. Returns empty values, if the
+// element does not exist.
+FormValues collect_form(const std::string& id);
+
+// Returns a FormData object with the values of the controls in the subtree of
+// an element by ID for multipart submission, like with upload(). Returns an
+// empty FormData, if the element does not exist.
+emscripten::val form_data(const std::string& id);
+
+// Decodes FormValues into the fields of a struct. Structs declare their
+// fields by defining a member function template, that passes each field to
+// the decoder with its control name:
+//
+//     struct Reply {
+//         std::string body;
+//         bool sage;
+//         std::optional<unsigned> thread;
+//         std::vector<File> images;
+//
+//         template <class D> void fields(D& d)
+//         {
+//             d("body", body);
+//             d("sage", sage);
+//             d("thread", thread);
+//             d("images", images);
+//         }
+//     };
+//
+// Supported field types are std::string, bool, integers, floating point
+// numbers, File, std::optional and std::vector of these.
+// bool fields are true, if the control has any value, like a checked
+// checkbox. Fields of absent controls are left unchanged, except for bool
+// fields, which are set to false, and std::optional fields, which are reset.
+class FormDecoder {
+public:
+    // Names of controls, that failed to decode, like a non-numeric value for
+    // an integer field
+    std::vector<std::string> errors;
+
+    FormDecoder(const FormValues& values)
+        : values(values)
+    {
+    }
+
+    void operator()(const std::string& name, std::string& field);
+    void operator()(const std::string& name, bool& field);
+    void operator()(const std::string& name, long long& field);
+    void operator()(const std::string& name, double& field);
+    void operator()(const std::string& name, File& field);
+
+    // Integer types other than long long
+    template <class T>
+    std::enable_if_t<std::is_integral_v<T> && !std::is_same_v<T, bool>
+        && !std::is_same_v<T, long long>>
+    operator()(const std::string& name, T& field)
+    {
+        long long v = field;
+        (*this)(name, v);
+        const bool in_range = v >= 0
+            ? (unsigned long long)v
+                <= (unsigned long long)std::numeric_limits<T>::max()
+            : std::is_signed_v<T>
+                && v >= (long long)std::numeric_limits<T>::min();
+        if (!in_range) {
+            errors.push_back(name);
+            return;
+        }
+        field = T(v);
+    }
+
+    void operator()(const std::string& name, float& field)
+    {
+        double v = field;
+        (*this)(name, v);
+        field = float(v);
+    }
+
+    template <class T>
+    void operator()(const std::string& name, std::optional<T>& field)
+    {
+        const auto v = values.get(name);
+        if ((!v || *v == "") && !has_file(name)) {
+            field = std::nullopt;
+            return;
+        }
+        T inner{};
+        (*this)(name, inner);
+        field = std::move(inner);
+    }
+
+    void operator()(const std::string& name, std::vector<File>& field)
+    {
+        field = values.get_files(name);
+    }
+
+    template <class T>
+    void operator()(const std::string& name, std::vector<T>& field)
+    {
+        field.clear();
+        for (auto& v : values.get_all(name)) {
+            FormValues single;
+            single.entries.push_back({ name, v });
+            FormDecoder d(single);
+            T item{};
+            d(name, item);
+            if (d.errors.size()) {
+                errors.push_back(name);
+                return;
+            }
+            field.push_back(std::move(item));
+        }
+    }
+
+private:
+    const FormValues& values;
+
+    bool has_file(const std::string& name) const;
+};
+
+// Decode FormValues into a struct defining fields(). Returns the names of any
+// controls, that failed to decode.
+template <class T>
+std::vector<std::string> decode_form(const FormValues& values, T& out)
+{
+    FormDecoder d(values);
+    out.fields(d);
+    return d.errors;
+}
+
+// Shorthand for collecting and decoding the controls of an element by ID
+template <class T>
+std::vector<std::string> decode_form(const std::string& id, T& out)
+{
+    return decode_form(collect_form(id), out);
+}
+}
@@ -0,0 +1,163 @@
+#include "fullscreen.hh"
+#include "promise.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// Registered handlers by ID
+static std::map<long, std::function<void(std::optional<string>)>> handlers;
+
+// Returns the JS fullscreen helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_fullscreen) {
+            return;
+        }
+
+        // Call the first defined method of obj
+        var call = function(obj, names)
+        {
+            for (var i = 0; i < names.length; i++) {
+                if (obj[names[i]]) {
+                    return obj[names[i]]();
+                }
+            }
+            throw new Error("fullscreen not supported");
+        };
+
+        // Wrap legacy non-promise implementations in a promise
+        var wrap = function(fn)
+        {
+            try {
+                return Promise.resolve(fn());
+            } catch (e) {
+                return Promise.reject(e);
+            }
+        };
+
+        window.__bh_fullscreen = {
+            request : function(id)
+            {
+                return wrap(function() {
+                    var el = window.__bh_get(id);
+                    if (!el) {
+                        throw new Error("element not found: " + id);
+                    }
+                    return call(el, [
+                        "requestFullscreen", "webkitRequestFullscreen",
+                        "mozRequestFullScreen", "msRequestFullscreen"
+                    ]);
+                });
+            },
+            exit : function()
+            {
+                return wrap(function() {
+                    return call(document, [
+                        "exitFullscreen", "webkitExitFullscreen",
+                        "mozCancelFullScreen", "msExitFullscreen"
+                    ]);
+                });
+            },
+            enabled : function()
+            {
+                return !!(document.fullscreenEnabled
+                    || document.webkitFullscreenEnabled
+                    || document.mozFullScreenEnabled
+                    || document.msFullscreenEnabled);
+            },
+            element : function()
+            {
+                return document.fullscreenElement
+                    || document.webkitFullscreenElement
+                    || document.mozFullScreenElement
+                    || document.msFullscreenElement || null;
+            },
+            listen : function()
+            {
+                // Some browsers fire both prefixed and unprefixed events, so
+                // only listen to the first supported one
+                var types = [
+                    "fullscreenchange", "webkitfullscreenchange",
+                    "mozfullscreenchange", "MSFullscreenChange"
+                ];
+                for (var i = 0; i < types.length; i++) {
+                    if ("on" + types[i].toLowerCase() in document) {
+                        break;
+                    }
+                }
+                document.addEventListener(types[i] || types[0], function() {
+                    Module._bh_fullscreen_changed();
+                });
+            }
+        };
+    });
+    return val::global("__bh_fullscreen");
+}
+
+static Settle on_settled(FullscreenCallback cb)
+{
+    return [cb](bool ok, val) {
+        if (cb) {
+            cb(ok);
+        }
+    };
+}
+
+void request_fullscreen(const string& id, FullscreenCallback cb)
+{
+    await(helpers().call<val>("request", id), on_settled(cb));
+}
+
+void exit_fullscreen(FullscreenCallback cb)
+{
+    await(helpers().call<val>("exit"), on_settled(cb));
+}
+
+bool fullscreen_enabled() { return helpers().call<bool>("enabled"); }
+
+std::optional<string> fullscreen_element()
+{
+    auto el = helpers().call<val>("element");
+    if (el.isNull()) {
+        return std::nullopt;
+    }
+    return el["id"].as<string>();
+}
+
+long on_fullscreen_change(
+    std::function<void(std::optional<string>)> handler)
+{
+    static bool listening = false;
+    if (!listening) {
+        listening = true;
+        helpers().call<void>("listen");
+    }
+    const long id = id_counter++;
+    handlers[id] = handler;
+    return id;
+}
+
+void remove_fullscreen_handler(long id) { handlers.erase(id); }
+
+static void fullscreen_changed()
+{
+    const auto el = fullscreen_element();
+    auto copy = handlers; // Handler might invalidate this iterator
+    for (auto & [ _, h ] : copy) {
+        h(el);
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_fullscreen)
+{
+    emscripten::function("_bh_fullscreen_changed", &fullscreen_changed);
+}
+}
@@ -0,0 +1,36 @@
+#pragma once
+
+#include <functional>
+#include <optional>
+#include <string>
+
+// Fullscreen API integration. Handles vendor-prefixed implementations.
+// Entering fullscreen requires a user gesture, so call request_fullscreen()
+// from event handlers.
+namespace brunhild {
+
+// Receives, if the request succeeded
+typedef std::function<void(bool)> FullscreenCallback;
+
+// Display an element by ID in fullscreen mode. cb is optional.
+void request_fullscreen(const std::string& id, FullscreenCallback cb = nullptr);
+
+// Exit fullscreen mode. cb is optional.
+void exit_fullscreen(FullscreenCallback cb = nullptr);
+
+// Returns, if the browser supports and allows fullscreen mode
+bool fullscreen_enabled();
+
+// Returns the ID of the current fullscreen element or std::nullopt, if not in
+// fullscreen mode. The ID is empty, if the element has no ID.
+std::optional<std::string> fullscreen_element();
+
+// Register a handler called with the ID of the new fullscreen element or
+// std::nullopt, whenever fullscreen mode is entered, switched or exited.
+// Returns handler ID.
+long on_fullscreen_change(
+    std::function<void(std::optional<std::string>)> handler);
+
+// Remove a fullscreen change handler by ID
+void remove_fullscreen_handler(long id);
+}
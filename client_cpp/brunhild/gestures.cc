@@ -0,0 +1,170 @@
+#include "gestures.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All active recognizers by ID
+static std::unordered_map<long, Gestures*> recognizers;
+
+Gestures::Gestures(string selector, std::function<void(const Gesture&)> handler,
+    GestureOptions opts)
+    : selector(selector)
+    , recognizer_id(id_counter++)
+    , handler(handler)
+{
+    recognizers[recognizer_id] = this;
+    EM_ASM_INT(
+        {
+            if (!window.__bh_gestures) {
+                window.__bh_gestures = {};
+            }
+            var id = $0;
+            var sel = UTF8ToString($1);
+            var o = {
+                tap_distance : $2,
+                tap_duration : $3,
+                swipe_distance : $4,
+                swipe_velocity : $5
+            };
+            var s = null; // State of the current gesture
+            var r = { listeners : {} };
+
+            // type: 0 - tap, 1 - swipe, 2 - pinch
+            var emit = function(type, x, y, dir, dist, vel, scale, ended)
+            {
+                Module._bh_gesture(
+                    id, type, s.target, x, y, dir, dist, vel, scale, ended);
+            };
+            var spread = function(t)
+            {
+                var dx = t[0].clientX - t[1].clientX;
+                var dy = t[0].clientY - t[1].clientY;
+                return Math.sqrt(dx * dx + dy * dy);
+            };
+
+            r.listeners.touchstart = function(e)
+            {
+                var t = e.composedPath ? e.composedPath()[0] : e.target;
+                var el = t && t.closest ? t.closest(sel) : null;
+                if (!el) {
+                    s = null;
+                    return;
+                }
+                var touch = e.touches[0];
+                if (e.touches.length == 1) {
+                    s = {
+                        target : el.id || "",
+                        x : touch.clientX,
+                        y : touch.clientY,
+                        start : Date.now(),
+                        moved : 0,
+                        pinch : 0
+                    };
+                } else if (s && e.touches.length == 2) {
+                    s.pinch = spread(e.touches);
+                    s.scale = 1;
+                }
+            };
+            r.listeners.touchmove = function(e)
+            {
+                if (!s) {
+                    return;
+                }
+                var t = e.touches;
+                if (s.pinch && t.length == 2) {
+                    s.scale = spread(t) / s.pinch;
+                    emit(2, (t[0].clientX + t[1].clientX) / 2,
+                        (t[0].clientY + t[1].clientY) / 2, 0, 0, 0, s.scale,
+                        false);
+                    return;
+                }
+                var dx = t[0].clientX - s.x;
+                var dy = t[0].clientY - s.y;
+                s.moved = Math.max(s.moved, Math.sqrt(dx * dx + dy * dy));
+            };
+            r.listeners.touchend = function(e)
+            {
+                if (!s) {
+                    return;
+                }
+                if (s.pinch) {
+                    emit(2, s.x, s.y, 0, 0, 0, s.scale, true);
+                    s = null;
+                    return;
+                }
+                if (e.touches.length) {
+                    return;
+                }
+                var touch = e.changedTouches[0];
+                var dx = touch.clientX - s.x;
+                var dy = touch.clientY - s.y;
+                var dist = Math.sqrt(dx * dx + dy * dy);
+                var dt = Math.max(Date.now() - s.start, 1);
+                if (dist >= o.swipe_distance && dist / dt >= o.swipe_velocity) {
+                    // 0 - left, 1 - right, 2 - up, 3 - down
+                    var dir = Math.abs(dx) > Math.abs(dy) ? (dx < 0 ? 0 : 1)
+                                                          : (dy < 0 ? 2 : 3);
+                    emit(1, s.x, s.y, dir, dist, dist / dt, 1, true);
+                } else if (s.moved <= o.tap_distance
+                    && dt <= o.tap_duration) {
+                    emit(0, touch.clientX, touch.clientY, 0, 0, 0, 1, true);
+                }
+                s = null;
+            };
+            r.listeners.touchcancel = function() { s = null; };
+
+            for (var type in r.listeners) {
+                document.addEventListener(
+                    type, r.listeners[type], { passive : true });
+            }
+            window.__bh_gestures[id] = r;
+        },
+        recognizer_id, selector.c_str(), opts.tap_distance, opts.tap_duration,
+        opts.swipe_distance, opts.swipe_velocity);
+}
+
+Gestures::~Gestures()
+{
+    recognizers.erase(recognizer_id);
+    EM_ASM_INT(
+        {
+            var r = window.__bh_gestures[$0];
+            for (var type in r.listeners) {
+                document.removeEventListener(
+                    type, r.listeners[type], { passive : true });
+            }
+            delete window.__bh_gestures[$0];
+        },
+        recognizer_id);
+}
+
+void run_gesture_handler(long id, int type, string target, double x, double y,
+    int direction, double distance, double velocity, double scale, bool ended)
+{
+    if (!recognizers.count(id)) {
+        return;
+    }
+    recognizers.at(id)->handler({
+        static_cast<GestureType>(type),
+        target,
+        x,
+        y,
+        static_cast<SwipeDirection>(direction),
+        distance,
+        velocity,
+        scale,
+        ended,
+    });
+}
+
+EMSCRIPTEN_BINDINGS(module_gestures)
+{
+    emscripten::function("_bh_gesture", &run_gesture_handler);
+}
+}
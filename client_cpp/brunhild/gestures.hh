@@ -0,0 +1,72 @@
+#pragma once
+
+#include <functional>
+#include <string>
+
+namespace brunhild {
+
+// Kind of a recognized touch gesture
+enum class GestureType { tap, swipe, pinch };
+
+// Direction of a swipe
+enum class SwipeDirection { left, right, up, down };
+
+// High-level touch gesture
+struct Gesture {
+    GestureType type;
+
+    // ID of the element matching the recognizer's selector, the gesture
+    // started on. Empty, if it has no ID.
+    std::string target;
+
+    // Position of the tap, swipe start or pinch center relative to the
+    // viewport
+    double x, y;
+
+    // Swipe direction
+    SwipeDirection direction;
+
+    // Swipe distance in CSS pixels and velocity in CSS pixels per millisecond
+    double distance, velocity;
+
+    // Pinch scale relative to the initial finger distance
+    double scale;
+
+    // Pinch gestures are reported continuously. Set on the last report, when
+    // a finger is lifted.
+    bool ended;
+};
+
+// Thresholds of gesture recognition
+struct GestureOptions {
+    // Maximum movement in CSS pixels and duration in milliseconds of a tap
+    double tap_distance = 10, tap_duration = 250;
+
+    // Minimum distance in CSS pixels and velocity in CSS pixels per
+    // millisecond of a swipe
+    double swipe_distance = 50, swipe_velocity = 0.3;
+};
+
+// Recognizes gestures on elements matching a CSS selector for its lifetime
+// from touch events and delivers them to a handler. Raw touch events can be
+// handled with register_handler() as usual.
+class Gestures {
+public:
+    // CSS selector of elements to recognize gestures on
+    const std::string selector;
+
+    Gestures(std::string selector, std::function<void(const Gesture&)> handler,
+        GestureOptions opts = {});
+    ~Gestures();
+
+    Gestures(const Gestures&) = delete;
+    Gestures& operator=(const Gestures&) = delete;
+
+private:
+    const long recognizer_id;
+    const std::function<void(const Gesture&)> handler;
+
+    friend void run_gesture_handler(long, int, std::string, double, double,
+        int, double, double, double, bool);
+};
+}
@@ -0,0 +1,210 @@
+#include "gl.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All GlViews with a context by ID
+static std::map<long, GlView*> views;
+
+GlView::GlView(string id, GlOptions opts)
+    : CanvasBase(id)
+    , opts(opts)
+    , view_id(id_counter++)
+{
+}
+
+GlView::~GlView() { release_context(); }
+
+Size GlView::buffer_size() const
+{
+    if (!gl_version) {
+        return { 0, 0 };
+    }
+    return {
+        gl["drawingBufferWidth"].as<double>(),
+        gl["drawingBufferHeight"].as<double>(),
+    };
+}
+
+void GlView::set_animating(bool on)
+{
+    is_animating = on;
+    update_schedule();
+}
+
+void GlView::redraw()
+{
+    redraw_pending = true;
+    update_schedule();
+}
+
+void GlView::update_schedule()
+{
+    if (has_context() && is_mounted() && (is_animating || redraw_pending)) {
+        request_frames();
+    } else {
+        cancel_frames();
+    }
+}
+
+void GlView::mount()
+{
+    VirtualView::mount();
+    create_context();
+}
+
+void GlView::unmount()
+{
+    release_context();
+    VirtualView::unmount();
+}
+
+void GlView::create_context()
+{
+    if (gl_version) {
+        return;
+    }
+
+    val attrs = val::object();
+    attrs.set("alpha", opts.alpha);
+    attrs.set("depth", opts.depth);
+    attrs.set("stencil", opts.stencil);
+    attrs.set("antialias", opts.antialias);
+    attrs.set("premultipliedAlpha", opts.premultiplied_alpha);
+    attrs.set("preserveDrawingBuffer", opts.preserve_drawing_buffer);
+    attrs.set("powerPreference", opts.power_preference);
+
+    EM_ASM({
+        if (window.__bh_gl) {
+            return;
+        }
+        window.__bh_gl = {};
+        window.__bh_gl_create = function(viewID, id, version, attrs)
+        {
+            var el = window.__bh_get(id);
+            if (!el) {
+                return null;
+            }
+            var gl = null;
+            var v = 0;
+            if (version != 1) {
+                gl = el.getContext("webgl2", attrs);
+                v = 2;
+            }
+            if (!gl && version != 2) {
+                gl = el.getContext("webgl", attrs)
+                    || el.getContext("experimental-webgl", attrs);
+                v = 1;
+            }
+            if (!gl) {
+                return null;
+            }
+
+            var onLost = function(e)
+            {
+                // Required for the context to be restored
+                e.preventDefault();
+                Module._bh_gl_context(viewID, true);
+            };
+            var onRestored = function()
+            {
+                Module._bh_gl_context(viewID, false);
+            };
+            el.addEventListener("webglcontextlost", onLost);
+            el.addEventListener("webglcontextrestored", onRestored);
+            window.__bh_gl[viewID] = function()
+            {
+                el.removeEventListener("webglcontextlost", onLost);
+                el.removeEventListener("webglcontextrestored", onRestored);
+            };
+            return { gl : gl, version : v };
+        };
+    });
+
+    // 0 allows both versions
+    int version = 0;
+    switch (opts.version) {
+    case GlVersion::webgl1:
+        version = 1;
+        break;
+    case GlVersion::webgl2:
+        version = 2;
+        break;
+    }
+    auto res = val::global("__bh_gl_create")(view_id, id, version, attrs);
+    if (res.isNull()) {
+        on_unsupported();
+        return;
+    }
+
+    gl = res["gl"];
+    gl_version = res["version"].as<int>();
+    lost = false;
+    views[view_id] = this;
+    init_gl(gl);
+    redraw();
+}
+
+void GlView::release_context()
+{
+    cancel_frames();
+    if (!views.erase(view_id)) {
+        return;
+    }
+    EM_ASM_INT(
+        {
+            var s = window.__bh_gl;
+            if (s && s[$0]) {
+                s[$0]();
+                delete s[$0];
+            }
+        },
+        view_id);
+    gl = val::null();
+    gl_version = 0;
+    lost = false;
+}
+
+void GlView::draw_now(double now)
+{
+    redraw_pending = false;
+    const auto s = buffer_size();
+    gl.call<void>("viewport", 0, 0, s.width, s.height);
+    draw(gl, now);
+}
+
+void run_gl_context_handler(long id, bool lost)
+{
+    if (!views.count(id)) {
+        return;
+    }
+    auto v = views.at(id);
+    v->lost = lost;
+    v->update_schedule();
+    if (lost) {
+        v->on_context_lost();
+    } else {
+        v->init_gl(v->gl);
+        v->on_context_restored();
+        v->redraw();
+    }
+}
+
+void GlView::on_frame(double timestamp, double dt)
+{
+    draw_now(timestamp);
+    update_schedule();
+}
+
+EMSCRIPTEN_BINDINGS(module_gl)
+{
+    emscripten::function("_bh_gl_context", &run_gl_context_handler);
+}
+}
@@ -0,0 +1,106 @@
+#pragma once
+
+#include "canvas.hh"
+#include <emscripten/val.h>
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// WebGL version of a GlView context
+enum class GlVersion {
+    webgl1,
+    webgl2,
+
+    // WebGL 2, falling back to WebGL 1, if unsupported
+    webgl2_or_webgl1,
+};
+
+// Context creation attributes of a GlView. Mirror the WebGLContextAttributes
+// of the same name.
+struct GlOptions {
+    GlVersion version = GlVersion::webgl2_or_webgl1;
+    bool alpha = true, depth = true, stencil = false, antialias = true,
+         premultiplied_alpha = true, preserve_drawing_buffer = false;
+
+    // "default", "high-performance" or "low-power"
+    std::string power_preference = "default";
+};
+
+// View with a <canvas> root element rendered to with a WebGL context.
+// The context is created on mount and passed to all callbacks as the
+// WebGLRenderingContext or WebGL2RenderingContext JS object.
+// Like CanvasView, the backing store is scaled by window.devicePixelRatio.
+class GlView : public CanvasBase {
+public:
+    GlView(std::string id = new_id(), GlOptions opts = {});
+    ~GlView();
+
+    // Returns the size of the drawing buffer in physical pixels
+    Size buffer_size() const;
+
+    // Returns the WebGL version of the created context or 0, if there is no
+    // context
+    int version() const { return gl_version; }
+
+    // Returns, if a usable context exists
+    bool has_context() const { return gl_version && !lost; }
+
+    // Call draw() on every frame, while the view is mounted and has a context.
+    // Frames are drawn from on_frame() before DOM mutations are flushed.
+    void set_animating(bool);
+
+    // Returns, if draw() is called on every frame
+    bool animating() const { return is_animating; }
+
+    // Call draw() once on the next frame
+    void redraw();
+
+protected:
+    // Create GPU resources like shaders and buffers. Called after the context
+    // has been created and again after it has been restored, as all resources
+    // are lost with the context.
+    virtual void init_gl(emscripten::val& gl) {}
+
+    // Draw a frame. The viewport is set to the full drawing buffer before each
+    // call. now is the current time in milliseconds as returned by
+    // performance.now().
+    virtual void draw(emscripten::val& gl, double now) = 0;
+
+    // Called, when the context is lost. No more frames are drawn until the
+    // context is restored.
+    virtual void on_context_lost() {}
+
+    // Called, when the context has been restored and init_gl() has been called
+    virtual void on_context_restored() {}
+
+    // Called, if no WebGL context of the requested version could be created
+    virtual void on_unsupported() {}
+
+    void mount();
+    void unmount();
+    void on_frame(double timestamp, double dt);
+
+private:
+    const GlOptions opts;
+    const long view_id;
+    int gl_version = 0;
+    emscripten::val gl = emscripten::val::null();
+
+    bool lost = false, is_animating = false, redraw_pending = false;
+
+    // Create the context and attach context loss listeners
+    void create_context();
+
+    // Drop the context and detach listeners
+    void release_context();
+
+    // Set the viewport and call draw()
+    void draw_now(double now);
+
+    // Request or cancel frames depending on the need to draw
+    void update_schedule();
+
+    friend void run_gl_context_handler(long, bool);
+};
+}
@@ -0,0 +1,156 @@
+#include "head.hh"
+#include <emscripten.h>
+#include <optional>
+#include <stdlib.h>
+#include <vector>
+
+using std::string;
+
+namespace brunhild::head {
+
+static unsigned scope_counter = 0;
+
+// Titles set by scopes in scope creation order
+static std::vector<std::pair<unsigned, string>> titles;
+
+// Title of the document before any scope or prefix has set it
+static std::optional<string> original_title;
+
+// Prefix of the displayed title
+static string title_prefix;
+
+// Save the document title before it is first modified
+static void save_original_title()
+{
+    if (original_title) {
+        return;
+    }
+    char* s = (char*)EM_ASM_INT({
+        var len = lengthBytesUTF8(document.title) + 1;
+        var buf = Module._malloc(len);
+        stringToUTF8(document.title, buf, len);
+        return buf;
+    });
+    original_title = string(s);
+    free(s);
+}
+
+// Display the prefixed title of the most recent scope or the original title
+static void apply_title()
+{
+    string title;
+    if (titles.size()) {
+        title = titles.back().second;
+    } else if (original_title) {
+        title = *original_title;
+    } else {
+        return;
+    }
+    title = title_prefix + title;
+    EM_ASM_INT({ document.title = UTF8ToString($0); }, title.c_str());
+}
+
+void set_title_prefix(const string& prefix)
+{
+    save_original_title();
+    title_prefix = prefix;
+    apply_title();
+}
+
+Scope::Scope()
+    : scope_id(scope_counter++)
+{
+}
+
+Scope::~Scope() { clear(); }
+
+void Scope::set_title(const string& title)
+{
+    save_original_title();
+
+    bool found = false;
+    for (auto & [ id, t ] : titles) {
+        if (id == scope_id) {
+            t = title;
+            found = true;
+            break;
+        }
+    }
+    if (!found) {
+        // Keep titles ordered by scope creation
+        auto it = titles.begin();
+        while (it != titles.end() && it->first < scope_id) {
+            it++;
+        }
+        titles.insert(it, { scope_id, title });
+    }
+    apply_title();
+}
+
+void Scope::set_meta(const string& name, const string& content)
+{
+    set("meta:" + name,
+        Node("meta", { { "name", name }, { "content", content } }));
+}
+
+void Scope::set_link(const string& rel, const string& href, Attrs attrs)
+{
+    attrs["rel"] = rel;
+    attrs["href"] = href;
+    set("link:" + rel, Node("link", attrs));
+}
+
+void Scope::remove_meta(const string& name) { remove("meta:" + name); }
+
+void Scope::remove_link(const string& rel) { remove("link:" + rel); }
+
+void Scope::set(const string& key, Node node)
+{
+    if (!elements.count(key)) {
+        elements[key] = new_id();
+    }
+    node.attrs["id"] = elements.at(key);
+    EM_ASM_INT(
+        {
+            var old = document.getElementById(UTF8ToString($0));
+            var html = UTF8ToString($1);
+            if (old) {
+                old.outerHTML = html;
+            } else {
+                document.head.insertAdjacentHTML("beforeend", html);
+            }
+        },
+        node.attrs.at("id").c_str(), node.html().c_str());
+}
+
+void Scope::remove(const string& key)
+{
+    if (!elements.count(key)) {
+        return;
+    }
+    EM_ASM_INT(
+        {
+            var el = document.getElementById(UTF8ToString($0));
+            if (el) {
+                el.remove();
+            }
+        },
+        elements.at(key).c_str());
+    elements.erase(key);
+}
+
+void Scope::clear()
+{
+    while (elements.size()) {
+        remove(elements.begin()->first);
+    }
+
+    for (auto it = titles.begin(); it != titles.end(); it++) {
+        if (it->first == scope_id) {
+            titles.erase(it);
+            apply_title();
+            break;
+        }
+    }
+}
+}
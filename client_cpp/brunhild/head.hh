@@ -0,0 +1,59 @@
+#pragma once
+
+#include "node.hh"
+#include <string>
+#include <unordered_map>
+
+// Declarative management of the document's <title>, <meta> and <link>
+// elements. Changes are applied to the DOM immediately.
+namespace brunhild::head {
+
+// Prepend a prefix, like an unread count, to the displayed title of any scope
+// or the original title. Empty for none.
+void set_title_prefix(const std::string& prefix);
+
+// Set of head elements and title owned by one part of the application, like a
+// route's view. Multiple scopes can coexist. The title of the most recently
+// created scope, that has a title set, is displayed. All owned elements are
+// removed and the title reverts, when the scope is destroyed.
+class Scope {
+public:
+    Scope();
+    ~Scope();
+
+    Scope(const Scope&) = delete;
+    Scope& operator=(const Scope&) = delete;
+
+    // Set the document title for as long as the scope exists
+    void set_title(const std::string& title);
+
+    // Set or update a <meta name="..." content="..."> element
+    void set_meta(const std::string& name, const std::string& content);
+
+    // Set or update a <link rel="..." href="..."> element. Only one link per
+    // rel is owned by a scope. attrs specifies any extra attributes.
+    void set_link(
+        const std::string& rel, const std::string& href, Attrs attrs = {});
+
+    // Remove a <meta> element set with set_meta()
+    void remove_meta(const std::string& name);
+
+    // Remove a <link> element set with set_link()
+    void remove_link(const std::string& rel);
+
+    // Remove all owned elements and the title
+    void clear();
+
+private:
+    const unsigned scope_id;
+
+    // IDs of owned elements by "meta:<name>" or "link:<rel>" keys
+    std::unordered_map<std::string, std::string> elements;
+
+    // Insert or replace an owned element
+    void set(const std::string& key, Node node);
+
+    // Remove an owned element
+    void remove(const std::string& key);
+};
+}
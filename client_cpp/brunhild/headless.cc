@@ -0,0 +1,484 @@
+#include "headless.hh"
+
+#ifdef BRUNHILD_HEADLESS
+
+#include "error.hh"
+#include "util.hh"
+#include <cctype>
+#include <set>
+#include <unordered_set>
+
+using std::string;
+using std::unique_ptr;
+
+namespace brunhild::headless {
+
+// Elements, that have no closing tag
+static const std::unordered_set<string> void_elements = {
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta",
+    "param", "source", "track", "wbr",
+};
+
+// Elements, whose contents are not parsed as HTML
+static const std::unordered_set<string> raw_text_elements = {
+    "script", "style", "textarea", "title",
+};
+
+static Element document_body = [] {
+    Element el;
+    el.tag = "body";
+    return el;
+}();
+
+static std::vector<Operation> applied;
+
+// Decode the entities produced by escape() and their common aliases
+static string decode_entities(const string& text)
+{
+    static const std::pair<const char*, char> entities[] = {
+        { "&amp;", '&' }, { "&lt;", '<' }, { "&gt;", '>' }, { "&#34;", '"' },
+        { "&quot;", '"' }, { "&#39;", '\'' }, { "&apos;", '\'' },
+    };
+
+    string s;
+    s.reserve(text.size());
+    for (size_t i = 0; i < text.size(); i++) {
+        bool matched = false;
+        if (text[i] == '&') {
+            for (auto[ent, ch] : entities) {
+                if (text.compare(i, strlen(ent), ent) == 0) {
+                    s += ch;
+                    i += strlen(ent) - 1;
+                    matched = true;
+                    break;
+                }
+            }
+        }
+        if (!matched) {
+            s += text[i];
+        }
+    }
+    return s;
+}
+
+// Simple non-validating HTML parser
+class Parser {
+public:
+    Parser(const string& html)
+        : s(html)
+    {
+    }
+
+    std::vector<unique_ptr<Element>> parse()
+    {
+        Element root;
+        std::vector<Element*> stack = { &root };
+
+        while (i < s.size()) {
+            if (s[i] != '<') {
+                text(*stack.back());
+            } else if (s.compare(i, 4, "<!--") == 0) {
+                const auto end = s.find("-->", i + 4);
+                i = end == string::npos ? s.size() : end + 3;
+            } else if (i + 1 < s.size() && s[i + 1] == '/') {
+                close_tag(stack);
+            } else if (i + 1 < s.size() && isalpha((unsigned char)s[i + 1])) {
+                open_tag(stack);
+            } else {
+                text(*stack.back());
+            }
+        }
+
+        for (auto& ch : root.children) {
+            ch->parent = nullptr;
+        }
+        return std::move(root.children);
+    }
+
+private:
+    const string& s;
+    size_t i = 0;
+
+    void append(Element& parent, unique_ptr<Element> el)
+    {
+        el->parent = &parent;
+        parent.children.push_back(std::move(el));
+    }
+
+    void text(Element& parent)
+    {
+        const auto start = i++;
+        while (i < s.size() && s[i] != '<') {
+            i++;
+        }
+        // Merge adjacent text
+        if (parent.children.size() && parent.children.back()->tag.empty()) {
+            parent.children.back()->text += s.substr(start, i - start);
+            return;
+        }
+        auto el = std::make_unique<Element>();
+        el->text = s.substr(start, i - start);
+        append(parent, std::move(el));
+    }
+
+    string read_name()
+    {
+        const auto start = i;
+        while (i < s.size() && !isspace((unsigned char)s[i]) && s[i] != '>' && s[i] != '/'
+            && s[i] != '=') {
+            i++;
+        }
+        string name = s.substr(start, i - start);
+        for (auto& ch : name) {
+            ch = tolower((unsigned char)ch);
+        }
+        return name;
+    }
+
+    void skip_space()
+    {
+        while (i < s.size() && isspace((unsigned char)s[i])) {
+            i++;
+        }
+    }
+
+    void open_tag(std::vector<Element*>& stack)
+    {
+        i++; // '<'
+        auto el = std::make_unique<Element>();
+        el->tag = read_name();
+
+        while (1) {
+            skip_space();
+            if (i >= s.size()) {
+                break;
+            }
+            if (s[i] == '>') {
+                i++;
+                break;
+            }
+            if (s[i] == '/') {
+                i++;
+                continue;
+            }
+
+            const auto key = read_name();
+            if (key.empty()) {
+                i++;
+                continue;
+            }
+            skip_space();
+            string val;
+            if (i < s.size() && s[i] == '=') {
+                i++;
+                skip_space();
+                if (i < s.size() && (s[i] == '"' || s[i] == '\'')) {
+                    const char quote = s[i++];
+                    const auto end = s.find(quote, i);
+                    val = s.substr(i, end - i);
+                    i = end == string::npos ? s.size() : end + 1;
+                } else {
+                    const auto start = i;
+                    while (i < s.size() && !isspace((unsigned char)s[i]) && s[i] != '>') {
+                        i++;
+                    }
+                    val = s.substr(start, i - start);
+                }
+            }
+            el->attrs[key] = decode_entities(val);
+        }
+
+        auto ptr = el.get();
+        append(*stack.back(), std::move(el));
+        if (void_elements.count(ptr->tag)) {
+            return;
+        }
+        if (raw_text_elements.count(ptr->tag)) {
+            const auto end = s.find("</" + ptr->tag, i);
+            if (end != i) {
+                auto text = std::make_unique<Element>();
+                text->text = s.substr(i, end - i);
+                append(*ptr, std::move(text));
+            }
+            i = end == string::npos ? s.size() : end;
+        }
+        stack.push_back(ptr);
+    }
+
+    void close_tag(std::vector<Element*>& stack)
+    {
+        i += 2; // "</"
+        const auto tag = read_name();
+        const auto end = s.find('>', i);
+        i = end == string::npos ? s.size() : end + 1;
+
+        // Pop up to the matching open element. Ignore stray closing tags.
+        for (size_t j = stack.size() - 1; j > 0; j--) {
+            if (stack[j]->tag == tag) {
+                stack.resize(j);
+                return;
+            }
+        }
+    }
+};
+
+std::vector<unique_ptr<Element>> parse(const string& html)
+{
+    return Parser(html).parse();
+}
+
+string Element::outer_html() const
+{
+    if (tag.empty()) {
+        return text;
+    }
+
+    Rope s;
+    s << '<' << tag;
+    for (auto & [ key, val ] : attrs) {
+        s << ' ' << key;
+        if (val != "") {
+            s << "=\"";
+            write_escaped(s, val) << '"';
+        }
+    }
+    s << '>';
+    if (void_elements.count(tag)) {
+        return s.str();
+    }
+    s << inner_html() << "</" << tag << '>';
+    return s.str();
+}
+
+string Element::inner_html() const
+{
+    string s;
+    for (auto& ch : children) {
+        s += ch->outer_html();
+    }
+    return s;
+}
+
+string Element::text_content() const
+{
+    if (tag.empty()) {
+        return decode_entities(text);
+    }
+
+    string s;
+    for (auto& ch : children) {
+        s += ch->text_content();
+    }
+    return s;
+}
+
+Element* Element::find(const string& id)
+{
+    if (attrs.count("id") && attrs.at("id") == id) {
+        return this;
+    }
+    for (auto& ch : children) {
+        if (auto el = ch->find(id)) {
+            return el;
+        }
+    }
+    return nullptr;
+}
+
+size_t Element::index_of(const Element* child) const
+{
+    for (size_t i = 0; i < children.size(); i++) {
+        if (children[i].get() == child) {
+            return i;
+        }
+    }
+    return children.size();
+}
+
+unique_ptr<Element> Element::detach()
+{
+    auto p = parent;
+    const auto i = p->index_of(this);
+    auto owned = std::move(p->children[i]);
+    p->children.erase(p->children.begin() + i);
+    parent = nullptr;
+    return owned;
+}
+
+Element& body() { return document_body; }
+
+void reset(const string& html)
+{
+    document_body.children = parse(html);
+    for (auto& ch : document_body.children) {
+        ch->parent = &document_body;
+    }
+    applied.clear();
+}
+
+Element* get(const string& id) { return document_body.find(id); }
+
+const std::vector<Operation>& operations() { return applied; }
+
+void clear_operations() { applied.clear(); }
+
+// Parse HTML and return only the first node, like the browser implementation
+static unique_ptr<Element> parse_first(const string& html)
+{
+    auto nodes = parse(html);
+    if (nodes.empty()) {
+        return nullptr;
+    }
+    return std::move(nodes[0]);
+}
+
+// Insert a node into a parent at a position
+static void insert(Element& parent, size_t i, unique_ptr<Element> el)
+{
+    if (!el) {
+        return;
+    }
+    el->parent = &parent;
+    parent.children.insert(parent.children.begin() + i, std::move(el));
+}
+
+static void set_children(Element& el, const string& html)
+{
+    el.children = parse(html);
+    for (auto& ch : el.children) {
+        ch->parent = &el;
+    }
+}
+
+// Split a class attribute into a set of classes
+static std::set<string> split_classes(const string& s)
+{
+    std::set<string> classes;
+    size_t i = 0;
+    while (i < s.size()) {
+        const auto end = s.find(' ', i);
+        const auto cls = s.substr(i, end - i);
+        if (cls != "") {
+            classes.insert(cls);
+        }
+        if (end == string::npos) {
+            break;
+        }
+        i = end + 1;
+    }
+    return classes;
+}
+
+static string join_classes(const std::set<string>& classes)
+{
+    string s;
+    for (auto& cls : classes) {
+        if (s.size()) {
+            s += ' ';
+        }
+        s += cls;
+    }
+    return s;
+}
+
+bool apply(const Operation& op)
+{
+    auto el = get(op.id);
+    if (!el) {
+        return false;
+    }
+    applied.push_back(op);
+    const auto& t = op.type;
+
+    if (t == "before" || t == "after") {
+        auto p = el->parent;
+        if (p) {
+            insert(*p, p->index_of(el) + (t == "after"), parse_first(op.value));
+        }
+    } else if (t == "remove") {
+        if (el->parent) {
+            el->detach();
+        }
+    } else if (t == "set_outer_html") {
+        auto p = el->parent;
+        if (p) {
+            const auto i = p->index_of(el);
+            el->detach();
+            auto nodes = parse(op.value);
+            for (size_t j = 0; j < nodes.size(); j++) {
+                insert(*p, i + j, std::move(nodes[j]));
+            }
+        }
+    } else if (t == "set_inner_html") {
+        set_children(*el, op.value);
+    } else if (t == "append") {
+        insert(*el, el->children.size(), parse_first(op.value));
+    } else if (t == "prepend") {
+        insert(*el, 0, parse_first(op.value));
+    } else if (t == "move_prepend" || t == "move_after") {
+        auto child = get(op.value);
+        if (child && child->parent && child != el) {
+            auto owned = child->detach();
+            if (t == "move_prepend") {
+                insert(*el, 0, std::move(owned));
+            } else if (el->parent) {
+                insert(*el->parent, el->parent->index_of(el) + 1,
+                    std::move(owned));
+            }
+        }
+    } else if (t == "set_attr") {
+        el->attrs[op.key] = op.value;
+    } else if (t == "remove_attr") {
+        el->attrs.erase(op.key);
+    } else if (t == "add_class" || t == "remove_class") {
+        auto classes = split_classes(el->attrs["class"]);
+        classes.erase(op.value);
+        if (t == "add_class") {
+            classes.insert(op.value);
+        }
+        el->attrs["class"] = join_classes(classes);
+    }
+    // Other operations are only recorded
+
+    return true;
+}
+
+// Throw an assertion error
+[[noreturn]] static void fail(const string& id, const string& msg)
+{
+    throw Error("headless assertion failed: #" + id + ": " + msg, id);
+}
+
+void assert_inner_html(const string& id, const string& expected)
+{
+    auto el = get(id);
+    if (!el) {
+        fail(id, "element not found");
+    }
+    const auto got = el->inner_html();
+    if (got != expected) {
+        fail(id, "expected inner HTML\n" + expected + "\ngot\n" + got);
+    }
+}
+
+void assert_outer_html(const string& id, const string& expected)
+{
+    auto el = get(id);
+    if (!el) {
+        fail(id, "element not found");
+    }
+    const auto got = el->outer_html();
+    if (got != expected) {
+        fail(id, "expected outer HTML\n" + expected + "\ngot\n" + got);
+    }
+}
+
+void assert_missing(const string& id)
+{
+    if (get(id)) {
+        fail(id, "element exists");
+    }
+}
+}
+
+#endif
@@ -0,0 +1,107 @@
+#pragma once
+
+// In-memory DOM backend for testing rendering and diffing without a browser.
+// Enabled by compiling with BRUNHILD_HEADLESS defined. All DOM mutations are
+// then applied to an in-memory document instead of being executed in JS and
+// recorded for later inspection. The host-compiled tests in client_cpp/test
+// use it and are run with `make test`.
+
+#ifdef BRUNHILD_HEADLESS
+
+#include <map>
+#include <memory>
+#include <string>
+#include <vector>
+
+namespace brunhild::headless {
+
+// Node in the in-memory document
+struct Element {
+    // Tag of the element. Empty for text nodes.
+    std::string tag;
+
+    // Unescaped attributes of the element. Ordered for deterministic
+    // serialization.
+    std::map<std::string, std::string> attrs;
+
+    // Raw HTML text of a text node
+    std::string text;
+
+    // Parent element. NULL for the document root and detached nodes.
+    Element* parent = nullptr;
+
+    // Child nodes
+    std::vector<std::unique_ptr<Element>> children;
+
+    // Serialize the element and its subtree to HTML
+    std::string outer_html() const;
+
+    // Serialize the element's subtree to HTML
+    std::string inner_html() const;
+
+    // Returns the concatenated text of the node and its subtree with
+    // entities decoded
+    std::string text_content() const;
+
+    // Find an element in the subtree by ID. Returns NULL, if not found.
+    Element* find(const std::string& id);
+
+    // Returns the position of a child node in the children vector
+    size_t index_of(const Element* child) const;
+
+    // Detach the node from its parent and return ownership of it
+    std::unique_ptr<Element> detach();
+};
+
+// DOM mutation applied to the in-memory document
+struct Operation {
+    // Type of the operation. Same as the name of the function in mutations.hh.
+    std::string type;
+
+    // ID of the target element
+    std::string id;
+
+    // Key of the target attribute or property, if any
+    std::string key;
+
+    // Inserted HTML, set attribute value or moved element ID
+    std::string value;
+};
+
+// Parse an HTML string into a list of detached nodes
+std::vector<std::unique_ptr<Element>> parse(const std::string& html);
+
+// Returns the document body
+Element& body();
+
+// Reset the document and set the inner HTML of the body. Also clears the
+// operation log.
+void reset(const std::string& html = "");
+
+// Look up an element by ID. Returns NULL, if not found.
+Element* get(const std::string& id);
+
+// Returns all operations applied since the last reset() or
+// clear_operations()
+const std::vector<Operation>& operations();
+
+// Clear the operation log
+void clear_operations();
+
+// Throw brunhild::Error, if an element does not exist or its inner HTML does
+// not match expected
+void assert_inner_html(const std::string& id, const std::string& expected);
+
+// Throw brunhild::Error, if an element does not exist or its outer HTML does
+// not match expected
+void assert_outer_html(const std::string& id, const std::string& expected);
+
+// Throw brunhild::Error, if an element with the specified ID exists
+void assert_missing(const std::string& id);
+
+// Apply an operation to the document. Called by the mutation buffer on flush.
+// Returns false, if the target element does not exist.
+bool apply(const Operation&);
+}
+
+#endif
@@ -0,0 +1,213 @@
+#include "history.hh"
+#include "js_json.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+#include <optional>
+#include <unordered_map>
+
+using emscripten::val;
+using nlohmann::json;
+using std::string;
+
+namespace brunhild::history {
+
+// Tracked part of the UI state
+struct Tracked {
+    string key;
+    Saver save;
+    Restorer restore;
+};
+
+static std::map<long, Tracked> tracked;
+
+static long id_counter = 0;
+
+// ID of the history entry currently displayed. Empty, until first used.
+static string current;
+
+// Saved state by history entry ID. Needed for entries the browser has
+// already switched away from, as their history.state can no longer be
+// written.
+static std::unordered_map<string, json> snapshots;
+
+// Generate an ID unique across page loads
+static string new_entry_id()
+{
+    static unsigned counter = 0;
+    const auto now = val::global("Date").call<double>("now");
+    return std::to_string((long long)now) + '-' + std::to_string(counter++);
+}
+
+static val history() { return val::global("history"); }
+
+static bool is_object(val v)
+{
+    return !v.isNull() && v.typeOf().as<string>() == "object";
+}
+
+// Returns the entry ID stored in history.state or an empty string
+static string entry_of(val state)
+{
+    if (!is_object(state)) {
+        return "";
+    }
+    auto id = state["__bh_entry"];
+    return id.isString() ? id.as<string>() : "";
+}
+
+// Copy history.state with the keys of patch set
+static val merge_state(val patch)
+{
+    auto state = history()["state"];
+    if (!is_object(state)) {
+        state = val::object();
+    }
+    return val::global("Object").call<val>(
+        "assign", val::object(), state, patch);
+}
+
+// Assign an ID to the current history entry, if it has none
+static string claim_entry()
+{
+    auto id = entry_of(history()["state"]);
+    if (id == "") {
+        id = new_entry_id();
+        auto patch = val::object();
+        patch.set("__bh_entry", id);
+        history().call<void>("replaceState", merge_state(patch), string());
+    }
+    return id;
+}
+
+// Assign the current entry an ID and save state on pagehide, if not yet done
+static void init()
+{
+    if (current != "") {
+        return;
+    }
+    current = claim_entry();
+    EM_ASM({
+        window.addEventListener(
+            "pagehide", function() { Module._bh_history_save(); });
+    });
+}
+
+long track(string key, Saver save, Restorer restore)
+{
+    const long id = id_counter++;
+    tracked[id] = { key, save, restore };
+    return id;
+}
+
+long track(View& view, string key, Saver save, Restorer restore)
+{
+    const long id = track(key, save, restore);
+    view.on_next_unmount([id]() { untrack(id); });
+    return id;
+}
+
+void untrack(long id) { tracked.erase(id); }
+
+long track_window_scroll()
+{
+    return track("@window-scroll",
+        []() {
+            auto w = val::global("window");
+            return json{ w["scrollX"].as<double>(),
+                w["scrollY"].as<double>() };
+        },
+        [](const json& j) {
+            if (j.is_array() && j.size() == 2) {
+                val::global("window").call<void>(
+                    "scrollTo", j[0].get<double>(), j[1].get<double>());
+            }
+        });
+}
+
+long track_scroll(View& view, const string& id)
+{
+    return track(view, "@scroll:" + id,
+        [id]() {
+            auto el = val::global("__bh_get")(id);
+            if (el.isNull()) {
+                return json();
+            }
+            return json{ el["scrollLeft"].as<double>(),
+                el["scrollTop"].as<double>() };
+        },
+        [id](const json& j) {
+            auto el = val::global("__bh_get")(id);
+            if (!el.isNull() && j.is_array() && j.size() == 2) {
+                el.set("scrollLeft", j[0].get<double>());
+                el.set("scrollTop", j[1].get<double>());
+            }
+        });
+}
+
+void save()
+{
+    init();
+    auto snapshot = json::object();
+    for (auto & [ _, t ] : tracked) {
+        auto v = t.save();
+        if (!v.is_null()) {
+            snapshot[t.key] = std::move(v);
+        }
+    }
+
+    // The browser might have already switched entries on popstate
+    if (entry_of(history()["state"]) == current) {
+        auto patch = val::object();
+        patch.set("__bh_ui", to_js(snapshot));
+        history().call<void>("replaceState", merge_state(patch), string());
+    }
+    snapshots[current] = std::move(snapshot);
+}
+
+void push(const string& url)
+{
+    init();
+    current = new_entry_id();
+    auto state = val::object();
+    state.set("__bh_entry", current);
+    history().call<void>("pushState", state, string(), url);
+}
+
+void restore()
+{
+    init();
+    current = claim_entry();
+
+    std::optional<json> snapshot;
+    auto it = snapshots.find(current);
+    if (it != snapshots.end()) {
+        snapshot = it->second;
+    } else {
+        auto state = history()["state"];
+        if (is_object(state) && is_object(state["__bh_ui"])) {
+            snapshot = from_js(state["__bh_ui"]);
+        }
+    }
+    if (!snapshot || !snapshot->is_object()) {
+        return;
+    }
+
+    defer([s = std::move(*snapshot)]() {
+        // Restorers might track or untrack state
+        auto copy = tracked;
+        for (auto & [ _, t ] : copy) {
+            auto it = s.find(t.key);
+            if (it != s.end()) {
+                t.restore(*it);
+            }
+        }
+    });
+}
+
+EMSCRIPTEN_BINDINGS(module_history)
+{
+    emscripten::function("_bh_history_save", &save);
+}
+}
@@ -0,0 +1,62 @@
+#pragma once
+
+#include "view.hh"
+#include <functional>
+#include <nlohmann/json.hpp>
+#include <string>
+
+// Saving of UI state, like scroll offsets, expanded images or form drafts,
+// with history entries and restoring it on back/forward navigation.
+//
+// A router calls save() before rendering the page navigated to, then either
+// push() for new navigations or restore() for back/forward navigation:
+//
+//     brunhild::history::save();
+//     render_page();
+//     if (need_push) {
+//         brunhild::history::push(url);
+//     } else {
+//         brunhild::history::restore();
+//     }
+//
+// State is stored under the "__bh_ui" key of history.state, so it survives
+// page reloads. Any other keys of history.state are preserved.
+namespace brunhild::history {
+
+// Serializes a part of the UI state
+typedef std::function<nlohmann::json()> Saver;
+
+// Restores a part of the UI state saved by a Saver
+typedef std::function<void(const nlohmann::json&)> Restorer;
+
+// Track a part of the UI state under a key unique to the page. Returns
+// tracking ID.
+long track(std::string key, Saver save, Restorer restore);
+
+// Same as above, but stops tracking, when the view is next unmounted
+long track(View& view, std::string key, Saver save, Restorer restore);
+
+// Stop tracking a part of the UI state by ID
+void untrack(long id);
+
+// Track the scroll offset of the window
+long track_window_scroll();
+
+// Track the scroll offset of an element by ID, until the view is next
+// unmounted
+long track_scroll(View& view, const std::string& id);
+
+// Snapshot all tracked state into the current history entry. Must be called
+// before the page is rerendered on navigation, including on popstate, when
+// the browser has already switched entries. Also called automatically on
+// pagehide.
+void save();
+
+// Push a new history entry for a URL
+void push(const std::string& url);
+
+// Restore the tracked state saved with the current history entry, if any.
+// Restorers are called after the next flush, so the restored page's elements
+// are in the DOM.
+void restore();
+}
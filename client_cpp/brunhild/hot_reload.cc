@@ -0,0 +1,95 @@
+#include "hot_reload.hh"
+#include "debug.hh"
+#include "events.hh"
+#include "view.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::hot_reload {
+
+// Registered state slices by key
+static std::unordered_map<string, Save> slices;
+
+void register_state(string key, Save save) { slices[key] = save; }
+
+// Returns the object holding the state saved by the previous module instance
+static val saved()
+{
+    return val::global("window")["__bh_hot_state"];
+}
+
+bool is_hot_reload() { return !saved().isUndefined(); }
+
+std::optional<string> restore_state(const string& key)
+{
+    if (!is_hot_reload()) {
+        return std::nullopt;
+    }
+    auto v = saved()["slices"][key];
+    if (!v.isString()) {
+        return std::nullopt;
+    }
+    return v.as<string>();
+}
+
+std::vector<ViewInfo> saved_views()
+{
+    std::vector<ViewInfo> views;
+    if (!is_hot_reload()) {
+        return views;
+    }
+    auto arr = saved()["views"];
+    const auto len = arr["length"].as<unsigned>();
+    views.reserve(len);
+    for (unsigned i = 0; i < len; i++) {
+        auto v = arr[i];
+        views.push_back({ v["id"].as<string>(), v["type"].as<string>() });
+    }
+    return views;
+}
+
+void prepare_reload()
+{
+    auto state = val::object();
+
+    auto s = val::object();
+    for (auto & [ key, save ] : slices) {
+        s.set(key, save());
+    }
+    state.set("slices", s);
+
+    auto views = val::array();
+    for (auto & [ id, v ] : rendered_views()) {
+        auto info = val::object();
+        info.set("id", id);
+        info.set("type", debug::type_name(typeid(*v)));
+        views.call<void>("push", info);
+    }
+    state.set("views", views);
+
+    val::global("window").set("__bh_hot_state", state);
+
+    remove_all_handlers();
+    emscripten_cancel_main_loop();
+    EM_ASM({
+        window.__bh_shadow_roots = {};
+        delete window.__bh_get;
+        delete window.__bh_report;
+    });
+}
+
+void clear_saved()
+{
+    EM_ASM({ delete window.__bh_hot_state; });
+}
+
+EMSCRIPTEN_BINDINGS(module_hot_reload)
+{
+    emscripten::function("_bh_prepare_reload", &prepare_reload);
+}
+}
@@ -0,0 +1,45 @@
+#pragma once
+
+#include <functional>
+#include <optional>
+#include <string>
+#include <vector>
+
+// Development hot reload support. Preserves registered application state
+// across replacing the running module with a newly compiled one.
+//
+// Before loading the new module, call Module._bh_prepare_reload() from the
+// reload script. This saves all registered state into a JS global surviving the
+// module and tears down the JS-side resources of the old module. On boot, the
+// new module can retrieve the state with restore_state().
+namespace brunhild::hot_reload {
+
+// Serializes a state slice to string
+typedef std::function<std::string()> Save;
+
+// Register a state slice to be saved on reload under a unique key
+void register_state(std::string key, Save save);
+
+// Returns the state saved under key by the previous module instance, if any
+std::optional<std::string> restore_state(const std::string& key);
+
+// Returns, if the module was booted by a hot reload
+bool is_hot_reload();
+
+// Metadata of a view rendered at the time of reload
+struct ViewInfo {
+    std::string id, type;
+};
+
+// Returns the views rendered at the time of reload by the previous module
+// instance
+std::vector<ViewInfo> saved_views();
+
+// Save all registered state and tear down the JS-side resources of this
+// module instance: DOM event listeners, shadow root registry and the render
+// loop. The module must not be used after calling this.
+void prepare_reload();
+
+// Clear all state saved by the previous module instance. Call after restoring.
+void clear_saved();
+}
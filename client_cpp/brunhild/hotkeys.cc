@@ -0,0 +1,217 @@
+#include "hotkeys.hh"
+#include "error.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::hotkeys {
+
+struct Binding {
+    string combo;
+    std::function<void()> handler;
+    Options opts;
+};
+
+static long id_counter = 0;
+
+// All bindings by ID
+static std::map<long, Binding> bindings;
+
+static string to_lower(string s)
+{
+    for (auto& ch : s) {
+        if (ch >= 'A' && ch <= 'Z') {
+            ch += 'a' - 'A';
+        }
+    }
+    return s;
+}
+
+// Common aliases of key names
+static string canonical_key(const string& key)
+{
+    static const std::map<string, string> aliases = {
+        { "esc", "escape" }, { "del", "delete" }, { "space", " " },
+        { "up", "arrowup" }, { "down", "arrowdown" }, { "left", "arrowleft" },
+        { "right", "arrowright" }, { "return", "enter" },
+    };
+    auto it = aliases.find(key);
+    return it != aliases.end() ? it->second : key;
+}
+
+string normalize(const string& combo)
+{
+    const bool is_mac = EM_ASM_INT(
+        { return /Mac|iPhone|iPad/.test(navigator.platform) ? 1 : 0; });
+
+    bool ctrl = false, alt = false, shift = false, meta = false;
+    string key;
+    size_t i = 0;
+    while (i <= combo.size()) {
+        auto end = combo.find('+', i);
+        if (end == i) { // "+" key itself, as in "Ctrl++"
+            end = combo.find('+', i + 1);
+        }
+        if (end == string::npos) {
+            end = combo.size();
+        }
+        const auto part = to_lower(combo.substr(i, end - i));
+        i = end + 1;
+
+        if (part == "ctrl" || part == "control") {
+            ctrl = true;
+        } else if (part == "alt" || part == "option") {
+            alt = true;
+        } else if (part == "shift") {
+            shift = true;
+        } else if (part == "meta" || part == "cmd" || part == "command") {
+            meta = true;
+        } else if (part == "mod") {
+            (is_mac ? meta : ctrl) = true;
+        } else if (part != "") {
+            if (key != "") {
+                throw Error("multiple keys in shortcut: " + combo);
+            }
+            key = canonical_key(part);
+        }
+    }
+    if (key == "") {
+        throw Error("no key in shortcut: " + combo);
+    }
+
+    // Shift is implied by the produced character for single character
+    // non-letter keys, like "?"
+    if (key.size() == 1 && !(key[0] >= 'a' && key[0] <= 'z')) {
+        shift = false;
+    }
+
+    string s;
+    if (ctrl) {
+        s += "ctrl+";
+    }
+    if (alt) {
+        s += "alt+";
+    }
+    if (shift) {
+        s += "shift+";
+    }
+    if (meta) {
+        s += "meta+";
+    }
+    return s + key;
+}
+
+long bind(const string& combo, std::function<void()> handler, Options opts)
+{
+    const auto norm = normalize(combo);
+    for (auto & [ _, b ] : bindings) {
+        if (b.combo == norm && b.opts.scope == opts.scope) {
+            throw Error("shortcut already bound: " + norm, opts.scope);
+        }
+    }
+
+    if (bindings.empty()) {
+        EM_ASM({
+            if (window.__bh_hotkeys) {
+                return;
+            }
+            window.__bh_hotkeys = function(e)
+            {
+                if (e.isComposing || e.repeat && e.key.length > 1) {
+                    return;
+                }
+                var key = e.key.toLowerCase();
+                if (key == "control" || key == "alt" || key == "shift"
+                    || key == "meta") {
+                    return;
+                }
+                var combo = "";
+                if (e.ctrlKey) {
+                    combo += "ctrl+";
+                }
+                if (e.altKey) {
+                    combo += "alt+";
+                }
+                if (e.shiftKey && (key.length > 1 || /[a-z]/.test(key))) {
+                    combo += "shift+";
+                }
+                if (e.metaKey) {
+                    combo += "meta+";
+                }
+                combo += key;
+
+                // Focused element and its ancestors by ID, innermost first
+                var path = e.composedPath ? e.composedPath() : [e.target];
+                var ids = [];
+                var typing = false;
+                for (var i = 0; i < path.length; i++) {
+                    var el = path[i];
+                    if (!el.tagName) {
+                        continue;
+                    }
+                    if (i == 0) {
+                        typing = /^(INPUT|TEXTAREA|SELECT)$/.test(el.tagName)
+                            || el.isContentEditable;
+                    }
+                    if (el.id) {
+                        ids.push(el.id);
+                    }
+                }
+                if (Module._bh_hotkey(combo, ids, typing)) {
+                    e.preventDefault();
+                }
+            };
+            document.addEventListener("keydown", window.__bh_hotkeys);
+        });
+    }
+
+    const long id = id_counter++;
+    bindings[id] = { norm, handler, opts };
+    return id;
+}
+
+void unbind(long id) { bindings.erase(id); }
+
+std::vector<string> bound(const string& scope)
+{
+    std::vector<string> combos;
+    for (auto & [ _, b ] : bindings) {
+        if (b.opts.scope == scope) {
+            combos.push_back(b.combo);
+        }
+    }
+    return combos;
+}
+
+// Find and run the binding of the innermost scope matching a key event.
+// Returns, if a binding was run.
+static bool run_hotkey(string combo, val ids, bool typing)
+{
+    std::vector<string> scopes;
+    const unsigned len = ids["length"].as<unsigned>();
+    for (unsigned i = 0; i < len; i++) {
+        scopes.push_back(ids[i].as<string>());
+    }
+    scopes.push_back(""); // Global scope
+
+    for (auto& scope : scopes) {
+        for (auto & [ _, b ] : bindings) {
+            if (b.combo == combo && b.opts.scope == scope
+                && (!typing || b.opts.in_inputs)) {
+                auto h = b.handler; // Handler might unbind itself
+                h();
+                return true;
+            }
+        }
+    }
+    return false;
+}
+
+EMSCRIPTEN_BINDINGS(module_hotkeys)
+{
+    emscripten::function("_bh_hotkey", &run_hotkey);
+}
+}
@@ -0,0 +1,58 @@
+#pragma once
+
+#include <functional>
+#include <string>
+#include <vector>
+
+// Keyboard shortcut registry. Shortcuts are written as modifiers and a key
+// joined with "+", like "Ctrl+Shift+K", "Escape" or "?". Key names follow
+// KeyboardEvent.key and are case-insensitive. "Mod" stands for Meta on macOS
+// and Ctrl elsewhere.
+namespace brunhild::hotkeys {
+
+// Options of a shortcut binding
+struct Options {
+    // ID of an element the shortcut is scoped to. Scoped shortcuts only fire,
+    // while focus is inside the element, and take precedence over shortcuts of
+    // enclosing scopes. Empty for a global shortcut.
+    std::string scope;
+
+    // Also fire, while typing in an input, textarea, select or
+    // contenteditable element
+    bool in_inputs = false;
+};
+
+// Normalize a shortcut string. Throws brunhild::Error on invalid shortcuts.
+std::string normalize(const std::string& combo);
+
+// Bind a shortcut to a handler. Matching key events have their default action
+// prevented. Throws brunhild::Error, if the shortcut is already bound in the
+// same scope. Returns binding ID.
+long bind(const std::string& combo, std::function<void()> handler,
+    Options opts = {});
+
+// Remove a binding by ID
+void unbind(long id);
+
+// Returns the normalized shortcuts bound in a scope
+std::vector<std::string> bound(const std::string& scope = "");
+
+// Binds a shortcut for its lifetime. Store as a View member to tie the binding
+// to the View's lifecycle.
+class Hotkey {
+public:
+    Hotkey(const std::string& combo, std::function<void()> handler,
+        Options opts = {})
+        : binding_id(bind(combo, handler, opts))
+    {
+    }
+
+    ~Hotkey() { unbind(binding_id); }
+
+    Hotkey(const Hotkey&) = delete;
+    Hotkey& operator=(const Hotkey&) = delete;
+
+private:
+    const long binding_id;
+};
+}
@@ -0,0 +1,144 @@
+#include "hydrate.hh"
+#include "intersection.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+#include <vector>
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// View pending lazy hydration
+struct PendingHydration {
+    std::shared_ptr<View> view;
+    std::shared_ptr<Intersection> intersection;
+};
+
+// Views pending lazy hydration by ID
+static std::unordered_map<long, PendingHydration> pending;
+
+void hydrate(View& view)
+{
+    // Rendering initializes the view and schedules its mount
+    view.html();
+}
+
+// Remove the JS triggers of a pending hydration
+static void remove_triggers(long id)
+{
+    EM_ASM_INT(
+        {
+            var s = window.__bh_hydrate;
+            var p = s && s[$0];
+            if (!p) {
+                return;
+            }
+            if (p.idle !== null) {
+                if (window.cancelIdleCallback) {
+                    cancelIdleCallback(p.idle);
+                } else {
+                    clearTimeout(p.idle);
+                }
+            }
+            if (p.el) {
+                p.events.forEach(function(type) {
+                    p.el.removeEventListener(type, p.fn, true);
+                });
+            }
+            delete s[$0];
+        },
+        id);
+}
+
+// Release a pending hydration without hydrating
+static std::shared_ptr<View> release(long id)
+{
+    auto it = pending.find(id);
+    if (it == pending.end()) {
+        return nullptr;
+    }
+    auto view = std::move(it->second.view);
+
+    // Might be called from the observer's own handler, so keep it alive until
+    // the handler returns
+    if (auto i = std::move(it->second.intersection)) {
+        defer([i]() {});
+    }
+    pending.erase(it);
+    remove_triggers(id);
+    return view;
+}
+
+long hydrate_lazily(std::shared_ptr<View> view, HydrateOptions opts)
+{
+    const long id = id_counter++;
+    auto& p = pending[id];
+    p.view = view;
+
+    EM_ASM_INT(
+        {
+            if (!window.__bh_hydrate) {
+                window.__bh_hydrate = {};
+            }
+            var id = $0;
+            var fn = function() { Module._bh_hydrate(id); };
+            var p = {
+                el : window.__bh_get(UTF8ToString($1)),
+                fn : fn,
+                events : [ "pointerdown", "touchstart", "focusin", "keydown" ],
+                idle : null
+            };
+            if ($2) {
+                if (window.requestIdleCallback) {
+                    p.idle = requestIdleCallback(fn, {timeout : $3});
+                } else {
+                    p.idle = setTimeout(fn, 200);
+                }
+            }
+            if ($4 && p.el) {
+                p.events.forEach(function(type) {
+                    p.el.addEventListener(type, fn, true);
+                });
+            }
+            window.__bh_hydrate[id] = p;
+        },
+        id, view->id.c_str(), opts.idle, opts.idle_timeout, opts.interaction);
+
+    if (opts.visible) {
+        IntersectionOptions io;
+        io.root_margin = opts.root_margin;
+        p.intersection = std::make_shared<Intersection>(
+            view->id, [id]() { hydrate_now(id); }, nullptr, io);
+    }
+    return id;
+}
+
+void hydrate_now(long id)
+{
+    auto view = release(id);
+    if (view) {
+        hydrate(*view);
+    }
+}
+
+void cancel_hydration(long id) { release(id); }
+
+void hydrate_all()
+{
+    std::vector<long> ids;
+    ids.reserve(pending.size());
+    for (auto & [ id, _ ] : pending) {
+        ids.push_back(id);
+    }
+    for (auto id : ids) {
+        hydrate_now(id);
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_hydrate)
+{
+    emscripten::function("_bh_hydrate", &hydrate_now);
+}
+}
@@ -0,0 +1,48 @@
+#pragma once
+
+#include "view.hh"
+#include <memory>
+#include <string>
+
+// Hydration of server-rendered HTML. The view must render exactly the markup
+// already in the document, including element IDs, so views and nodes
+// rendered on the server must be given explicit IDs.
+namespace brunhild {
+
+// Adopt the view's server-rendered HTML in the document instead of inserting
+// it. Initializes the view's saved state and mounts it after the next flush.
+void hydrate(View& view);
+
+// Triggers for hydrating a view with hydrate_lazily(). The view is hydrated on
+// the first enabled trigger.
+struct HydrateOptions {
+    // Hydrate, when the view's root element comes near the viewport
+    bool visible = true;
+
+    // Margin around the viewport for the visible trigger, like "200px 0px"
+    std::string root_margin = "200px";
+
+    // Hydrate, when the browser is idle
+    bool idle = true;
+
+    // Maximum time in milliseconds to wait for the browser to become idle
+    unsigned idle_timeout = 2000;
+
+    // Hydrate on the first pointer, touch, focus or key event inside the
+    // view's root element
+    bool interaction = true;
+};
+
+// Defer hydrating a view with hydrate() until one of the triggers in opts
+// fires. The view is kept alive until then. Returns hydration ID.
+long hydrate_lazily(std::shared_ptr<View> view, HydrateOptions opts = {});
+
+// Hydrate a lazily hydrated view immediately
+void hydrate_now(long id);
+
+// Cancel hydration of a lazily hydrated view and release it
+void cancel_hydration(long id);
+
+// Hydrate all pending lazily hydrated views immediately
+void hydrate_all();
+}
@@ -0,0 +1,195 @@
+#include "i18n.hh"
+#include "promise.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::i18n {
+
+// Messages by locale and key
+static std::unordered_map<string, std::unordered_map<string, string>>
+    catalogs;
+
+static string fallback = "en";
+
+Provider<Locale>& locale_context()
+{
+    static Provider<Locale> p(Locale{ "en" });
+    return p;
+}
+
+// Returns the JS catalog helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_i18n) {
+            return;
+        }
+
+        // Flatten nested objects into dot-separated keys
+        var flatten = function(obj, prefix, out)
+        {
+            for (var k in obj) {
+                var v = obj[k];
+                if (v !== null && typeof v == "object") {
+                    flatten(v, prefix + k + ".", out);
+                } else {
+                    out[prefix + k] = String(v);
+                }
+            }
+            return out;
+        };
+
+        window.__bh_i18n = {
+            parse : function(json)
+            {
+                try {
+                    return flatten(JSON.parse(json), "", {});
+                } catch (e) {
+                    console.error(e);
+                    return null;
+                }
+            },
+            fetch : function(url)
+            {
+                return fetch(url).then(function(res) {
+                    if (!res.ok) {
+                        throw new Error("HTTP " + res.status);
+                    }
+                    return res.text();
+                });
+            }
+        };
+    });
+    return val::global("__bh_i18n");
+}
+
+bool load(const string& locale, const string& json)
+{
+    auto messages = helpers().call<val>("parse", json);
+    if (messages.isNull()) {
+        return false;
+    }
+    auto& cat = catalogs[locale];
+    auto keys = emscripten::vecFromJSArray<string>(
+        val::global("Object").call<val>("keys", messages));
+    cat.reserve(cat.size() + keys.size());
+    for (auto& k : keys) {
+        cat[k] = messages[k].as<string>();
+    }
+
+    // Rerender with the newly loaded messages
+    if (locale == i18n::locale().tag || locale == fallback) {
+        set_locale(i18n::locale().tag);
+    }
+    return true;
+}
+
+bool load_element(const string& locale, const string& id)
+{
+    auto el = val::global("__bh_get")(id);
+    if (el.isNull()) {
+        return false;
+    }
+    return load(locale, el["textContent"].as<string>());
+}
+
+void fetch(
+    const string& locale, const string& url, std::function<void(bool)> cb)
+{
+    await(helpers().call<val>("fetch", url), [=](bool ok, val res) {
+        if (ok) {
+            ok = load(locale, res.as<string>());
+        }
+        if (cb) {
+            cb(ok);
+        }
+    });
+}
+
+bool loaded(const string& locale) { return catalogs.count(locale); }
+
+const Locale& locale() { return locale_context().get(); }
+
+void set_locale(const string& tag) { locale_context().set(Locale{ tag }); }
+
+void set_fallback(const string& tag) { fallback = tag; }
+
+// Look up a message in a locale's catalog. Returns NULL, if not found.
+static const string* find(const string& locale, const string& key)
+{
+    auto cat = catalogs.find(locale);
+    if (cat == catalogs.end()) {
+        return nullptr;
+    }
+    auto it = cat->second.find(key);
+    if (it == cat->second.end()) {
+        return nullptr;
+    }
+    return &it->second;
+}
+
+// Look up a message in a locale, falling back to the fallback locale and the
+// key itself
+static string lookup(const string& locale, const string& key, const Args& args)
+{
+    auto msg = find(locale, key);
+    if (!msg) {
+        msg = find(fallback, key);
+    }
+    if (!msg) {
+        return key;
+    }
+    return interpolate(*msg, args);
+}
+
+string translate(const string& key, const Args& args)
+{
+    return lookup(locale().tag, key, args);
+}
+
+string translate(View* view, const string& key, const Args& args)
+{
+    auto l = use_context<Locale>(view);
+    if (!l) {
+        // Subscribe to the active locale
+        locale_context().scope([&]() { l = use_context<Locale>(view); });
+    }
+    return lookup(l->tag, key, args);
+}
+
+string interpolate(const string& msg, const Args& args)
+{
+    if (args.empty()) {
+        return msg;
+    }
+
+    string out;
+    out.reserve(msg.size());
+    size_t i = 0;
+    while (i < msg.size()) {
+        const auto open = msg.find('{', i);
+        if (open == string::npos) {
+            break;
+        }
+        const auto close = msg.find('}', open);
+        if (close == string::npos) {
+            break;
+        }
+        out.append(msg, i, open - i);
+        auto it = args.find(msg.substr(open + 1, close - open - 1));
+        if (it != args.end()) {
+            out += it->second;
+        } else {
+            // Keep unknown placeholders verbatim
+            out.append(msg, open, close - open + 1);
+        }
+        i = close + 1;
+    }
+    out.append(msg, i, string::npos);
+    return out;
+}
+}
@@ -0,0 +1,69 @@
+#pragma once
+
+#include "context.hh"
+#include <functional>
+#include <string>
+#include <unordered_map>
+
+// Localized message catalogs. Catalogs are JSON objects mapping keys to
+// message strings. Nested objects are flattened into dot-separated keys, so
+// {"posts": {"reply": "Reply"}} defines "posts.reply".
+//
+// Messages may contain placeholders like "{name}", that are replaced by the
+// argument of the same name on lookup.
+namespace brunhild::i18n {
+
+// Locale of rendered messages. Provided to views through the context API.
+struct Locale {
+    // BCP 47 language tag, like "en" or "pt-BR"
+    std::string tag;
+};
+
+// Named arguments interpolated into messages
+typedef std::unordered_map<std::string, std::string> Args;
+
+// Load a catalog for a locale from a JSON string. Merges with any previously
+// loaded messages of the locale. Returns false, if the JSON is invalid.
+bool load(const std::string& locale, const std::string& json);
+
+// Load a catalog for a locale from JSON inlined into the document, like in a
+// <script type="application/json"> element, by element ID
+bool load_element(const std::string& locale, const std::string& id);
+
+// Fetch a catalog for a locale from a URL. cb is optional and receives, if the
+// catalog was loaded.
+void fetch(const std::string& locale, const std::string& url,
+    std::function<void(bool)> cb = nullptr);
+
+// Returns, if a catalog for the locale has been loaded
+bool loaded(const std::string& locale);
+
+// Returns the active locale
+const Locale& locale();
+
+// Set the active locale and patch all mounted views, that have looked up
+// messages through translate() or read the Locale context
+void set_locale(const std::string& tag);
+
+// Set the locale to fall back to, for keys missing from the active locale's
+// catalog. Defaults to "en".
+void set_fallback(const std::string& tag);
+
+// Provider of the active locale. Views reading it with use_context<Locale>()
+// outside the scope of another provider must first enter its scope.
+Provider<Locale>& locale_context();
+
+// Look up a message in the active locale and interpolate args. Returns the key
+// itself, if neither the active nor the fallback locale define it.
+std::string translate(const std::string& key, const Args& args = {});
+
+// Same as translate(), but also subscribes the view to locale changes.
+// Uses the innermost Locale provided to the view's current render, if any, so
+// subtrees can be rendered in a different locale with a nested
+// Provider<Locale>.
+std::string translate(
+    View* view, const std::string& key, const Args& args = {});
+
+// Interpolate args into a message
+std::string interpolate(const std::string& msg, const Args& args);
+}
@@ -0,0 +1,148 @@
+#include "idle.hh"
+#include "timer.hh"
+#include "visibility.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// Registered handlers by ID
+static std::map<long, std::function<void(bool)>> handlers;
+
+static unsigned timeout = 60000;
+
+static bool idle = false;
+
+// ID of the inactivity timer or -1
+static long timer = -1;
+
+// Returns the milliseconds since the last input event
+static double since_activity()
+{
+    return EM_ASM_DOUBLE({ return Date.now() - window.__bh_idle.last; });
+}
+
+static void set_idle(bool is_idle)
+{
+    if (idle == is_idle) {
+        return;
+    }
+    idle = is_idle;
+    EM_ASM_INT({ window.__bh_idle.idle = !!$0; }, is_idle);
+
+    auto copy = handlers; // Handler might invalidate this iterator
+    for (auto & [ _, h ] : copy) {
+        h(is_idle);
+    }
+}
+
+// (Re)start the inactivity timer for the remaining time until the timeout
+static void start_timer()
+{
+    if (timer != -1) {
+        clear_timer(timer);
+    }
+    const double elapsed = since_activity();
+    const unsigned remaining
+        = elapsed < timeout ? unsigned(timeout - elapsed) : 0;
+    timer = set_timeout(
+        []() {
+            timer = -1;
+            if (since_activity() >= timeout) {
+                set_idle(true);
+            } else {
+                start_timer();
+            }
+        },
+        remaining);
+}
+
+// Called by the JS side on the first input event after becoming idle
+static void on_activity()
+{
+    set_idle(false);
+    start_timer();
+}
+
+// Install the input event listeners and start tracking, if not yet done
+static void init()
+{
+    static bool done = false;
+    if (done) {
+        return;
+    }
+    done = true;
+
+    // Only the time of the last input event is recorded on the JS side to
+    // avoid calling into the module on every mouse move
+    EM_ASM({
+        var state = window.__bh_idle = { last : Date.now(), idle : false };
+        var record = function()
+        {
+            state.last = Date.now();
+            if (state.idle) {
+                state.idle = false;
+                Module._bh_user_active();
+            }
+        };
+        [ "mousemove", "mousedown", "keydown", "wheel", "touchstart",
+            "pointerdown", "scroll" ]
+            .forEach(function(type) {
+                window.addEventListener(
+                    type, record, { passive : true, capture : true });
+            });
+    });
+
+    on_visibility_change([](bool visible) {
+        if (visible) {
+            EM_ASM({ window.__bh_idle.last = Date.now(); });
+            on_activity();
+        } else {
+            if (timer != -1) {
+                clear_timer(timer);
+                timer = -1;
+            }
+            set_idle(true);
+        }
+    });
+
+    if (page_visible()) {
+        start_timer();
+    } else {
+        set_idle(true);
+    }
+}
+
+bool user_idle()
+{
+    init();
+    return idle;
+}
+
+void set_idle_timeout(unsigned ms)
+{
+    init();
+    timeout = ms;
+    if (!idle) {
+        start_timer();
+    }
+}
+
+long on_idle_change(std::function<void(bool)> handler)
+{
+    init();
+    const long id = id_counter++;
+    handlers[id] = handler;
+    return id;
+}
+
+void remove_idle_handler(long id) { handlers.erase(id); }
+
+EMSCRIPTEN_BINDINGS(module_idle)
+{
+    emscripten::function("_bh_user_active", &on_activity);
+}
+}
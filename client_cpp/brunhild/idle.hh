@@ -0,0 +1,25 @@
+#pragma once
+
+#include <functional>
+
+// Detection of user inactivity. The user is idle, once no input events have
+// been received for the idle timeout or while the page is hidden, and becomes
+// active again on the next input event or when the page becomes visible.
+// Useful for slowing down polling, pausing expensive rendering or batching
+// notifications.
+namespace brunhild {
+
+// Returns, if the user is currently idle
+bool user_idle();
+
+// Set the milliseconds without input after which the user is idle. Defaults
+// to 60000.
+void set_idle_timeout(unsigned ms);
+
+// Register a handler called with the new state, whenever the user becomes
+// idle or active. Returns handler ID.
+long on_idle_change(std::function<void(bool idle)> handler);
+
+// Remove an idle state handler by ID
+void remove_idle_handler(long id);
+}
@@ -0,0 +1,172 @@
+#include "img.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using std::string;
+
+namespace brunhild {
+
+// All mounted images by ID
+static std::unordered_map<string, Img*> images;
+
+Img::Img(string src, Attrs attrs, string placeholder, string fallback,
+    OnLoad on_load, unsigned margin, string id)
+    : VirtualView(id)
+    , src(src)
+    , attrs(attrs)
+    , placeholder(placeholder)
+    , fallback(fallback)
+    , on_load(on_load)
+    , margin(margin)
+{
+}
+
+Img::~Img() { images.erase(id); }
+
+Node Img::render()
+{
+    Node n("img", attrs);
+    switch (current) {
+    case State::loaded:
+        n.attrs["src"] = src;
+        break;
+    case State::failed:
+        if (fallback != "") {
+            n.attrs["src"] = fallback;
+            break;
+        }
+        [[fallthrough]];
+    default:
+        if (placeholder != "") {
+            n.attrs["src"] = placeholder;
+        }
+    }
+    return n;
+}
+
+void Img::mount()
+{
+    VirtualView::mount();
+    if (current != State::pending) {
+        return;
+    }
+    images[id] = this;
+    EM_ASM_INT(
+        {
+            if (!window.__bh_img) {
+                window.__bh_img = { observers : {}, loading : {} };
+            }
+            var s = window.__bh_img;
+            var id = UTF8ToString($0);
+            var src = UTF8ToString($1);
+            var margin = $2;
+
+            var load = function()
+            {
+                Module._bh_img_event(id, 0);
+                var img = new Image();
+                s.loading[id] = img;
+                var done = function(ok)
+                {
+                    if (s.loading[id] === img) {
+                        delete s.loading[id];
+                        Module._bh_img_event(id, ok ? 1 : 2);
+                    }
+                };
+                img.onerror = function() { done(false); };
+                img.onload = function()
+                {
+                    // Decode off the main thread before swapping in, where
+                    // supported
+                    if (img.decode) {
+                        img.decode().then(function() { done(true); },
+                            function() { done(true); });
+                    } else {
+                        done(true);
+                    }
+                };
+                img.src = src;
+            };
+
+            var el = window.__bh_get(id);
+            if (!el || !window.IntersectionObserver) {
+                load();
+                return;
+            }
+
+            // Share observers between images with the same margin
+            var io = s.observers[margin];
+            if (!io) {
+                io = s.observers[margin] = new IntersectionObserver(
+                    function(entries) {
+                        for (var i = 0; i < entries.length; i++) {
+                            var e = entries[i];
+                            if (e.isIntersecting && e.target.__bh_load) {
+                                io.unobserve(e.target);
+                                var fn = e.target.__bh_load;
+                                delete e.target.__bh_load;
+                                fn();
+                            }
+                        }
+                    },
+                    { rootMargin : margin + "px" });
+            }
+            el.__bh_load = load;
+            io.observe(el);
+        },
+        id.c_str(), src.c_str(), margin);
+}
+
+void Img::unmount()
+{
+    images.erase(id);
+    EM_ASM_INT(
+        {
+            var s = window.__bh_img;
+            var id = UTF8ToString($0);
+            if (!s) {
+                return;
+            }
+            delete s.loading[id];
+            var el = window.__bh_get(id);
+            if (el && el.__bh_load) {
+                delete el.__bh_load;
+                for (var m in s.observers) {
+                    s.observers[m].unobserve(el);
+                }
+            }
+        },
+        id.c_str());
+    if (current == State::loading) {
+        current = State::pending; // Restart loading, when mounted again
+    }
+    VirtualView::unmount();
+}
+
+// state: 0 - loading started, 1 - loaded, 2 - failed
+void run_img_handler(string id, int state)
+{
+    if (!images.count(id)) {
+        return;
+    }
+    auto img = images.at(id);
+    if (!state) {
+        img->current = Img::State::loading;
+        return;
+    }
+
+    const bool ok = state == 1;
+    img->current = ok ? Img::State::loaded : Img::State::failed;
+    images.erase(id);
+    img->patch();
+    if (img->on_load) {
+        img->on_load(ok);
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_img)
+{
+    emscripten::function("_bh_img_event", &run_img_handler);
+}
+}
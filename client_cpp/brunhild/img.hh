@@ -0,0 +1,53 @@
+#pragma once
+
+#include "view.hh"
+#include <functional>
+#include <string>
+
+namespace brunhild {
+
+// Lazy-loading image. Renders the placeholder source, until the element comes
+// near the viewport, then loads and decodes the real source in the background
+// and swaps it in. Renders the fallback source, if loading fails.
+class Img : public VirtualView {
+public:
+    // Loading state of the image
+    enum class State { pending, loading, loaded, failed };
+
+    // Receives true, if the image loaded, and false, if it failed
+    typedef std::function<void(bool)> OnLoad;
+
+    // Source of the image to load
+    const std::string src;
+
+    // src: image to lazy load
+    // attrs: extra attributes of the <img> element, like "alt" or "class"
+    // placeholder: source rendered before loading. Empty for none.
+    // fallback: source rendered on error. Empty to keep the placeholder.
+    // on_load: optional callback reporting load completion
+    // margin: distance from the viewport in CSS pixels to start loading at
+    Img(std::string src, Attrs attrs = {}, std::string placeholder = "",
+        std::string fallback = "", OnLoad on_load = nullptr,
+        unsigned margin = 200, std::string id = new_id());
+
+    ~Img();
+
+    Node render();
+
+    // Returns the current loading state
+    State state() const { return current; }
+
+protected:
+    void mount();
+    void unmount();
+
+private:
+    const Attrs attrs;
+    const std::string placeholder, fallback;
+    const OnLoad on_load;
+    const unsigned margin;
+    State current = State::pending;
+
+    friend void run_img_handler(std::string, int);
+};
+}
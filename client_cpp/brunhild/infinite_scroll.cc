@@ -0,0 +1,101 @@
+#include "infinite_scroll.hh"
+#include "mutations.hh"
+
+namespace brunhild {
+
+InfiniteScroll::InfiniteScroll(std::shared_ptr<View> list, Loader loader,
+    Node indicator, std::string margin, std::string tag, std::string id)
+    : View(id)
+    , tag(tag)
+    , sentinel_id(new_id())
+    , list(list)
+    , loader(loader)
+    , indicator(indicator)
+    , margin(margin)
+{
+}
+
+InfiniteScroll::~InfiniteScroll() { *alive = false; }
+
+void InfiniteScroll::write_html(Rope& s)
+{
+    if (!is_initialized) {
+        is_initialized = true;
+        schedule_mount();
+    }
+    s << '<' << tag << " id=\"" << id << "\">";
+    list->write_html(s);
+    s << "<div id=\"" << sentinel_id << "\">";
+    if (loading) {
+        indicator.write_html(s);
+    }
+    s << "</div></" << tag << '>';
+}
+
+void InfiniteScroll::patch() { list->patch(); }
+
+void InfiniteScroll::reset()
+{
+    more = true;
+    if (is_mounted()) {
+        observe();
+    }
+}
+
+void InfiniteScroll::mount()
+{
+    View::mount();
+    observe();
+}
+
+void InfiniteScroll::observe()
+{
+    if (more && !observer) {
+        IntersectionOptions opts;
+        opts.root_margin = margin;
+        observer = std::make_unique<Intersection>(
+            sentinel_id, [this]() { load(); }, nullptr, opts);
+    }
+}
+
+void InfiniteScroll::unmount()
+{
+    observer.reset();
+    View::unmount_child(*list);
+    View::unmount();
+}
+
+void InfiniteScroll::load()
+{
+    if (loading || !more) {
+        return;
+    }
+    loading = true;
+    set_inner_html(sentinel_id, indicator.html());
+
+    loader([this, alive = alive](bool has_more) {
+        if (!*alive) {
+            return;
+        }
+        loading = false;
+        more = has_more;
+        set_inner_html(sentinel_id, "");
+        if (!is_mounted()) {
+            return;
+        }
+        list->patch();
+
+        if (!more) {
+            observer.reset();
+        } else if (observer && observer->intersecting()) {
+            // Sentinel still in view after appending, so the page was too
+            // short to fill the viewport
+            defer([this, alive = alive]() {
+                if (*alive && observer && observer->intersecting()) {
+                    load();
+                }
+            });
+        }
+    });
+}
+}
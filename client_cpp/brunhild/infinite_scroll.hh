@@ -0,0 +1,81 @@
+#pragma once
+
+#include "intersection.hh"
+#include "view.hh"
+#include <functional>
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// Wraps a list view and loads more items asynchronously, when the end of the
+// list comes near the viewport. A sentinel element after the list is observed
+// for intersection and shows a loading indicator, while a page is loading.
+// The loader is expected to add the new models to the list's source, after
+// which the list is patched to append them, usually by a ListView's keyed
+// diffing.
+class InfiniteScroll : public View {
+public:
+    // Receives, if there are more pages to load
+    typedef std::function<void(bool has_more)> Done;
+
+    // Starts loading the next page and calls Done, when finished. Can complete
+    // synchronously or at any later point in time.
+    typedef std::function<void(Done)> Loader;
+
+    // Tag of root node
+    const std::string tag;
+
+    // list: wrapped list view
+    // loader: loads the next page
+    // indicator: rendered inside the sentinel while loading
+    // margin: distance from the viewport to start loading at, as a CSS
+    // margin
+    InfiniteScroll(std::shared_ptr<View> list, Loader loader,
+        Node indicator = { "div", "..." }, std::string margin = "400px",
+        std::string tag = "div", std::string id = new_id());
+
+    ~InfiniteScroll();
+
+    void write_html(Rope&);
+
+    // Patch the wrapped list
+    void patch();
+
+    // Returns the wrapped list view
+    View& get_list() { return *list; }
+
+    // Returns, if a page is currently loading
+    bool is_loading() const { return loading; }
+
+    // Returns, if there are more pages to load
+    bool has_more() const { return more; }
+
+    // Allow loading again after the loader reported no more pages, for
+    // example after new items have been created
+    void reset();
+
+protected:
+    void mount();
+    void unmount();
+
+private:
+    const std::string sentinel_id;
+    std::shared_ptr<View> list;
+    const Loader loader;
+    Node indicator;
+    const std::string margin;
+    bool is_initialized = false, loading = false, more = true;
+    std::unique_ptr<Intersection> observer;
+
+    // Guards loader callbacks against running after destruction
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Start observing the sentinel, if there are more pages to load and it
+    // is not observed already
+    void observe();
+
+    // Load the next page, if not already loading
+    void load();
+};
+}
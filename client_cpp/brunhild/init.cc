@@ -1,11 +1,163 @@
 #include "mutations.hh"
+#include "visibility.hh"
 #include <emscripten.h>
 
 namespace brunhild {
 void init()
 {
+    // Element lookup, that also searches the shadow roots managed by
+    // brunhild
+    EM_ASM({
+        window.__bh_shadow_roots = {};
+        window.__bh_get = function(id)
+        {
+            var el = document.getElementById(id);
+            if (el) {
+                return el;
+            }
+            for (var host in window.__bh_shadow_roots) {
+                el = window.__bh_shadow_roots[host].getElementById(id);
+                if (el) {
+                    return el;
+                }
+            }
+            return null;
+        };
+    });
+
+    // Save and restore focus and text selection of the focused element, when
+    // replacing the HTML of a subtree it is in
+    EM_ASM({
+        window.__bh_save_focus = function(root)
+        {
+            var el = document.activeElement;
+            while (el && el.shadowRoot && el.shadowRoot.activeElement) {
+                el = el.shadowRoot.activeElement;
+            }
+            if (!el || el === document.body || !root.contains(el)) {
+                return null;
+            }
+
+            var s = { id : el.id, path : [] };
+            if (el !== root) {
+                // Path of child indices from the root, for elements without ID
+                for (var n = el; n && n !== root; n = n.parentNode) {
+                    s.path.unshift(
+                        Array.prototype.indexOf.call(n.parentNode.children, n));
+                }
+            }
+            try {
+                s.start = el.selectionStart;
+                s.end = el.selectionEnd;
+                s.dir = el.selectionDirection;
+            } catch (e) {
+                // Element does not support selection
+            }
+            return s;
+        };
+
+        window.__bh_restore_focus = function(root, s)
+        {
+            if (!s || !root) {
+                return;
+            }
+            var el = s.id ? window.__bh_get(s.id) : null;
+            if (!el) {
+                el = root;
+                for (var i = 0; el && i < s.path.length; i++) {
+                    el = el.children[s.path[i]];
+                }
+            }
+            if (!el || el === document.activeElement) {
+                return;
+            }
+            el.focus();
+            if (s.start !== undefined && s.start !== null) {
+                try {
+                    el.setSelectionRange(s.start, s.end, s.dir);
+                } catch (e) {
+                    // Element does not support selection
+                }
+            }
+        };
+    });
+
+    // Save and restore scroll positions of elements marked with the
+    // "data-bh-keep-scroll" attribute, when replacing the HTML of a subtree
+    // they are in
+    EM_ASM({
+        window.__bh_save_scroll = function(root)
+        {
+            var saved = [];
+            var els = root.querySelectorAll('[data-bh-keep-scroll]');
+            var all = Array.prototype.slice.call(els);
+            if (root.hasAttribute('data-bh-keep-scroll')) {
+                all.push(root);
+            }
+            for (var i = 0; i < all.length; i++) {
+                var el = all[i];
+                if (el.id && (el.scrollTop || el.scrollLeft)) {
+                    saved.push({
+                        id : el.id,
+                        top : el.scrollTop,
+                        left : el.scrollLeft
+                    });
+                }
+            }
+            return saved;
+        };
+
+        window.__bh_restore_scroll = function(saved)
+        {
+            for (var i = 0; i < saved.length; i++) {
+                var el = window.__bh_get(saved[i].id);
+                if (el) {
+                    el.scrollTop = saved[i].top;
+                    el.scrollLeft = saved[i].left;
+                }
+            }
+        };
+    });
+
+    // Routes exceptions caught in JS snippets to the JS error handler. Property
+    // and event mutations are wrapped the same way, as setters and listeners
+    // can throw.
+    EM_ASM({
+        window.__bh_report = function(e)
+        {
+            var msg = String(e && e.message !== undefined ? e.message : e);
+            var stack = String((e && e.stack) || '');
+            Module._bh_js_error(msg, stack);
+        };
+        window.__bh_set_prop = function(el, key, val)
+        {
+            try {
+                el[key] = val;
+            } catch (e) {
+                window.__bh_report(e);
+            }
+        };
+        window.__bh_dispatch = function(el, type, detail)
+        {
+            try {
+                el.dispatchEvent(new CustomEvent(
+                    type, { detail : detail, bubbles : true }));
+            } catch (e) {
+                window.__bh_report(e);
+            }
+        };
+    });
+
+    // Pause the render loop and pausable timers, while the page is hidden
+    EM_ASM({
+        document.addEventListener("visibilitychange", function() {
+            Module._bh_visibility_changed(!document.hidden);
+        });
+    });
+
     // TODO: Set up default event listeners, including the ones buffering
     // input element status.
     emscripten_set_main_loop(flush, 0, 0);
+    pause_when_hidden(true); // Page might already be hidden
 }
 }
@@ -0,0 +1,146 @@
+#include "intersection.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All active observers by ID
+static std::unordered_map<long, Intersection*> observers;
+
+Intersection::Intersection(
+    string id, Handler on_change, IntersectionOptions opts)
+    : id(id)
+    , observer_id(id_counter++)
+    , opts(opts)
+    , on_change(on_change)
+{
+    observers[observer_id] = this;
+    if (!observe()) {
+        const long o_id = observer_id;
+        defer([o_id]() {
+            if (observers.count(o_id)) {
+                observers.at(o_id)->observe();
+            }
+        });
+    }
+}
+
+Intersection::Intersection(string id, std::function<void()> on_enter,
+    std::function<void()> on_exit, IntersectionOptions opts)
+    : Intersection(id,
+          [this, on_enter, on_exit](const IntersectionEntry& e) {
+              // Only report transitions
+              if (e.intersecting == is_intersecting) {
+                  return;
+              }
+              if (e.intersecting) {
+                  on_enter();
+              } else if (on_exit) {
+                  on_exit();
+              }
+          },
+          opts)
+{
+}
+
+bool Intersection::observe()
+{
+    auto th = val::array();
+    for (size_t i = 0; i < opts.thresholds.size(); i++) {
+        th.set(i, opts.thresholds[i]);
+    }
+
+    EM_ASM({
+        if (window.__bh_intersection) {
+            return;
+        }
+        window.__bh_intersection = {};
+        window.__bh_observe_intersection = function(
+            obs_id, id, root_id, margin, thresholds)
+        {
+            var el = window.__bh_get(id);
+            if (!el) {
+                return false;
+            }
+            var io = new IntersectionObserver(
+                function(entries) {
+                    for (var i = 0; i < entries.length; i++) {
+                        var e = entries[i];
+                        var r = e.boundingClientRect;
+                        Module._bh_intersection(obs_id, {
+                            intersecting : e.isIntersecting,
+                            ratio : e.intersectionRatio,
+                            rect : [ r.x, r.y, r.width, r.height, r.top,
+                                r.right, r.bottom, r.left ]
+                        });
+                    }
+                },
+                {
+                    root : root_id ? window.__bh_get(root_id) : null,
+                    rootMargin : margin,
+                    threshold : thresholds
+                });
+            io.observe(el);
+            window.__bh_intersection[obs_id] = io;
+            return true;
+        };
+    });
+
+    return val::global("__bh_observe_intersection")(
+        observer_id, id, opts.root_id, opts.root_margin, th)
+        .as<bool>();
+}
+
+Intersection::~Intersection()
+{
+    observers.erase(observer_id);
+    EM_ASM_INT(
+        {
+            var s = window.__bh_intersection;
+            if (s && s[$0]) {
+                s[$0].disconnect();
+                delete s[$0];
+            }
+        },
+        observer_id);
+}
+
+void run_intersection_handler(long id, val e)
+{
+    if (!observers.count(id)) {
+        return;
+    }
+    auto o = observers.at(id);
+    const auto r = e["rect"];
+    IntersectionEntry entry{
+        e["intersecting"].as<bool>(),
+        e["ratio"].as<double>(),
+        {
+            r[0].as<double>(),
+            r[1].as<double>(),
+            r[2].as<double>(),
+            r[3].as<double>(),
+            r[4].as<double>(),
+            r[5].as<double>(),
+            r[6].as<double>(),
+            r[7].as<double>(),
+        },
+    };
+    o->on_change(entry);
+    if (observers.count(id)) { // Handler might have destroyed the observer
+        o->is_intersecting = entry.intersecting;
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_intersection)
+{
+    emscripten::function("_bh_intersection", &run_intersection_handler);
+}
+}
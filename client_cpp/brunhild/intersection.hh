@@ -0,0 +1,72 @@
+#pragma once
+
+#include "ref.hh"
+#include <functional>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Options of an IntersectionObserver
+struct IntersectionOptions {
+    // Margin around the root, like "200px 0px"
+    std::string root_margin = "0px";
+
+    // Intersection ratios to report changes at
+    std::vector<double> thresholds = { 0 };
+
+    // ID of the scrollable ancestor to use as root. Empty for the viewport.
+    std::string root_id;
+};
+
+// Change in intersection of an observed element with its root
+struct IntersectionEntry {
+    // Element intersects the root
+    bool intersecting;
+
+    // Visible fraction of the element in the range [0, 1]
+    double ratio;
+
+    // Bounding rectangle of the element
+    Rect bounds;
+};
+
+// Observes intersection of an element with the viewport or a scrollable
+// ancestor. If the element does not exist yet, observation starts after the
+// next flush of DOM mutations. Disconnects on destruction.
+class Intersection {
+public:
+    // ID of observed element
+    const std::string id;
+
+    typedef std::function<void(const IntersectionEntry&)> Handler;
+
+    // Call on_change on each crossing of a threshold
+    Intersection(
+        std::string id, Handler on_change, IntersectionOptions opts = {});
+
+    // Call on_enter, when the element starts intersecting, and on_exit, when
+    // it stops. on_exit can be empty.
+    Intersection(std::string id, std::function<void()> on_enter,
+        std::function<void()> on_exit, IntersectionOptions opts = {});
+
+    ~Intersection();
+
+    Intersection(const Intersection&) = delete;
+    Intersection& operator=(const Intersection&) = delete;
+
+    // Returns, if the element intersected the root at the last change
+    bool intersecting() const { return is_intersecting; }
+
+private:
+    const long observer_id;
+    const IntersectionOptions opts;
+    const Handler on_change;
+    bool is_intersecting = false;
+
+    // Start observing. Returns false, if the element does not exist.
+    bool observe();
+
+    friend void run_intersection_handler(long, emscripten::val);
+};
+}
@@ -0,0 +1,169 @@
+#include "intl.hh"
+#include "i18n.hh"
+#include <emscripten.h>
+#include <emscripten/val.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::intl {
+
+// Cached formatters by locale, type and options
+static std::unordered_map<string, val> cache;
+
+// Returns the locale to use for an optional locale argument
+static const string& resolve(const string& locale)
+{
+    return locale != "" ? locale : i18n::locale().tag;
+}
+
+// Returns a cached formatter or creates one with make()
+template <class F> static val& cached(const string& key, F make)
+{
+    auto it = cache.find(key);
+    if (it == cache.end()) {
+        it = cache.emplace(key, make()).first;
+    }
+    return it->second;
+}
+
+// Construct an Intl formatter. Falls back to the default locale, if the
+// locale or options are invalid.
+static val create(const char* type, const string& locale, val opts)
+{
+    EM_ASM({
+        if (window.__bh_intl) {
+            return;
+        }
+        window.__bh_intl = function(type, locale, opts)
+        {
+            try {
+                return new Intl[type](locale, opts);
+            } catch (e) {
+                console.error(e);
+                return new Intl[type](undefined, opts);
+            }
+        };
+    });
+    return val::global("__bh_intl")(string(type), locale, opts);
+}
+
+string format_number(double n, const NumberOptions& o, const string& locale)
+{
+    const auto& l = resolve(locale);
+    const auto key = "n:" + l + ':' + o.style + ':' + o.currency + ':'
+        + o.unit + ':' + o.notation + ':'
+        + std::to_string(o.min_fraction_digits) + ':'
+        + std::to_string(o.max_fraction_digits) + ':'
+        + (o.grouping ? '1' : '0');
+    auto& f = cached(key, [&]() {
+        auto opts = val::object();
+        opts.set("style", o.style);
+        if (o.currency != "") {
+            opts.set("currency", o.currency);
+        }
+        if (o.unit != "") {
+            opts.set("unit", o.unit);
+        }
+        opts.set("notation", o.notation);
+        if (o.min_fraction_digits >= 0) {
+            opts.set("minimumFractionDigits", o.min_fraction_digits);
+        }
+        if (o.max_fraction_digits >= 0) {
+            opts.set("maximumFractionDigits", o.max_fraction_digits);
+        }
+        opts.set("useGrouping", o.grouping);
+        return create("NumberFormat", l, opts);
+    });
+    return f.call<string>("format", n);
+}
+
+string format_date(double ms, const DateOptions& o, const string& locale)
+{
+    const auto& l = resolve(locale);
+    const auto key = "d:" + l + ':' + o.date_style + ':' + o.time_style + ':'
+        + o.time_zone + ':' + o.hour_cycle;
+    auto& f = cached(key, [&]() {
+        auto opts = val::object();
+        if (o.date_style != "") {
+            opts.set("dateStyle", o.date_style);
+        }
+        if (o.time_style != "") {
+            opts.set("timeStyle", o.time_style);
+        }
+        if (o.time_zone != "") {
+            opts.set("timeZone", o.time_zone);
+        }
+        if (o.hour_cycle != "") {
+            opts.set("hourCycle", o.hour_cycle);
+        }
+        return create("DateTimeFormat", l, opts);
+    });
+    return f.call<string>("format", val::global("Date").new_(ms));
+}
+
+string format_relative(
+    double value, const string& unit, bool numeric, const string& locale)
+{
+    const auto& l = resolve(locale);
+    auto& f = cached("r:" + l + ':' + (numeric ? '1' : '0'), [&]() {
+        auto opts = val::object();
+        opts.set("numeric", string(numeric ? "always" : "auto"));
+        return create("RelativeTimeFormat", l, opts);
+    });
+    return f.call<string>("format", value, unit);
+}
+
+Plural plural(double n, bool ordinal, const string& locale)
+{
+    const auto& l = resolve(locale);
+    auto& f = cached("p:" + l + ':' + (ordinal ? '1' : '0'), [&]() {
+        auto opts = val::object();
+        opts.set("type", string(ordinal ? "ordinal" : "cardinal"));
+        return create("PluralRules", l, opts);
+    });
+    const auto c = f.call<string>("select", n);
+    if (c == "zero") {
+        return Plural::zero;
+    }
+    if (c == "one") {
+        return Plural::one;
+    }
+    if (c == "two") {
+        return Plural::two;
+    }
+    if (c == "few") {
+        return Plural::few;
+    }
+    if (c == "many") {
+        return Plural::many;
+    }
+    return Plural::other;
+}
+
+const char* plural_name(Plural p)
+{
+    switch (p) {
+    case Plural::zero:
+        return "zero";
+    case Plural::one:
+        return "one";
+    case Plural::two:
+        return "two";
+    case Plural::few:
+        return "few";
+    case Plural::many:
+        return "many";
+    default:
+        return "other";
+    }
+}
+
+string plural_key(const string& key, double n, const string& locale)
+{
+    return key + '.' + plural_name(plural(n, false, locale));
+}
+
+void clear_cache() { cache.clear(); }
+}
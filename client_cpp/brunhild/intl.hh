@@ -0,0 +1,77 @@
+#pragma once
+
+#include <string>
+
+// Locale-aware formatting with the ECMAScript Internationalization API.
+// Formatters are created once per locale and option set and cached. An empty
+// locale selects the active locale of the i18n module.
+namespace brunhild::intl {
+
+// Options of format_number(). Mirror the Intl.NumberFormat options of the
+// same name.
+struct NumberOptions {
+    // "decimal", "percent", "currency" or "unit"
+    std::string style = "decimal";
+
+    // ISO 4217 currency code. Required for the "currency" style.
+    std::string currency;
+
+    // Unit identifier, like "kilobyte". Required for the "unit" style.
+    std::string unit;
+
+    // "standard" or "compact", as in "1.2K"
+    std::string notation = "standard";
+
+    // Fraction digit bounds. Negative values use the style's default.
+    int min_fraction_digits = -1, max_fraction_digits = -1;
+
+    // Use grouping separators, as in "1,000"
+    bool grouping = true;
+};
+
+// Format a number
+std::string format_number(
+    double n, const NumberOptions& opts = {}, const std::string& locale = "");
+
+// Options of format_date(). Mirror the Intl.DateTimeFormat options of the
+// same name. Empty styles omit the date or time part.
+struct DateOptions {
+    // "full", "long", "medium" or "short"
+    std::string date_style = "medium", time_style;
+
+    // IANA time zone name. Empty for the local time zone.
+    std::string time_zone;
+
+    // Use a 12 hour clock. Empty for the locale's default.
+    std::string hour_cycle;
+};
+
+// Format a Unix timestamp in milliseconds as a date and/or time
+std::string format_date(
+    double ms, const DateOptions& opts = {}, const std::string& locale = "");
+
+// Format a relative time, like "3 minutes ago" or "in 2 days".
+// unit: "second", "minute", "hour", "day", "week", "month" or "year".
+// Negative values are in the past. If numeric is false, phrases like
+// "yesterday" are used, where available.
+std::string format_relative(double value, const std::string& unit,
+    bool numeric = false, const std::string& locale = "");
+
+// Plural category of a number
+enum class Plural { zero, one, two, few, many, other };
+
+// Returns the plural category of a number in a locale. If ordinal is true,
+// returns the category for ordinal numbers, as in "1st" or "2nd".
+Plural plural(double n, bool ordinal = false, const std::string& locale = "");
+
+// Returns the lowercase name of a plural category, like "one"
+const char* plural_name(Plural);
+
+// Append the plural category of n to key, as in "replies.one", for looking up
+// pluralized messages with i18n::translate()
+std::string plural_key(
+    const std::string& key, double n, const std::string& locale = "");
+
+// Drop all cached formatters
+void clear_cache();
+}
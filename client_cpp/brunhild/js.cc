@@ -0,0 +1,77 @@
+#include "js.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <stdlib.h>
+
+using std::string;
+
+namespace brunhild {
+
+static JSErrorHandler js_error_handler = [](const JSError& e) {
+    EM_ASM_INT(
+        {
+            console.error(
+                'brunhild: ' + UTF8ToString($0) + '\n' + UTF8ToString($1));
+        },
+        e.message.c_str(), e.stack.c_str());
+};
+
+void on_js_error(JSErrorHandler fn) { js_error_handler = fn; }
+
+// Copy a JS-allocated string into a std::string and free it
+static string take_string(char* s)
+{
+    if (!s) {
+        return "";
+    }
+    string re(s);
+    free(s);
+    return re;
+}
+
+JSResult try_js(const string& code)
+{
+    char *value = nullptr, *message = nullptr, *stack = nullptr;
+    const bool ok = (bool)EM_ASM_INT(
+        {
+            function toC(s)
+            {
+                var len = lengthBytesUTF8(s) + 1;
+                var buf = Module._malloc(len);
+                stringToUTF8(s, buf, len);
+                return buf;
+            }
+
+            try {
+                var val = new Function(UTF8ToString($0))();
+                HEAP32[$1 >> 2]
+                    = toC(val === undefined || val === null ? '' : String(val));
+                return 1;
+            } catch (e) {
+                HEAP32[$2 >> 2]
+                    = toC(String(e && e.message !== undefined ? e.message : e));
+                HEAP32[$3 >> 2] = toC(String((e && e.stack) || ''));
+                return 0;
+            }
+        },
+        code.c_str(), &value, &message, &stack);
+
+    JSResult res;
+    if (ok) {
+        res.value = take_string(value);
+    } else {
+        res.error = { take_string(message), take_string(stack) };
+    }
+    return res;
+}
+
+static void run_js_error_handler(string message, string stack)
+{
+    js_error_handler({ message, stack });
+}
+
+EMSCRIPTEN_BINDINGS(module_js)
+{
+    emscripten::function("_bh_js_error", &run_js_error_handler);
+}
+}
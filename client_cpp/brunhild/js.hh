@@ -0,0 +1,33 @@
+#pragma once
+
+#include <functional>
+#include <optional>
+#include <string>
+
+namespace brunhild {
+
+// Exception thrown on the JS side
+struct JSError {
+    std::string message, stack;
+};
+
+// Result of evaluating a JS snippet with try_js()
+struct JSResult {
+    // Snippet return value converted to string. Empty on error.
+    std::string value;
+
+    // Exception thrown by the snippet, if any
+    std::optional<JSError> error;
+};
+
+// Evaluate a JS function body and return its result converted to string or the
+// exception it has thrown
+JSResult try_js(const std::string& code);
+
+// Handles exceptions caught in JS snippets run by brunhild, like failing DOM
+// mutations. Defaults to logging the error to the console.
+typedef std::function<void(const JSError&)> JSErrorHandler;
+
+// Set the handler for JS exceptions caught by brunhild
+void on_js_error(JSErrorHandler);
+}
@@ -0,0 +1,100 @@
+#include "js_json.hh"
+#include <emscripten.h>
+
+using emscripten::val;
+using nlohmann::json;
+using std::string;
+
+namespace brunhild {
+
+// Returns the JS conversion helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_json) {
+            return;
+        }
+
+        // Error to report to C++
+        var toError = function(e)
+        {
+            return {
+                message :
+                    String(e && e.message !== undefined ? e.message : e),
+                stack : String((e && e.stack) || "")
+            };
+        };
+
+        window.__bh_json = {
+            parse : function(s) { return JSON.parse(s); },
+            stringify : function(v)
+            {
+                try {
+                    var s = JSON.stringify(v);
+                    return s === undefined ? "null" : s;
+                } catch (e) {
+                    return null;
+                }
+            },
+            run : function(code, args)
+            {
+                args = JSON.parse(args);
+                var names = args.map(function(_, i) { return "$" + i; });
+                var v;
+                try {
+                    v = Function.apply(null, names.concat(code))
+                            .apply(null, args);
+                } catch (e) {
+                    return { error : toError(e) };
+                }
+                try {
+                    v = JSON.stringify(v);
+                } catch (e) {
+                    return { error : toError(e) };
+                }
+                return { value : v === undefined ? "null" : v };
+            }
+        };
+    });
+    return val::global("__bh_json");
+}
+
+// Parse JSON produced by JSON.stringify(). Returns std::nullopt on error.
+static std::optional<json> parse(const string& s)
+{
+    auto j = json::parse(s, nullptr, false);
+    if (j.is_discarded()) {
+        return std::nullopt;
+    }
+    return j;
+}
+
+JSONResult try_js_json(const string& code, const std::vector<json>& args)
+{
+    JSONResult res;
+    auto r = helpers().call<val>("run", code, json(args).dump());
+    auto err = r["error"];
+    if (!err.isUndefined()) {
+        res.error
+            = { err["message"].as<string>(), err["stack"].as<string>() };
+        return res;
+    }
+    if (auto j = parse(r["value"].as<string>())) {
+        res.value = std::move(*j);
+    } else {
+        res.error = { "invalid JSON returned from JS", "" };
+    }
+    return res;
+}
+
+val to_js(const json& j) { return helpers().call<val>("parse", j.dump()); }
+
+std::optional<json> from_js(val v)
+{
+    auto s = helpers().call<val>("stringify", v);
+    if (s.isNull()) {
+        return std::nullopt;
+    }
+    return parse(s.as<string>());
+}
+}
@@ -0,0 +1,57 @@
+#pragma once
+
+#include "js.hh"
+#include <emscripten/val.h>
+#include <nlohmann/json.hpp>
+#include <optional>
+#include <string>
+#include <vector>
+
+// Passing of structured data between C++ and JS as JSON. Any type with
+// nlohmann::json to_json()/from_json() overloads can be passed.
+namespace brunhild {
+
+// Result of evaluating a JS snippet with try_js_json()
+struct JSONResult {
+    // Snippet return value. Null, if the snippet returned undefined or on
+    // error.
+    nlohmann::json value;
+
+    // Exception thrown by the snippet or raised while converting its return
+    // value, if any
+    std::optional<JSError> error;
+};
+
+// Evaluate a JS function body with each of args parsed into a JS value and
+// bound to $0, $1, ... and return its result converted back from JSON or the
+// exception it has thrown:
+//
+//     auto res = try_js_json("return $0.map(x => x * $1)", { { 1, 2, 3 }, 2 });
+JSONResult try_js_json(
+    const std::string& code, const std::vector<nlohmann::json>& args = {});
+
+// Convert a JSON value to a JS value
+emscripten::val to_js(const nlohmann::json&);
+
+// Convert any value serializable to JSON to a JS value
+template <class T> emscripten::val to_js_value(const T& v)
+{
+    return to_js(nlohmann::json(v));
+}
+
+// Convert a JS value to JSON. Returns std::nullopt, if the value can not be
+// serialized, like functions or cyclic objects.
+std::optional<nlohmann::json> from_js(emscripten::val);
+
+// Convert a JS value to any type deserializable from JSON. Returns
+// std::nullopt, if the value can not be serialized. Type mismatches throw
+// nlohmann::json::type_error.
+template <class T> std::optional<T> from_js_value(emscripten::val v)
+{
+    auto j = from_js(v);
+    if (!j) {
+        return std::nullopt;
+    }
+    return j->get<T>();
+}
+}
@@ -0,0 +1,82 @@
+#include "keep_alive.hh"
+#include "mutations.hh"
+
+namespace brunhild {
+
+KeepAlive::KeepAlive(
+    std::shared_ptr<View> child, bool visible, std::string tag, std::string id)
+    : View(id)
+    , tag(tag)
+    , child(child)
+    , visible(visible)
+{
+}
+
+void KeepAlive::write_html(Rope& s)
+{
+    if (!is_initialized) {
+        is_initialized = true;
+        schedule_mount();
+    }
+    auto a = attrs();
+    a["id"] = id;
+    if (visible) {
+        a.erase("hidden");
+    } else {
+        a["hidden"] = "";
+    }
+    s << '<' << tag;
+    a.write_html(s);
+    s << '>';
+    child->write_html(s);
+    s << "</" << tag << '>';
+    stale = false;
+}
+
+void KeepAlive::patch()
+{
+    if (!is_initialized) {
+        return;
+    }
+    if (visible) {
+        child->patch();
+    } else {
+        stale = true;
+    }
+}
+
+void KeepAlive::hide()
+{
+    if (!visible) {
+        return;
+    }
+    visible = false;
+    if (is_initialized) {
+        set_attr(id, "hidden", "");
+    }
+}
+
+void KeepAlive::show()
+{
+    if (visible) {
+        return;
+    }
+    visible = true;
+    if (!is_initialized) {
+        return;
+    }
+    remove_attr(id, "hidden");
+    if (stale) {
+        stale = false;
+        child->patch();
+    }
+}
+
+void KeepAlive::unmount()
+{
+    if (is_initialized) {
+        unmount_child(*child);
+    }
+    View::unmount();
+}
+}
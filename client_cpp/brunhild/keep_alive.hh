@@ -0,0 +1,57 @@
+#pragma once
+
+#include "view.hh"
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// Wraps a child view in a container, that can be hidden and shown again
+// without unmounting the child, preserving its DOM and saved state. Useful
+// for tab-like interfaces switching between expensive views.
+// While hidden, the container has the "hidden" attribute set and patching
+// the child is skipped. The child is patched once, when shown again, if any
+// patch was skipped.
+class KeepAlive : public View {
+public:
+    // Creates a new wrapper around child in a container with the given tag
+    KeepAlive(std::shared_ptr<View> child, bool visible = true,
+        std::string tag = "div", std::string id = new_id());
+
+    void write_html(Rope&);
+
+    // Patch the child view, if visible
+    void patch();
+
+    // Hide the container and stop patching the child
+    void hide();
+
+    // Show the container and patch the child, if any patch was skipped, while
+    // hidden
+    void show();
+
+    // Show or hide the container
+    void set_visible(bool visible) { visible ? show() : hide(); }
+
+    // Returns, if the container is visible
+    bool is_visible() const { return visible; }
+
+    // Returns the child view
+    View& get_child() { return *child; }
+
+protected:
+    // Attributes of the container. Any "id" and "hidden" are overridden.
+    virtual Attrs attrs() { return {}; }
+
+    void unmount();
+
+private:
+    const std::string tag;
+    std::shared_ptr<View> child;
+    bool visible;
+    bool is_initialized = false;
+
+    // Patching the child was skipped, while hidden
+    bool stale = false;
+};
+}
@@ -0,0 +1,33 @@
+#include "log.hh"
+#include <emscripten.h>
+
+namespace brunhild::log {
+
+static Level min_level = Level::debug;
+
+void set_level(Level l) { min_level = l; }
+
+Level get_level() { return min_level; }
+
+void emit(Level l, const std::string& msg)
+{
+    EM_ASM_INT(
+        {
+            var msg = UTF8ToString($1);
+            switch ($0) {
+            case 0:
+                console.debug(msg);
+                break;
+            case 1:
+                console.info(msg);
+                break;
+            case 2:
+                console.warn(msg);
+                break;
+            default:
+                console.error(msg);
+            }
+        },
+        static_cast<int>(l), msg.c_str());
+}
+}
@@ -0,0 +1,59 @@
+#pragma once
+
+#include "util.hh"
+#include <string>
+
+// Minimum level of messages compiled into the program. Messages below this
+// level are compiled out. Defaults to warnings in release builds and all
+// messages in debug builds.
+#ifndef BRUNHILD_LOG_LEVEL
+#ifdef NDEBUG
+#define BRUNHILD_LOG_LEVEL 2
+#else
+#define BRUNHILD_LOG_LEVEL 0
+#endif
+#endif
+
+// Log values to console.debug(). Accepts anything writable to Rope.
+#define BH_DEBUG(...)                                                          \
+    brunhild::log::write<0, BRUNHILD_LOG_LEVEL>(__VA_ARGS__)
+
+// Log values to console.info(). Accepts anything writable to Rope.
+#define BH_INFO(...) brunhild::log::write<1, BRUNHILD_LOG_LEVEL>(__VA_ARGS__)
+
+// Log values to console.warn(). Accepts anything writable to Rope.
+#define BH_WARN(...) brunhild::log::write<2, BRUNHILD_LOG_LEVEL>(__VA_ARGS__)
+
+// Log values to console.error(). Accepts anything writable to Rope.
+#define BH_ERROR(...) brunhild::log::write<3, BRUNHILD_LOG_LEVEL>(__VA_ARGS__)
+
+namespace brunhild::log {
+
+// Severity of a log message
+enum class Level { debug, info, warn, error };
+
+// Set the minimum level of messages logged at runtime. Messages below the
+// compile time BRUNHILD_LOG_LEVEL are never logged.
+void set_level(Level);
+
+// Returns the minimum level of messages logged at runtime
+Level get_level();
+
+// Write a message to the console at the specified level
+void emit(Level, const std::string& msg);
+
+// Concatenate values and write them to the console. Use the BH_* macros
+// instead of calling this directly.
+template <int level, int min_level, class... T>
+inline void write(const T&... args)
+{
+    if constexpr (level >= min_level) {
+        if (static_cast<int>(get_level()) > level) {
+            return;
+        }
+        Rope s;
+        (s << ... << args);
+        emit(static_cast<Level>(level), s.str());
+    }
+}
+}
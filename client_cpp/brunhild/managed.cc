@@ -0,0 +1,126 @@
+#include "managed.hh"
+#include <emscripten.h>
+
+using std::string;
+
+namespace brunhild {
+
+ManagedElement::~ManagedElement()
+{
+    while (attrs.size()) {
+        remove_attr(attrs.begin()->first);
+    }
+    for (auto& name : classes) {
+        set_class(name, false);
+    }
+}
+
+void ManagedElement::patch(Attrs new_attrs)
+{
+    new_attrs.erase("class");
+
+    std::vector<string> removed;
+    for (auto & [ key, _ ] : attrs) {
+        if (!new_attrs.count(key)) {
+            removed.push_back(key);
+        }
+    }
+    for (auto& key : removed) {
+        remove_attr(key);
+    }
+
+    for (auto & [ key, val ] : new_attrs) {
+        if (!attrs.count(key) || attrs.at(key) != val) {
+            set_attr(key, val);
+        }
+    }
+}
+
+void ManagedElement::set_classes(std::set<string> new_classes)
+{
+    for (auto& name : classes) {
+        if (!new_classes.count(name)) {
+            set_class(name, false);
+        }
+    }
+    for (auto& name : new_classes) {
+        if (!classes.count(name)) {
+            set_class(name, true);
+        }
+    }
+    classes = std::move(new_classes);
+}
+
+void ManagedElement::add_class(const string& name)
+{
+    if (classes.insert(name).second) {
+        set_class(name, true);
+    }
+}
+
+void ManagedElement::remove_class(const string& name)
+{
+    if (classes.erase(name)) {
+        set_class(name, false);
+    }
+}
+
+void ManagedElement::toggle_class(const string& name, bool enabled)
+{
+    if (enabled) {
+        add_class(name);
+    } else {
+        remove_class(name);
+    }
+}
+
+void ManagedElement::set_attr(const string& key, const string& val)
+{
+    attrs[key] = val;
+    EM_ASM_INT(
+        {
+            var el = document.querySelector(UTF8ToString($0));
+            if (el) {
+                el.setAttribute(UTF8ToString($1), UTF8ToString($2));
+            }
+        },
+        selector.c_str(), key.c_str(), val.c_str());
+}
+
+void ManagedElement::remove_attr(const string& key)
+{
+    attrs.erase(key);
+    EM_ASM_INT(
+        {
+            var el = document.querySelector(UTF8ToString($0));
+            if (el) {
+                el.removeAttribute(UTF8ToString($1));
+            }
+        },
+        selector.c_str(), key.c_str());
+}
+
+void ManagedElement::set_class(const string& name, bool enabled)
+{
+    EM_ASM_INT(
+        {
+            var el = document.querySelector(UTF8ToString($0));
+            if (el) {
+                el.classList.toggle(UTF8ToString($1), !!$2);
+            }
+        },
+        selector.c_str(), name.c_str(), enabled);
+}
+
+ManagedElement& body()
+{
+    static ManagedElement el("body");
+    return el;
+}
+
+ManagedElement& root_element()
+{
+    static ManagedElement el("html");
+    return el;
+}
+}
@@ -0,0 +1,59 @@
+#pragma once
+
+#include "node.hh"
+#include <set>
+#include <string>
+
+namespace brunhild {
+
+// Manages attributes and classes of a pre-existing element not created by
+// brunhild, like <body> or <html>. Only attributes and classes set through the
+// ManagedElement are ever modified. Other attributes, classes and the
+// element's children are left untouched. Changes are applied to the DOM
+// immediately. All managed attributes and classes are removed on destruction.
+class ManagedElement {
+public:
+    // CSS selector of the element. The first matching element is managed.
+    const std::string selector;
+
+    ManagedElement(std::string selector)
+        : selector(selector)
+    {
+    }
+
+    ~ManagedElement();
+
+    ManagedElement(const ManagedElement&) = delete;
+    ManagedElement& operator=(const ManagedElement&) = delete;
+
+    // Diff the managed attributes with new values and apply patches.
+    // The "class" attribute is ignored. Use set_classes() instead.
+    void patch(Attrs attrs);
+
+    // Diff the managed classes with new values and apply patches
+    void set_classes(std::set<std::string> classes);
+
+    // Add a single managed class
+    void add_class(const std::string& name);
+
+    // Remove a single managed class
+    void remove_class(const std::string& name);
+
+    // Add the class, if enabled is true, and remove it otherwise
+    void toggle_class(const std::string& name, bool enabled);
+
+private:
+    Attrs attrs;
+    std::set<std::string> classes;
+
+    void set_attr(const std::string& key, const std::string& val);
+    void remove_attr(const std::string& key);
+    void set_class(const std::string& name, bool enabled);
+};
+
+// Manages attributes and classes of <body>
+ManagedElement& body();
+
+// Manages attributes and classes of the root <html> element
+ManagedElement& root_element();
+}
@@ -0,0 +1,208 @@
+#include "markup.hh"
+#include <cctype>
+
+using std::string;
+using std::string_view;
+
+namespace brunhild {
+
+// Formatting toggled by a two character delimiter, in nesting order
+static const struct {
+    const char* delim;
+    const char* tag;
+} formats[] = { { "**", "del" }, { "@@", "b" }, { "~~", "i" } };
+
+static const size_t format_count = sizeof(formats) / sizeof(formats[0]);
+
+static Node default_post_link(unsigned long id)
+{
+    Attrs attrs = {
+        { "class", "post-link" },
+        { "href", "#p" + std::to_string(id) },
+    };
+    attrs.set_data("id", id);
+    return Node("a", attrs, "&gt;&gt;" + std::to_string(id));
+}
+
+static Node default_url_link(const string& url)
+{
+    return Node("a",
+        {
+            { "href", url },
+            { "target", "_blank" },
+            { "rel", "noopener noreferrer" },
+        },
+        url, true);
+}
+
+// Parser state of a single render_markup() call
+class Parser {
+public:
+    Parser(const MarkupOptions& opts)
+        : opts(opts)
+    {
+    }
+
+    // Parse a line and append the resulting nodes to out
+    void parse_line(string_view line, std::vector<Node>& out)
+    {
+        this->out = &out;
+        string buf;
+        size_t i = 0;
+        while (i < line.size()) {
+            if (i + 1 < line.size()) {
+                auto pair = line.substr(i, 2);
+                if (pair == "``") {
+                    flush(buf);
+                    code = !code;
+                    i += 2;
+                    continue;
+                }
+                if (!code && toggle(pair, buf)) {
+                    i += 2;
+                    continue;
+                }
+            }
+            buf += line[i++];
+        }
+        flush(buf);
+    }
+
+private:
+    const MarkupOptions& opts;
+    bool code = false;
+    bool active[format_count] = {};
+    std::vector<Node>* out = nullptr;
+
+    // Toggle formatting, if pair is a delimiter. Returns, if it is.
+    bool toggle(string_view pair, string& buf)
+    {
+        for (size_t i = 0; i < format_count; i++) {
+            if (pair == formats[i].delim) {
+                flush(buf);
+                active[i] = !active[i];
+                return true;
+            }
+        }
+        return false;
+    }
+
+    // Render buffered text with the active formatting
+    void flush(string& buf)
+    {
+        if (buf.empty()) {
+            return;
+        }
+        std::vector<Node> nodes;
+        if (code) {
+            nodes.push_back(Node("code", buf, true));
+        } else {
+            parse_links(buf, nodes);
+        }
+        buf.clear();
+
+        for (size_t i = format_count; i-- > 0;) {
+            if (active[i]) {
+                Node n(formats[i].tag);
+                n.children = std::move(nodes);
+                nodes = { std::move(n) };
+            }
+        }
+        for (auto& n : nodes) {
+            out->push_back(std::move(n));
+        }
+    }
+
+    // Split text into plain text and links
+    void parse_links(const string& text, std::vector<Node>& nodes)
+    {
+        string plain;
+        auto flush_plain = [&]() {
+            if (plain.size()) {
+                nodes.push_back(Node("span", plain, true));
+                plain.clear();
+            }
+        };
+
+        size_t i = 0;
+        while (i < text.size()) {
+            const bool word_start
+                = i == 0 || isspace((unsigned char)text[i - 1]);
+
+            // Post link
+            if (text.compare(i, 2, ">>") == 0 && i + 2 < text.size()
+                && isdigit((unsigned char)text[i + 2])) {
+                size_t end = i + 2;
+                while (
+                    end < text.size() && isdigit((unsigned char)text[end])) {
+                    end++;
+                }
+                const auto id = strtoul(text.c_str() + i + 2, nullptr, 10);
+                flush_plain();
+                nodes.push_back(opts.post_link ? opts.post_link(id)
+                                               : default_post_link(id));
+                i = end;
+                continue;
+            }
+
+            // URL
+            if (word_start
+                && (text.compare(i, 7, "http://") == 0
+                       || text.compare(i, 8, "https://") == 0)) {
+                size_t end = i;
+                while (
+                    end < text.size() && !isspace((unsigned char)text[end])) {
+                    end++;
+                }
+                const auto url = text.substr(i, end - i);
+                flush_plain();
+                nodes.push_back(
+                    opts.url_link ? opts.url_link(url) : default_url_link(url));
+                i = end;
+                continue;
+            }
+
+            plain += text[i++];
+        }
+        flush_plain();
+    }
+};
+
+Node render_markup(string_view body, const MarkupOptions& opts)
+{
+    Node root(opts.tag);
+    Parser parser(opts);
+    unsigned successive_newlines = 0;
+    bool first = true;
+
+    while (true) {
+        const auto i = body.find('\n');
+        const auto line = body.substr(0, i);
+
+        // Prevent more than two successive empty lines
+        if (!first && successive_newlines < 2) {
+            root.children.push_back(Node("br"));
+        }
+        first = false;
+
+        if (line.empty()) {
+            successive_newlines++;
+        } else {
+            successive_newlines = 0;
+            if (line[0] == '>') {
+                Node quote("em");
+                parser.parse_line(line, quote.children);
+                root.children.push_back(std::move(quote));
+            } else {
+                parser.parse_line(line, root.children);
+            }
+        }
+
+        if (i == string_view::npos) {
+            break;
+        }
+        body = body.substr(i + 1);
+    }
+    return root;
+}
+}
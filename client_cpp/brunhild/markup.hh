@@ -0,0 +1,38 @@
+#pragma once
+
+#include "node.hh"
+#include <functional>
+#include <string>
+#include <string_view>
+
+// Renders post bodies written in meguca's formatting dialect to Nodes:
+//
+//     >quote          Line starting with ">", rendered in <em>
+//     >>123           Link to post 123
+//     https://...     Link to a URL
+//     **spoiler**     <del>
+//     @@bold@@        <b>
+//     ~~italic~~      <i>
+//     ``code``        <code>, with no formatting inside
+//
+// Formatting can span lines. All text is escaped, so the output is safe to
+// render and diffs like any other subtree. Link elements can be given event
+// handlers by delegating from the containing view with View::on() and a
+// selector matching them.
+namespace brunhild {
+
+struct MarkupOptions {
+    // Tag of the root element
+    std::string tag = "blockquote";
+
+    // Renders a link to a post. Defaults to an <a class="post-link"> with a
+    // data-id attribute set to the post ID.
+    std::function<Node(unsigned long id)> post_link;
+
+    // Renders a link to a URL. Defaults to an <a> opening in a new tab.
+    std::function<Node(const std::string& url)> url_link;
+};
+
+// Render a post body to a Node
+Node render_markup(std::string_view body, const MarkupOptions& opts = {});
+}
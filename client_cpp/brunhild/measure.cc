@@ -0,0 +1,102 @@
+#include "measure.hh"
+#include <emscripten.h>
+#include <emscripten/val.h>
+#include <stdlib.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+// Install a shared canvas context for text measurement
+static val context()
+{
+    EM_ASM({
+        if (!window.__bh_measure) {
+            window.__bh_measure
+                = document.createElement("canvas").getContext("2d");
+        }
+    });
+    return val::global("__bh_measure");
+}
+
+Size measure_text(const string& text, const string& font)
+{
+    auto ctx = context();
+    ctx.set("font", font);
+    auto m = ctx.call<val>("measureText", text);
+    return Size{
+        m["width"].as<double>(),
+        m["actualBoundingBoxAscent"].as<double>()
+            + m["actualBoundingBoxDescent"].as<double>(),
+    };
+}
+
+string truncate_text(const string& text, const string& font, double width,
+    const string& ellipsis)
+{
+    context();
+    char* s = (char*)EM_ASM_INT(
+        {
+            var ctx = window.__bh_measure;
+            var text = UTF8ToString($0);
+            var ellipsis = UTF8ToString($2);
+            var width = $3;
+            ctx.font = UTF8ToString($1);
+
+            var res = text;
+            if (ctx.measureText(text).width > width) {
+                // Binary search for the longest prefix, that fits with the
+                // ellipsis. Split by code point to not break surrogate pairs.
+                var chars = Array.from(text);
+                var lo = 0;
+                var hi = chars.length;
+                while (lo < hi) {
+                    var mid = Math.ceil((lo + hi) / 2);
+                    var w = ctx.measureText(chars.slice(0, mid).join("")
+                        + ellipsis).width;
+                    if (w <= width) {
+                        lo = mid;
+                    } else {
+                        hi = mid - 1;
+                    }
+                }
+                res = chars.slice(0, lo).join("").trimEnd() + ellipsis;
+            }
+
+            var len = lengthBytesUTF8(res) + 1;
+            var buf = Module._malloc(len);
+            stringToUTF8(res, buf, len);
+            return buf;
+        },
+        text.c_str(), font.c_str(), ellipsis.c_str(), width);
+    string res(s);
+    free(s);
+    return res;
+}
+
+Size measure_node(Node node, double max_width)
+{
+    EM_ASM({
+        if (window.__bh_measure_node) {
+            return;
+        }
+        window.__bh_measure_node = function(html, max_width)
+        {
+            var c = document.createElement("div");
+            c.style.cssText = "position:absolute;visibility:hidden;"
+                + "pointer-events:none;left:-10000px;top:0;"
+                + (max_width ? "width:" + max_width + "px;"
+                             : "width:max-content;");
+            c.innerHTML = html;
+            document.body.appendChild(c);
+            var el = c.firstElementChild || c;
+            var r = el.getBoundingClientRect();
+            document.body.removeChild(c);
+            return { width : r.width, height : r.height };
+        };
+    });
+    auto r = val::global("__bh_measure_node")(node.html(), max_width);
+    return Size{ r["width"].as<double>(), r["height"].as<double>() };
+}
+}
@@ -0,0 +1,25 @@
+#pragma once
+
+#include "node.hh"
+#include "ref.hh"
+#include <string>
+
+namespace brunhild {
+
+// Measure the rendered width and height of a single line of text in CSS
+// pixels using a canvas context. Does not touch the DOM.
+// font: CSS font shorthand, like "bold 14px sans-serif"
+Size measure_text(const std::string& text, const std::string& font);
+
+// Truncate text to fit into width CSS pixels, when rendered with font,
+// appending ellipsis, if truncated. Returns text unchanged, if it fits.
+std::string truncate_text(const std::string& text, const std::string& font,
+    double width, const std::string& ellipsis = "…");
+
+// Measure the rendered size of a Node and its subtree, as it would be
+// rendered as a child of <body>, by inserting it into a hidden element.
+// Useful for sizing popups before inserting them. Forces a synchronous
+// layout.
+// max_width: width in CSS pixels to wrap content at. 0 for no limit.
+Size measure_node(Node node, double max_width = 0);
+}
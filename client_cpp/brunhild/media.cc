@@ -0,0 +1,84 @@
+#include "media.hh"
+#include "schedule.hh"
+#include "view.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All active subscriptions by ID
+static std::unordered_map<long, MediaQuery*> subscriptions;
+
+bool matches_media(const string& query)
+{
+    return (bool)EM_ASM_INT(
+        { return window.matchMedia(UTF8ToString($0)).matches ? 1 : 0; },
+        query.c_str());
+}
+
+MediaQuery::MediaQuery(string query, std::function<void(bool)> handler)
+    : query(query)
+    , sub_id(id_counter++)
+    , handler(handler)
+{
+    subscriptions[sub_id] = this;
+    current = (bool)EM_ASM_INT(
+        {
+            if (!window.__bh_media) {
+                window.__bh_media = {};
+            }
+            var mql = window.matchMedia(UTF8ToString($0));
+            var id = $1;
+            var fn = function(e) { Module._bh_media_changed(id, e.matches); };
+            window.__bh_media[id] = { mql : mql, fn : fn };
+            if (mql.addEventListener) {
+                mql.addEventListener("change", fn);
+            } else {
+                mql.addListener(fn); // Safari < 14
+            }
+            return mql.matches ? 1 : 0;
+        },
+        query.c_str(), sub_id);
+}
+
+MediaQuery::MediaQuery(string query, View& view)
+    : MediaQuery(query, [id = view.id](bool) { mark_dirty(id); })
+{
+}
+
+MediaQuery::~MediaQuery()
+{
+    subscriptions.erase(sub_id);
+    EM_ASM_INT(
+        {
+            var sub = window.__bh_media[$0];
+            if (sub.mql.removeEventListener) {
+                sub.mql.removeEventListener("change", sub.fn);
+            } else {
+                sub.mql.removeListener(sub.fn);
+            }
+            delete window.__bh_media[$0];
+        },
+        sub_id);
+}
+
+void run_media_handler(long id, bool matches)
+{
+    if (!subscriptions.count(id)) {
+        return;
+    }
+    auto sub = subscriptions.at(id);
+    sub->current = matches;
+    sub->handler(matches);
+}
+
+EMSCRIPTEN_BINDINGS(module_media)
+{
+    emscripten::function("_bh_media_changed", &run_media_handler);
+}
+}
@@ -0,0 +1,43 @@
+#pragma once
+
+#include <functional>
+#include <string>
+
+namespace brunhild {
+
+class View;
+
+// Evaluate a media query against the current state of the document
+bool matches_media(const std::string& query);
+
+// Subscription to changes of a media query result, like
+// "(prefers-color-scheme: dark)" or "(max-width: 600px)".
+// Unsubscribes on destruction.
+class MediaQuery {
+public:
+    // Media query string
+    const std::string query;
+
+    // Subscribe handler to changes of the query result. The handler receives
+    // the new result.
+    MediaQuery(std::string query, std::function<void(bool)> handler);
+
+    // Mark view dirty, whenever the query result changes
+    MediaQuery(std::string query, View& view);
+
+    ~MediaQuery();
+
+    MediaQuery(const MediaQuery&) = delete;
+    MediaQuery& operator=(const MediaQuery&) = delete;
+
+    // Returns the current result of the query
+    bool matches() const { return current; }
+
+private:
+    const long sub_id;
+    bool current;
+    std::function<void(bool)> handler;
+
+    friend void run_media_handler(long, bool);
+};
+}
@@ -0,0 +1,280 @@
+#include "media_viewer.hh"
+#include "events.hh"
+#include "modal.hh"
+#include "schedule.hh"
+#include <algorithm>
+#include <cmath>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+MediaViewer::MediaViewer(
+    std::vector<MediaItem> items, size_t index, ViewerOptions opts, string id)
+    : VirtualView(id)
+    , items(std::move(items))
+    , opts(opts)
+    , current(index < this->items.size() ? index : 0)
+{
+    autofocus = this->id;
+    zoom_scale = opts.min_scale;
+
+    on("click", ".bh-viewer-prev", [this](val&) { prev(); });
+    on("click", ".bh-viewer-next", [this](val&) { next(); });
+    on("click", ".bh-viewer-close", [this](val&) { close(); });
+
+    ListenerOptions active;
+    active.passive = false;
+    on("wheel", ".bh-viewer-image", directed([this](val& e) {
+        zoom(e["deltaY"].as<double>() < 0 ? this->opts.wheel_step
+                                          : 1 / this->opts.wheel_step,
+            e["clientX"].as<double>(), e["clientY"].as<double>());
+        return Directive{ true };
+    }),
+        active);
+}
+
+Node MediaViewer::render()
+{
+    Attrs attrs = { { "class", "bh-viewer" }, { "tabindex", "-1" } };
+    attrs.aria_label(opts.label);
+    Node n("div", attrs);
+    if (items.empty()) {
+        return n;
+    }
+
+    const auto& item = items[current];
+    Rope style;
+    style << "transform:translate(" << offset_x << "px," << offset_y
+          << "px) scale(" << zoom_scale << ");touch-action:none";
+    auto img = Attrs::of({ Attr::id(id + "-img"),
+        Attr::class_("bh-viewer-image"), Attr::src(item.src),
+        Attr::alt(item.alt) });
+    img["draggable"] = "false";
+    img["style"] = style.str();
+    n.children.push_back(Node("img", img));
+
+    if (items.size() > 1) {
+        n.children.push_back(Node("span", { { "class", "bh-viewer-counter" } },
+            std::to_string(current + 1) + " / "
+                + std::to_string(items.size())));
+        Attrs prev = { { "class", "bh-viewer-prev" } };
+        Attrs next = { { "class", "bh-viewer-next" } };
+        if (!opts.loop) {
+            if (current == 0) {
+                prev.set(Attr::disabled(true));
+            }
+            if (current + 1 == items.size()) {
+                next.set(Attr::disabled(true));
+            }
+        }
+        n.children.push_back(
+            Node("button", prev.aria_label(opts.prev_label), "&lsaquo;"));
+        n.children.push_back(
+            Node("button", next.aria_label(opts.next_label), "&rsaquo;"));
+    }
+    n.children.push_back(Node("button",
+        Attrs({ { "class", "bh-viewer-close" } }).aria_label(opts.close_label),
+        "&times;"));
+    return n;
+}
+
+void MediaViewer::show(size_t index)
+{
+    if (index >= items.size()) {
+        return;
+    }
+    current = index;
+    pointers.clear();
+    reset();
+}
+
+void MediaViewer::next()
+{
+    if (current + 1 < items.size()) {
+        show(current + 1);
+    } else if (opts.loop) {
+        show(0);
+    }
+}
+
+void MediaViewer::prev()
+{
+    if (current > 0) {
+        show(current - 1);
+    } else if (opts.loop && items.size()) {
+        show(items.size() - 1);
+    }
+}
+
+void MediaViewer::zoom(double factor, double x, double y)
+{
+    const double scale
+        = std::clamp(zoom_scale * factor, opts.min_scale, opts.max_scale);
+    if (scale == zoom_scale) {
+        return;
+    }
+    if (scale == opts.min_scale) {
+        reset();
+        return;
+    }
+
+    // The image is scaled around its center, which is offset from the
+    // center of the viewport
+    auto w = val::global("window");
+    const double cx = w["innerWidth"].as<double>() / 2 + offset_x;
+    const double cy = w["innerHeight"].as<double>() / 2 + offset_y;
+    const double ratio = scale / zoom_scale;
+    offset_x += (x - cx) * (1 - ratio);
+    offset_y += (y - cy) * (1 - ratio);
+    zoom_scale = scale;
+    mark_dirty(*this);
+}
+
+void MediaViewer::pan(double dx, double dy)
+{
+    if (zoom_scale == opts.min_scale) {
+        return;
+    }
+    offset_x += dx;
+    offset_y += dy;
+    mark_dirty(*this);
+}
+
+void MediaViewer::reset()
+{
+    zoom_scale = opts.min_scale;
+    offset_x = offset_y = 0;
+    mark_dirty(*this);
+}
+
+void MediaViewer::close()
+{
+    if (dialog_id != -1) {
+        modal::close(dialog_id);
+    }
+}
+
+void MediaViewer::move_pointer(const Pointer& p)
+{
+    auto it = pointers.find(p.id);
+    if (it == pointers.end()) {
+        return;
+    }
+    const auto [ x, y ] = it->second;
+
+    if (pointers.size() == 2) {
+        // Pinch around the midpoint of both pointers and pan with it
+        auto other = pointers.begin();
+        if (other == it) {
+            ++other;
+        }
+        const auto [ ox, oy ] = other->second;
+        const double before = std::hypot(x - ox, y - oy);
+        const double after = std::hypot(p.x - ox, p.y - oy);
+        if (before > 0) {
+            zoom(after / before, (p.x + ox) / 2, (p.y + oy) / 2);
+        }
+        pan((p.x - x) / 2, (p.y - y) / 2);
+    } else if (pointers.size() == 1) {
+        pan(p.x - x, p.y - y);
+    }
+    it->second = { p.x, p.y };
+}
+
+void MediaViewer::on_mount()
+{
+    drag = std::make_unique<PointerDrag>(
+        '#' + id + "-img",
+        [this](const Pointer& p) { pointers[p.id] = { p.x, p.y }; },
+        [this](const Pointer& p) { move_pointer(p); },
+        [this](const Pointer& p, bool) { pointers.erase(p.id); });
+
+    hotkeys::Options scoped;
+    scoped.scope = id;
+    const std::pair<const char*, std::function<void()>> bindings[] = {
+        { "ArrowLeft", [this]() { prev(); } },
+        { "ArrowRight", [this]() { next(); } },
+        { "Home", [this]() { show(0); } },
+        { "End", [this]() { show(items.size() - 1); } },
+        { "0", [this]() { reset(); } },
+    };
+    for (auto & [ combo, fn ] : bindings) {
+        keys.push_back(std::make_unique<hotkeys::Hotkey>(combo, fn, scoped));
+    }
+}
+
+void MediaViewer::on_unmount()
+{
+    drag.reset();
+    keys.clear();
+    pointers.clear();
+}
+
+long open_viewer(std::vector<MediaItem> items, size_t index, ViewerOptions opts)
+{
+    modal::Options m;
+    m.label = opts.label;
+    m.class_name = "bh-viewer-dialog";
+    auto v = std::make_shared<MediaViewer>(std::move(items), index, opts);
+    v->dialog_id = modal::open(v, m);
+    return v->dialog_id;
+}
+
+// Read the gallery item of an element
+static MediaItem read_item(val el)
+{
+    MediaItem item;
+    for (auto attr : { "data-src", "href", "src" }) {
+        auto v = el.call<val>("getAttribute", string(attr));
+        if (v.isString()) {
+            item.src = v.as<string>();
+            break;
+        }
+    }
+    for (auto attr : { "alt", "title" }) {
+        auto v = el.call<val>("getAttribute", string(attr));
+        if (v.isString()) {
+            item.alt = v.as<string>();
+            break;
+        }
+    }
+    return item;
+}
+
+long expand_on_click(const string& selector, ViewerOptions opts)
+{
+    ListenerOptions active;
+    active.passive = false;
+    return register_handler("click",
+        directed([=](val& e) {
+            if (e["button"].as<int>() != 0 || e["ctrlKey"].as<bool>()
+                || e["metaKey"].as<bool>() || e["shiftKey"].as<bool>()
+                || e["altKey"].as<bool>()) {
+                return Directive{};
+            }
+            auto target = e["composedPath"].isUndefined()
+                ? e["target"]
+                : e.call<val>("composedPath")[0];
+            auto els = val::global("Array").call<val>("from",
+                val::global("document")
+                    .call<val>("querySelectorAll", selector));
+            std::vector<MediaItem> items;
+            size_t index = 0;
+            const auto len = els["length"].as<size_t>();
+            items.reserve(len);
+            for (size_t i = 0; i < len; i++) {
+                auto el = els[i];
+                if (el.strictlyEquals(target)) {
+                    index = i;
+                }
+                items.push_back(read_item(el));
+            }
+            open_viewer(std::move(items), index, opts);
+            return Directive{ true };
+        }),
+        selector, active);
+}
+}
@@ -0,0 +1,129 @@
+#pragma once
+
+#include "hotkeys.hh"
+#include "pointer.hh"
+#include "view.hh"
+#include <memory>
+#include <string>
+#include <unordered_map>
+#include <vector>
+
+// Image viewer with zooming and panning for galleries, opened in a modal
+// dialog above the page. Zooms with the mouse wheel or by pinching and pans
+// by dragging the zoomed image with any pointer. Arrow keys, Home and End
+// switch images, 0 resets the zoom and Escape closes the viewer.
+//
+// Rendered structure, for styling:
+//
+//     <div class="bh-viewer">
+//         <img class="bh-viewer-image">
+//         <span class="bh-viewer-counter">1 / 3</span>
+//         <button class="bh-viewer-prev"></button>
+//         <button class="bh-viewer-next"></button>
+//         <button class="bh-viewer-close"></button>
+//     </div>
+namespace brunhild {
+
+// Image of a gallery
+struct MediaItem {
+    // URL of the full size image
+    std::string src;
+
+    // Alternative text
+    std::string alt;
+};
+
+// Options of a MediaViewer
+struct ViewerOptions {
+    // Bounds of the zoom level relative to the image's fitted size
+    double min_scale = 1, max_scale = 8;
+
+    // Zoom factor of one mouse wheel step
+    double wheel_step = 1.25;
+
+    // Wrap around from the last image to the first and vice versa
+    bool loop = false;
+
+    // Accessible labels of the viewer and its buttons
+    std::string label = "Image viewer", prev_label = "Previous image",
+                next_label = "Next image", close_label = "Close";
+};
+
+// Views a gallery of images one at a time
+class MediaViewer : public VirtualView {
+public:
+    MediaViewer(std::vector<MediaItem> items, size_t index = 0,
+        ViewerOptions opts = {}, std::string id = new_id());
+
+    Node render();
+
+    // Returns the index of the shown image
+    size_t index() const { return current; }
+
+    // Returns the zoom level
+    double scale() const { return zoom_scale; }
+
+    // Show the image at index and reset the zoom. Out of range indices are
+    // ignored.
+    void show(size_t index);
+
+    // Show the next image
+    void next();
+
+    // Show the previous image
+    void prev();
+
+    // Multiply the zoom level by factor within the bounds of the options,
+    // keeping the point at viewport coordinates x, y in place
+    void zoom(double factor, double x, double y);
+
+    // Move the zoomed image by dx, dy CSS pixels
+    void pan(double dx, double dy);
+
+    // Reset zoom and panning
+    void reset();
+
+    // Close the dialog of the viewer, if opened with open_viewer()
+    void close();
+
+protected:
+    // Bind pointer and keyboard handlers and focus the viewer
+    void on_mount();
+
+    // Release pointer and keyboard handlers
+    void on_unmount();
+
+private:
+    const std::vector<MediaItem> items;
+    const ViewerOptions opts;
+    size_t current;
+    double zoom_scale = 1, offset_x = 0, offset_y = 0;
+
+    // ID of the containing dialog or -1
+    long dialog_id = -1;
+
+    // Last positions of pointers on the image by ID
+    std::unordered_map<long, std::pair<double, double>> pointers;
+
+    std::unique_ptr<PointerDrag> drag;
+    std::vector<std::unique_ptr<hotkeys::Hotkey>> keys;
+
+    // Update the pan and pinch state with a moved pointer
+    void move_pointer(const Pointer& p);
+
+    friend long open_viewer(std::vector<MediaItem>, size_t, ViewerOptions);
+};
+
+// Open a MediaViewer of a gallery starting at index in a modal dialog.
+// Returns dialog ID for modal::close().
+long open_viewer(
+    std::vector<MediaItem> items, size_t index = 0, ViewerOptions opts = {});
+
+// Open a MediaViewer on clicks on elements matching a CSS selector. All
+// matching elements in document order form the gallery. The image URL is read
+// from the "data-src", "href" or "src" attribute of an element, in that order,
+// and the alternative text from its "alt" or "title" attribute. Clicks with
+// modifier keys are left to the browser. Returns handler ID for
+// unregister_handler().
+long expand_on_click(const std::string& selector, ViewerOptions opts = {});
+}
@@ -0,0 +1,225 @@
+#include "modal.hh"
+#include "focus_trap.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <vector>
+
+using std::string;
+
+namespace brunhild::modal {
+
+static long id_counter = 0;
+static int base_z_index = 1000;
+
+// ID of the element containing all dialogs
+static const char* const root_id = "bh-modals";
+
+// Dialog frame with a backdrop
+class Frame : public View {
+public:
+    const long modal_id;
+    const Options opts;
+    const std::function<void()> on_close;
+
+    Frame(long modal_id, std::shared_ptr<View> content, Options opts,
+        std::function<void()> on_close, int z_index)
+        : modal_id(modal_id)
+        , opts(opts)
+        , on_close(on_close)
+        , z_index(z_index)
+        , trap(std::make_shared<FocusTrap>(content))
+    {
+        on("click", ".bh-modal-backdrop", [modal_id](emscripten::val&) {
+            close_backdrop(modal_id);
+        });
+    }
+
+    void write_html(Rope& s)
+    {
+        schedule_mount();
+
+        Attrs dialog = { { "class", "bh-modal-dialog" } };
+        if (opts.class_name != "") {
+            dialog["class"] += ' ' + opts.class_name;
+        }
+        dialog.role(Role::dialog).aria_modal(true);
+        if (opts.labelledby != "") {
+            dialog.aria_labelledby(opts.labelledby);
+        } else if (opts.label != "") {
+            dialog.aria_label(opts.label);
+        }
+
+        s << "<div id=\"" << id << "\" class=\"bh-modal\" style=\"z-index:"
+          << z_index << "\"><div class=\"bh-modal-backdrop\"></div><div";
+        dialog.write_html(s);
+        s << '>';
+        trap->write_html(s);
+        s << "</div></div>";
+    }
+
+    void patch() { trap->patch(); }
+
+protected:
+    void unmount()
+    {
+        unmount_child(*trap);
+        View::unmount();
+    }
+
+private:
+    const int z_index;
+    std::shared_ptr<FocusTrap> trap;
+
+    static void close_backdrop(long id);
+};
+
+// Open dialogs, topmost last
+static std::vector<std::shared_ptr<Frame>> stack;
+
+// Number of open dialogs locking scroll
+static size_t scroll_locks = 0;
+
+void set_base_z_index(int z) { base_z_index = z; }
+
+// Lock or unlock scrolling of the page body. Compensates for the removed
+// scrollbar to avoid the layout shifting.
+static void lock_scroll(bool lock)
+{
+    EM_ASM_INT(
+        {
+            var s = document.body.style;
+            if ($0) {
+                var w
+                    = window.innerWidth - document.documentElement.clientWidth;
+                window.__bh_modal_scroll = {
+                    overflow : s.overflow,
+                    paddingRight : s.paddingRight
+                };
+                s.overflow = "hidden";
+                if (w > 0) {
+                    s.paddingRight = w + "px";
+                }
+            } else if (window.__bh_modal_scroll) {
+                s.overflow = window.__bh_modal_scroll.overflow;
+                s.paddingRight = window.__bh_modal_scroll.paddingRight;
+                delete window.__bh_modal_scroll;
+            }
+        },
+        lock);
+}
+
+// Create the dialog container and the Escape key listener, if needed
+static void install()
+{
+    EM_ASM_INT(
+        {
+            if (document.getElementById(UTF8ToString($0))) {
+                return;
+            }
+            var el = document.createElement("div");
+            el.id = UTF8ToString($0);
+            document.body.appendChild(el);
+            if (!window.__bh_modal_keys) {
+                window.__bh_modal_keys = true;
+                document.addEventListener("keydown", function(e) {
+                    if (e.key == "Escape" && !e.defaultPrevented
+                        && Module._bh_modal_escape()) {
+                        e.preventDefault();
+                    }
+                });
+            }
+        },
+        root_id);
+}
+
+long open(
+    std::shared_ptr<View> content, Options opts, std::function<void()> on_close)
+{
+    install();
+    const long id = id_counter++;
+    const int z = base_z_index + 10 * int(stack.size());
+    stack.push_back(std::make_shared<Frame>(id, content, opts, on_close, z));
+    append(root_id, stack.back()->html());
+    if (opts.lock_scroll && !scroll_locks++) {
+        lock_scroll(true);
+    }
+    return id;
+}
+
+void close(long id)
+{
+    for (auto it = stack.begin(); it != stack.end(); it++) {
+        if ((*it)->modal_id != id) {
+            continue;
+        }
+        auto f = std::move(*it);
+        stack.erase(it);
+        f->remove();
+
+        // Might be called from one of the frame's own event handlers, so keep
+        // it alive until the handler returns
+        defer([f]() {});
+        if (f->opts.lock_scroll && !--scroll_locks) {
+            lock_scroll(false);
+        }
+        if (f->on_close) {
+            f->on_close(); // Might open or close other dialogs
+        }
+        return;
+    }
+}
+
+void close_top()
+{
+    if (stack.size()) {
+        close(stack.back()->modal_id);
+    }
+}
+
+void close_all()
+{
+    while (stack.size()) {
+        close_top();
+    }
+}
+
+bool is_open(long id)
+{
+    for (auto& f : stack) {
+        if (f->modal_id == id) {
+            return true;
+        }
+    }
+    return false;
+}
+
+size_t count() { return stack.size(); }
+
+void Frame::close_backdrop(long id)
+{
+    for (auto& f : stack) {
+        if (f->modal_id == id) {
+            if (f->opts.dismiss_on_backdrop) {
+                close(id);
+            }
+            return;
+        }
+    }
+}
+
+// Close the topmost dialog, if it can be dismissed with Escape. Returns, if
+// closed.
+static bool escape_pressed()
+{
+    if (stack.empty() || !stack.back()->opts.dismiss_on_escape) {
+        return false;
+    }
+    close_top();
+    return true;
+}
+
+EMSCRIPTEN_BINDINGS(module_modal)
+{
+    emscripten::function("_bh_modal_escape", &escape_pressed);
+}
+}
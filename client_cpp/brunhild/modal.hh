@@ -0,0 +1,67 @@
+#pragma once
+
+#include "view.hh"
+#include <functional>
+#include <memory>
+#include <string>
+
+// Stacked modal dialogs. Dialogs are rendered into a container appended to
+// the document body, each above a backdrop and inside a FocusTrap. Stacking
+// order, Escape key handling and body scroll locking are managed centrally.
+//
+// Rendered structure, for styling:
+//
+//     <div class="bh-modal" style="z-index:...">
+//         <div class="bh-modal-backdrop"></div>
+//         <div class="bh-modal-dialog" role="dialog" aria-modal="true">
+//             content
+//         </div>
+//     </div>
+namespace brunhild::modal {
+
+// Options of a dialog
+struct Options {
+    // Close the topmost dialog on Escape
+    bool dismiss_on_escape = true;
+
+    // Close the dialog on clicking its backdrop
+    bool dismiss_on_backdrop = true;
+
+    // Prevent scrolling of the page, while the dialog is open
+    bool lock_scroll = true;
+
+    // Accessible label of the dialog. Ignored, if labelledby is set.
+    std::string label;
+
+    // ID of an element in the content labeling the dialog
+    std::string labelledby;
+
+    // Additional class of the dialog element
+    std::string class_name;
+};
+
+// Set the z-index of the lowest dialog. Each stacked dialog is placed 10 above
+// the one below. Defaults to 1000.
+void set_base_z_index(int);
+
+// Open a dialog rendering content and place it on top of the stack.
+// on_close is optional and called after the dialog has been closed by any
+// means. Returns dialog ID.
+long open(std::shared_ptr<View> content, Options opts = {},
+    std::function<void()> on_close = nullptr);
+
+// Close a dialog by ID. No-op, if not open.
+void close(long id);
+
+// Close the topmost dialog
+void close_top();
+
+// Close all dialogs
+void close_all();
+
+// Returns, if a dialog is open
+bool is_open(long id);
+
+// Returns the number of open dialogs
+size_t count();
+}
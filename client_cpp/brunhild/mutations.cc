@@ -1,5 +1,15 @@
 #include "mutations.hh"
+#include "debug.hh"
+#include "headless.hh"
+#include "perf.hh"
+#include "perf_warnings.hh"
+#include "runtime.hh"
+#include "schedule.hh"
+#include "tween.hh"
+#include "view.hh"
 #include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
 #include <optional>
 #include <unordered_map>
 #include <unordered_set>
@@ -11,12 +21,19 @@ using std::string;
 // Pending mutations for an element
 struct Mutations {
     bool remove_el = false, scroll_into_view = false;
+    ScrollOptions scroll_opts;
     std::optional<std::string> set_inner_html, set_outer_html;
     std::vector<std::string> append, prepend, before, after, move_prepend,
         move_after;
     std::unordered_set<std::string> remove_attr;
     std::unordered_map<std::string, std::string> set_attr;
 
+    // Classes to add (true) or remove (false)
+    std::unordered_map<std::string, bool> toggle_class;
+    std::unordered_map<std::string, std::string> set_style;
+    std::unordered_map<std::string, emscripten::val> set_prop;
+    std::vector<std::pair<std::string, emscripten::val>> dispatch;
+
     // Clear mutations of element inner content to free up memory
     void free_inner();
 
@@ -25,6 +42,10 @@ struct Mutations {
 
     // Execute buffered mutations
     void exec(const std::string& id);
+
+    // Log buffered mutations together with the current state of the element.
+    // Must be called before exec() has modified the element.
+    void log(const std::string& id);
 };
 
 void (*before_flush)() = nullptr;
@@ -40,6 +61,9 @@ static std::unordered_map<string, Mutations> mutations;
 // manipulated, before insertion
 static std::vector<std::string> mutation_order;
 
+// Functions to run after the next flush
+static std::vector<std::function<void()>> deferred;
+
 // Fetches a mutation set by element ID or creates a new one ond registers
 // its execution order
 static Mutations* get_mutation_set(string id)
@@ -79,7 +103,7 @@ void move_prepend(string parent_id, string child_id)
 // Move child node after a sibling in the parent
 void move_after(string sibling_id, string child_id)
 {
-    get_mutation_set(sibling_id)->move_prepend.push_back(child_id);
+    get_mutation_set(sibling_id)->move_after.push_back(child_id);
 }
 
 void set_inner_html(string id, string html)
@@ -106,19 +130,205 @@ void remove(string id)
 
 void set_attr(string id, string key, string val)
 {
-    get_mutation_set(id)->set_attr[key] = val;
+    auto mut = get_mutation_set(id);
+    mut->set_attr[key] = val;
+    // Overwritten by the full attribute
+    if (key == "class") {
+        mut->toggle_class.clear();
+    } else if (key == "style") {
+        mut->set_style.clear();
+    }
 }
 
 void remove_attr(string id, string key)
 {
     auto mut = get_mutation_set(id);
     mut->set_attr.erase(key);
-    mut->remove_attr.insert(id);
+    mut->remove_attr.insert(key);
+}
+
+void add_class(string id, string name)
+{
+    get_mutation_set(id)->toggle_class[name] = true;
+}
+
+void remove_class(string id, string name)
+{
+    get_mutation_set(id)->toggle_class[name] = false;
+}
+
+void set_style(string id, string property, string val)
+{
+    get_mutation_set(id)->set_style[property] = val;
+}
+
+void set_prop(string id, string key, emscripten::val val)
+{
+    get_mutation_set(id)->set_prop[key] = val;
+}
+
+void dispatch_event(string id, string type, emscripten::val detail)
+{
+    get_mutation_set(id)->dispatch.push_back({ type, detail });
+}
+
+void scroll_into_view(string id, ScrollOptions opts)
+{
+    auto mut = get_mutation_set(id);
+    mut->scroll_into_view = true;
+    mut->scroll_opts = opts;
+}
+
+void defer(std::function<void()> fn) { deferred.push_back(fn); }
+
+// Convert a vector of strings to a JS array
+static emscripten::val to_array(const std::vector<string>& vec)
+{
+    auto arr = emscripten::val::array();
+    for (size_t i = 0; i < vec.size(); i++) {
+        arr.set(i, vec[i]);
+    }
+    return arr;
+}
+
+// Convert a JS array to a vector of strings
+static std::vector<string> from_array(emscripten::val arr)
+{
+    if (arr.isUndefined()) {
+        return {};
+    }
+    return emscripten::vecFromJSArray<string>(arr);
+}
+
+// Returns the own enumerable keys of a JS object
+static std::vector<string> keys(emscripten::val obj)
+{
+    return from_array(
+        emscripten::val::global("Object").call<emscripten::val>("keys", obj));
+}
+
+emscripten::val take_mutations()
+{
+    using emscripten::val;
+
+    auto arr = val::array();
+    size_t i = 0;
+    for (auto& id : mutation_order) {
+        auto& m = mutations.at(id);
+        auto o = val::object();
+        o.set("id", id);
+        if (m.remove_el) {
+            o.set("remove", true);
+        }
+        if (m.set_outer_html) {
+            o.set("outer", *m.set_outer_html);
+        }
+        if (m.set_inner_html) {
+            o.set("inner", *m.set_inner_html);
+        }
+        o.set("append", to_array(m.append));
+        o.set("prepend", to_array(m.prepend));
+        o.set("before", to_array(m.before));
+        o.set("after", to_array(m.after));
+        o.set("move_prepend", to_array(m.move_prepend));
+        o.set("move_after", to_array(m.move_after));
+        auto attrs = val::object();
+        for (auto & [ k, v ] : m.set_attr) {
+            attrs.set(k, v);
+        }
+        o.set("set_attr", attrs);
+        o.set("remove_attr",
+            to_array({ m.remove_attr.begin(), m.remove_attr.end() }));
+        auto classes = val::object();
+        for (auto & [ name, add ] : m.toggle_class) {
+            classes.set(name, add);
+        }
+        o.set("toggle_class", classes);
+        auto style = val::object();
+        for (auto & [ k, v ] : m.set_style) {
+            style.set(k, v);
+        }
+        o.set("set_style", style);
+        arr.set(i++, o);
+    }
+    mutation_order.clear();
+    mutations.clear();
+    return arr;
+}
+
+void put_mutations(emscripten::val muts)
+{
+    using emscripten::val;
+
+    const auto len = muts["length"].as<size_t>();
+    for (size_t i = 0; i < len; i++) {
+        auto o = muts[i];
+        const auto id = o["id"].as<string>();
+        for (auto& html : from_array(o["before"])) {
+            before(id, html);
+        }
+        for (auto& html : from_array(o["after"])) {
+            after(id, html);
+        }
+        if (!o["remove"].isUndefined()) {
+            remove(id);
+            continue;
+        }
+        if (!o["outer"].isUndefined()) {
+            set_outer_html(id, o["outer"].as<string>());
+        }
+        if (!o["inner"].isUndefined()) {
+            set_inner_html(id, o["inner"].as<string>());
+        }
+        for (auto& html : from_array(o["append"])) {
+            append(id, html);
+        }
+        for (auto& html : from_array(o["prepend"])) {
+            prepend(id, html);
+        }
+        for (auto& child : from_array(o["move_prepend"])) {
+            move_prepend(id, child);
+        }
+        for (auto& child : from_array(o["move_after"])) {
+            move_after(id, child);
+        }
+        auto attrs = o["set_attr"];
+        for (auto& k : keys(attrs)) {
+            set_attr(id, k, attrs[k].as<string>());
+        }
+        for (auto& k : from_array(o["remove_attr"])) {
+            remove_attr(id, k);
+        }
+        auto classes = o["toggle_class"];
+        for (auto& name : keys(classes)) {
+            if (classes[name].as<bool>()) {
+                add_class(id, name);
+            } else {
+                remove_class(id, name);
+            }
+        }
+        auto style = o["set_style"];
+        for (auto& k : keys(style)) {
+            set_style(id, k, style[k].as<string>());
+        }
+    }
+}
+
+struct PendingMutations {
+    std::unordered_map<string, Mutations> mutations;
+    std::vector<std::string> order;
+};
+
+std::shared_ptr<PendingMutations> save_mutations()
+{
+    return std::make_shared<PendingMutations>(
+        PendingMutations{ mutations, mutation_order });
 }
 
-void scroll_into_view(string id)
+void restore_mutations(std::shared_ptr<PendingMutations> snapshot)
 {
-    get_mutation_set(id)->scroll_into_view = true;
+    mutations = std::move(snapshot->mutations);
+    mutation_order = std::move(snapshot->order);
 }
 
 void Mutations::free_inner()
@@ -135,35 +345,139 @@ void Mutations::free_outer()
     free_inner();
     remove_attr.clear();
     set_attr.clear();
+    toggle_class.clear();
+    set_style.clear();
+    set_prop.clear();
     set_outer_html = std::nullopt;
 }
 
-extern "C" void flush()
+// Execute and clear all buffered mutations
+static void exec_mutations()
 {
-    if (before_flush) {
-        (*before_flush)();
-    }
-
     if (mutations.size()) {
         for (auto& id : mutation_order) {
             mutations.at(id).exec(id);
         }
+        if (debug::render_overlay()) {
+            debug::show_patched(mutation_order);
+        }
+
+        // Discard records of our own mutations from the foreign mutation
+        // watchdogs
+        EM_ASM({
+            if (window.__bh_watchdogs) {
+                for (var id in window.__bh_watchdogs) {
+                    window.__bh_watchdogs[id].takeRecords();
+                }
+            }
+        });
         mutation_order.clear();
         mutations.clear();
     }
+}
+
+extern "C" void flush()
+{
+    perf::Measure m("flush", "");
+    perf::FlushSample s;
+    if (before_flush) {
+        (*before_flush)();
+    }
+    runtime().flush();
+    patch_dirty();
+    step_tweens();
+    run_frame_hooks();
+
+    patch_user_input();
+    exec_mutations();
+
+    if (deferred.size()) {
+        // Deferred functions may defer more functions for the next flush
+        auto fns = std::move(deferred);
+        deferred.clear();
+        for (auto& fn : fns) {
+            fn();
+        }
+
+        // Apply any mutations produced by deferred functions in the same frame
+        patch_user_input();
+        exec_mutations();
+    }
 
     if (after_flush) {
         (*after_flush)();
     }
+    perf::end_frame();
 }
 
+#ifdef BRUNHILD_HEADLESS
+// Apply buffered mutations to the in-memory document
+void Mutations::exec(const string& id)
+{
+    using headless::apply;
+
+    if (!headless::get(id)) {
+        return;
+    }
+
+    for (auto& html : before) {
+        apply({ "before", id, "", html });
+    }
+    for (auto& html : after) {
+        apply({ "after", id, "", html });
+    }
+    if (remove_el) {
+        apply({ "remove", id, "", "" });
+        return;
+    }
+    if (set_outer_html) {
+        apply({ "set_outer_html", id, "", *set_outer_html });
+    }
+    if (set_inner_html) {
+        apply({ "set_inner_html", id, "", *set_inner_html });
+    }
+    for (auto& html : append) {
+        apply({ "append", id, "", html });
+    }
+    for (auto& html : prepend) {
+        apply({ "prepend", id, "", html });
+    }
+    for (auto& child : move_prepend) {
+        apply({ "move_prepend", id, "", child });
+    }
+    for (auto& child : move_after) {
+        apply({ "move_after", id, "", child });
+    }
+    for (auto & [ key, val ] : set_attr) {
+        apply({ "set_attr", id, key, val });
+    }
+    for (auto& key : remove_attr) {
+        apply({ "remove_attr", id, key, "" });
+    }
+    for (auto & [ name, add ] : toggle_class) {
+        apply({ add ? "add_class" : "remove_class", id, "", name });
+    }
+    for (auto & [ property, val ] : set_style) {
+        apply({ "set_style", id, property, val });
+    }
+    for (auto & [ key, _ ] : set_prop) {
+        apply({ "set_prop", id, key, "" });
+    }
+    for (auto & [ type, _ ] : dispatch) {
+        apply({ "dispatch_event", id, type, "" });
+    }
+    if (scroll_into_view) {
+        apply({ "scroll_into_view", id, "", "" });
+    }
+}
+#else
 void Mutations::exec(const string& id)
 {
     // Assign element to global variable, so we don't have to look it up each
     // time
     const bool exists = (bool)EM_ASM_INT(
         {
-            window.__el = document.getElementById(UTF8ToString($0));
+            window.__el = window.__bh_get(UTF8ToString($0));
             return !!window.__el;
         },
         id.c_str());
@@ -172,100 +486,287 @@ void Mutations::exec(const string& id)
         return;
     }
 
+    if (debug::patch_logging()) {
+        log(id);
+    }
+
     // TODO: Do these loops in one JS call, if possible
 
+    // All snippets catch and report their exceptions, so a failing DOM
+    // operation does not abort the rest of the flush
+
     // Before and after inserts need to happen, even if the element is going to
     // be removed
     for (auto& html : before) {
         EM_ASM_INT(
             {
-                var el = window.__el;
-                var cont = document.createElement('div');
-                cont.innerHTML = UTF8ToString($0);
-                el.parentNode.insertBefore(cont.firstChild, el);
+                try {
+                    var el = window.__el;
+                    var cont = document.createElement('div');
+                    cont.innerHTML = UTF8ToString($0);
+                    el.parentNode.insertBefore(cont.firstChild, el);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
             },
             html.c_str());
     }
     for (auto& html : after) {
         EM_ASM_INT(
             {
-                var el = window.__el;
-                var cont = document.createElement('div');
-                cont.innerHTML = UTF8ToString($0);
-                el.parentNode.insertBefore(cont.firstChild, el.nextSibling);
+                try {
+                    var el = window.__el;
+                    var cont = document.createElement('div');
+                    cont.innerHTML = UTF8ToString($0);
+                    el.parentNode.insertBefore(cont.firstChild, el.nextSibling);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
             },
             html.c_str());
     }
 
     if (remove_el) {
         EM_ASM({
-            var el = window.__el;
-            el.parentNode.removeChild(el);
+            try {
+                var el = window.__el;
+                el.parentNode.removeChild(el);
+            } catch (e) {
+                window.__bh_report(e);
+            }
         });
         // If the element is to be removed, nothing else needs to be done
         return;
     }
 
     if (set_outer_html) {
-        EM_ASM_INT({ window.__el.outerHTML = UTF8ToString($0); },
-            set_outer_html->c_str());
+        // Element reference is lost after replacement and needs to be looked
+        // up again for any further mutations
+        EM_ASM_INT(
+            {
+                var focus = window.__bh_save_focus(window.__el);
+                var scroll = window.__bh_save_scroll(window.__el);
+                try {
+                    window.__el.outerHTML = UTF8ToString($0);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
+                window.__el = window.__bh_get(UTF8ToString($1));
+                window.__bh_restore_focus(window.__el, focus);
+                window.__bh_restore_scroll(scroll);
+            },
+            set_outer_html->c_str(), id.c_str());
     }
     if (set_inner_html) {
-        EM_ASM_INT({ window.__el.innerHTML = UTF8ToString($0); },
+        EM_ASM_INT(
+            {
+                var focus = window.__bh_save_focus(window.__el);
+                var scroll = window.__bh_save_scroll(window.__el);
+                try {
+                    window.__el.innerHTML = UTF8ToString($0);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
+                window.__bh_restore_focus(window.__el, focus);
+                window.__bh_restore_scroll(scroll);
+            },
             set_inner_html->c_str());
     }
 
     for (auto& html : append) {
         EM_ASM_INT(
             {
-                var el = window.__el;
-                var cont = document.createElement('div');
-                cont.innerHTML = UTF8ToString($0);
-                el.appendChild(cont.firstChild);
+                try {
+                    var el = window.__el;
+                    var cont = document.createElement('div');
+                    cont.innerHTML = UTF8ToString($0);
+                    el.appendChild(cont.firstChild);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
             },
             html.c_str());
     }
     for (auto& html : prepend) {
         EM_ASM_INT(
             {
-                var el = window.__el;
-                var cont = document.createElement('div');
-                cont.innerHTML = UTF8ToString($0);
-                el.insertBefore(cont.firstChild, el.firstChild);
+                try {
+                    var el = window.__el;
+                    var cont = document.createElement('div');
+                    cont.innerHTML = UTF8ToString($0);
+                    el.insertBefore(cont.firstChild, el.firstChild);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
             },
             html.c_str());
     }
     for (auto& child_id : move_prepend) {
         EM_ASM_INT(
             {
-                var el = window.__el;
-                el.insertBefore(
-                    document.getElementById(UTF8ToString($0)), el.firstChild);
+                try {
+                    var el = window.__el;
+                    el.insertBefore(
+                        window.__bh_get(UTF8ToString($0)), el.firstChild);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
             },
             child_id.c_str());
     }
     for (auto& child_id : move_after) {
         EM_ASM_INT(
             {
-                var el = window.__el;
-                el.parentNode.insertBefore(
-                    document.getElementById(UTF8ToString($0)), el.nextSibling);
+                try {
+                    var el = window.__el;
+                    el.parentNode.insertBefore(
+                        window.__bh_get(UTF8ToString($0)), el.nextSibling);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
             },
             child_id.c_str());
     }
 
     for (auto& kv : set_attr) {
         EM_ASM_INT(
-            { window.__el.setAttribute(UTF8ToString($0), UTF8ToString($1)); },
+            {
+                try {
+                    window.__el.setAttribute(
+                        UTF8ToString($0), UTF8ToString($1));
+                } catch (e) {
+                    window.__bh_report(e);
+                }
+            },
             kv.first.c_str(), kv.second.c_str());
     }
     for (auto& key : remove_attr) {
         EM_ASM_INT(
-            { window.__el.removeAttribute(UTF8ToString($0)); }, key.c_str());
+            {
+                try {
+                    window.__el.removeAttribute(UTF8ToString($0));
+                } catch (e) {
+                    window.__bh_report(e);
+                }
+            },
+            key.c_str());
+    }
+
+    for (auto & [ name, add ] : toggle_class) {
+        EM_ASM_INT(
+            {
+                try {
+                    window.__el.classList.toggle(UTF8ToString($0), !!$1);
+                } catch (e) {
+                    window.__bh_report(e);
+                }
+            },
+            name.c_str(), add);
+    }
+
+    for (auto & [ property, v ] : set_style) {
+        EM_ASM_INT(
+            {
+                try {
+                    window.__el.style.setProperty(
+                        UTF8ToString($0), UTF8ToString($1));
+                } catch (e) {
+                    window.__bh_report(e);
+                }
+            },
+            property.c_str(), v.c_str());
+    }
+
+    if (set_prop.size() || dispatch.size()) {
+        using emscripten::val;
+
+        auto window = val::global("window");
+        auto el = window["__el"];
+        for (auto & [ key, v ] : set_prop) {
+            window.call<void>("__bh_set_prop", el, key, v);
+        }
+        for (auto & [ type, detail ] : dispatch) {
+            window.call<void>("__bh_dispatch", el, type, detail);
+        }
     }
 
     if (scroll_into_view) {
-        EM_ASM({ window.__el.scrollIntoView(); });
+        EM_ASM_INT(
+            {
+                try {
+                    window.__el.scrollIntoView({
+                        behavior : [ 'auto', 'smooth', 'instant' ][$0],
+                        block : [ 'start', 'center', 'end', 'nearest' ][$1]
+                    });
+                } catch (e) {
+                    window.__bh_report(e);
+                }
+            },
+            static_cast<int>(scroll_opts.behavior),
+            static_cast<int>(scroll_opts.block));
+    }
+}
+#endif
+
+void Mutations::log(const string& id)
+{
+    using emscripten::val;
+
+    auto el = val::global("window")["__el"];
+    auto attr = [&](const string& key) {
+        auto v = el.call<val>("getAttribute", key);
+        return v.isNull() ? string() : v.as<string>();
+    };
+
+    for (auto& html : before) {
+        debug::log_patch("before", id, "", html);
+    }
+    for (auto& html : after) {
+        debug::log_patch("after", id, "", html);
+    }
+    if (remove_el) {
+        debug::log_patch("remove", id, el["outerHTML"].as<string>(), "");
+        return;
+    }
+    if (set_outer_html) {
+        debug::log_patch(
+            "outerHTML", id, el["outerHTML"].as<string>(), *set_outer_html);
+    }
+    if (set_inner_html) {
+        debug::log_patch(
+            "innerHTML", id, el["innerHTML"].as<string>(), *set_inner_html);
+    }
+    for (auto& html : append) {
+        debug::log_patch("append", id, "", html);
+    }
+    for (auto& html : prepend) {
+        debug::log_patch("prepend", id, "", html);
+    }
+    for (auto& child : move_prepend) {
+        debug::log_patch("move_prepend", id, "", '#' + child);
+    }
+    for (auto& child : move_after) {
+        debug::log_patch("move_after", id, "", '#' + child);
+    }
+    for (auto & [ key, v ] : set_attr) {
+        debug::log_patch("set_attr " + key, id, attr(key), v);
+    }
+    for (auto& key : remove_attr) {
+        debug::log_patch("remove_attr " + key, id, attr(key), "");
+    }
+    for (auto & [ name, add ] : toggle_class) {
+        debug::log_patch(add ? "add_class" : "remove_class", id, attr("class"),
+            name);
+    }
+    for (auto & [ property, v ] : set_style) {
+        debug::log_patch("set_style " + property, id,
+            el["style"].call<string>("getPropertyValue", property), v);
+    }
+    for (auto & [ key, _ ] : set_prop) {
+        debug::log_patch("set_prop " + key, id, "", "");
+    }
+    for (auto & [ type, _ ] : dispatch) {
+        debug::log_patch("dispatch " + type, id, "", "");
     }
 }
 }
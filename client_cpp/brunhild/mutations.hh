@@ -1,6 +1,8 @@
 #pragma once
 
+#include <emscripten/val.h>
 #include <functional>
+#include <memory>
 #include <string>
 
 namespace brunhild {
@@ -37,8 +39,61 @@ void set_attr(std::string id, std::string key, std::string val);
 // Remove an element attribute
 void remove_attr(std::string id, std::string key);
 
+// Add a class to an element's classList
+void add_class(std::string id, std::string name);
+
+// Remove a class from an element's classList
+void remove_class(std::string id, std::string name);
+
+// Set an inline style property of an element, like "opacity" or "--my-var",
+// to a value
+void set_style(std::string id, std::string property, std::string val);
+
+// Set an element property to a value. Unlike attributes, properties can hold
+// any JS value and are not reflected in the rendered HTML.
+void set_prop(std::string id, std::string key, emscripten::val val);
+
+// Dispatch a bubbling CustomEvent on an element with an optional detail
+// payload
+void dispatch_event(std::string id, std::string type,
+    emscripten::val detail = emscripten::val::undefined());
+
+// Scrolling animation of scroll_into_view()
+enum class ScrollBehavior { automatic, smooth, instant };
+
+// Vertical alignment of the element after scroll_into_view()
+enum class ScrollBlock { start, center, end, nearest };
+
+// Options of scroll_into_view()
+struct ScrollOptions {
+    ScrollBehavior behavior = ScrollBehavior::automatic;
+    ScrollBlock block = ScrollBlock::start;
+};
+
 // Scroll and element into the viewport
-void scroll_into_view(std::string id);
+void scroll_into_view(std::string id, ScrollOptions opts = {});
+
+// Remove and return all pending mutations as a structured-cloneable JS array
+// instead of executing them. Properties and dispatched events can not be
+// cloned and are dropped.
+emscripten::val take_mutations();
+
+// Buffer mutations returned by take_mutations() to be executed on the next
+// flush
+void put_mutations(emscripten::val muts);
+
+// Snapshot of all pending mutations
+struct PendingMutations;
+
+// Returns a snapshot of all pending mutations
+std::shared_ptr<PendingMutations> save_mutations();
+
+// Replace all pending mutations with a snapshot returned by save_mutations(),
+// discarding any buffered since
+void restore_mutations(std::shared_ptr<PendingMutations>);
+
+// Run a function once after the next flush of DOM mutations
+void defer(std::function<void()> fn);
 
 // Flush all pending DOM mutations
 extern "C" void flush();
@@ -3,16 +3,19 @@
 #include "util.hh"
 
 static unsigned long long id_counter = 0;
+static std::string id_prefix = "bh-";
 
 namespace brunhild {
 
 std::string new_id()
 {
     std::ostringstream s;
-    s << "bh-" << id_counter++;
+    s << id_prefix << id_counter++;
     return s.str();
 }
 
+void set_id_prefix(std::string prefix) { id_prefix = prefix; }
+
 std::string HTMLWriter::html()
 {
     Rope s;
@@ -20,17 +23,209 @@ std::string HTMLWriter::html()
     return s.str();
 }
 
+void HTMLWriter::stream_html(Rope::Sink sink, size_t chunk_size)
+{
+    Rope s(sink, chunk_size);
+    write_html(s);
+    s.flush();
+}
+
+Attrs Attrs::of(std::initializer_list<Attr> attrs)
+{
+    Attrs a;
+    for (auto& attr : attrs) {
+        a.set(attr);
+    }
+    return a;
+}
+
+Attrs& Attrs::set(const Attr& attr)
+{
+    if (attr.value) {
+        (*this)[attr.key] = *attr.value;
+    } else {
+        erase(attr.key);
+    }
+    return *this;
+}
+
 void Attrs::write_html(Rope& s)
 {
     for (auto & [ key, val ] : *this) {
         s << ' ' << key;
         if (val != "") {
-            s << "=\"" << val << '"';
+            s << "=\"";
+            write_escaped(s, val) << '"';
+        }
+    }
+}
+
+std::string data_attr(const std::string& key)
+{
+    if (key.compare(0, 5, "data-") == 0) {
+        return key;
+    }
+    std::string s = "data-";
+    for (char ch : key) {
+        if (ch >= 'A' && ch <= 'Z') {
+            s += '-';
+            s += ch - 'A' + 'a';
+        } else {
+            s += ch;
         }
     }
+    return s;
 }
 
-void Attrs::patch(Attrs&& attrs)
+std::string dataset_key(const std::string& key)
+{
+    std::string s;
+    const size_t start = key.compare(0, 5, "data-") == 0 ? 5 : 0;
+    bool upper = false;
+    for (size_t i = start; i < key.size(); i++) {
+        const char ch = key[i];
+        if (ch == '-') {
+            upper = true;
+        } else if (upper && ch >= 'a' && ch <= 'z') {
+            s += ch - 'a' + 'A';
+            upper = false;
+        } else {
+            s += ch;
+            upper = false;
+        }
+    }
+    return s;
+}
+
+Attrs& Attrs::set_data(const std::string& key, const std::string& val)
+{
+    (*this)[data_attr(key)] = val;
+    return *this;
+}
+
+std::optional<std::string> Attrs::data(const std::string& key) const
+{
+    auto it = find(data_attr(key));
+    if (it == end()) {
+        return std::nullopt;
+    }
+    return it->second;
+}
+
+Attrs& Attrs::role(Role r)
+{
+    (*this)["role"] = role_name(r);
+    return *this;
+}
+
+Attrs& Attrs::aria(const std::string& key, const std::string& val)
+{
+    if (key.compare(0, 5, "aria-") == 0) {
+        (*this)[key] = val;
+    } else {
+        (*this)["aria-" + key] = val;
+    }
+    return *this;
+}
+
+static const char* bool_str(bool b) { return b ? "true" : "false"; }
+
+static const char* tristate_str(Tristate t)
+{
+    switch (t) {
+    case Tristate::true_:
+        return "true";
+    case Tristate::mixed:
+        return "mixed";
+    default:
+        return "false";
+    }
+}
+
+// Returns a number formatted without trailing zeros
+static std::string number_str(double n)
+{
+    std::ostringstream s;
+    s << n;
+    return s.str();
+}
+
+Attrs& Attrs::aria_label(const std::string& s)
+{
+    return aria("label", s);
+}
+
+Attrs& Attrs::aria_labelledby(const std::string& id)
+{
+    return aria("labelledby", id);
+}
+
+Attrs& Attrs::aria_describedby(const std::string& id)
+{
+    return aria("describedby", id);
+}
+
+Attrs& Attrs::aria_controls(const std::string& id)
+{
+    return aria("controls", id);
+}
+
+Attrs& Attrs::aria_owns(const std::string& id) { return aria("owns", id); }
+
+Attrs& Attrs::aria_activedescendant(const std::string& id)
+{
+    return aria("activedescendant", id);
+}
+
+Attrs& Attrs::aria_expanded(bool b) { return aria("expanded", bool_str(b)); }
+Attrs& Attrs::aria_hidden(bool b) { return aria("hidden", bool_str(b)); }
+Attrs& Attrs::aria_disabled(bool b) { return aria("disabled", bool_str(b)); }
+Attrs& Attrs::aria_selected(bool b) { return aria("selected", bool_str(b)); }
+Attrs& Attrs::aria_busy(bool b) { return aria("busy", bool_str(b)); }
+Attrs& Attrs::aria_modal(bool b) { return aria("modal", bool_str(b)); }
+Attrs& Attrs::aria_haspopup(bool b) { return aria("haspopup", bool_str(b)); }
+
+Attrs& Attrs::aria_checked(Tristate t)
+{
+    return aria("checked", tristate_str(t));
+}
+
+Attrs& Attrs::aria_checked(bool b) { return aria("checked", bool_str(b)); }
+
+Attrs& Attrs::aria_pressed(Tristate t)
+{
+    return aria("pressed", tristate_str(t));
+}
+
+Attrs& Attrs::aria_pressed(bool b) { return aria("pressed", bool_str(b)); }
+Attrs& Attrs::aria_live(Live l) { return aria("live", live_name(l)); }
+
+Attrs& Attrs::aria_level(int l)
+{
+    return aria("level", std::to_string(l));
+}
+
+Attrs& Attrs::aria_valuenow(double n)
+{
+    return aria("valuenow", number_str(n));
+}
+
+Attrs& Attrs::aria_valuemin(double n)
+{
+    return aria("valuemin", number_str(n));
+}
+
+Attrs& Attrs::aria_valuemax(double n)
+{
+    return aria("valuemax", number_str(n));
+}
+
+Attrs& Attrs::aria_valuetext(const std::string& s)
+{
+    return aria("valuetext", s);
+}
+
+bool Attrs::patch(Attrs&& attrs)
 {
     const auto id = (*this)["id"];
     bool patched = false;
@@ -55,6 +250,7 @@ void Attrs::patch(Attrs&& attrs)
         *this = attrs;
         (*this)["id"] = id;
     }
+    return patched;
 }
 
 void Node::write_html(Rope& s)
@@ -73,6 +269,7 @@ void Node::write_html(Rope& s)
     } else {
         for (auto& ch : children) {
             ch.write_html(s);
+            s.checkpoint();
         }
     }
 
@@ -93,9 +290,155 @@ void Node::clear()
 {
     tag.clear();
     attrs.clear();
+    props.clear();
     children.clear();
     inner_html = std::nullopt;
 }
 
 void Node::hide() { attrs["hide"] = ""; }
+
+void Node::keep_scroll() { attrs["data-bh-keep-scroll"] = ""; }
+
+Node* Node::find(const std::string& id)
+{
+    if (attrs.count("id") && attrs.at("id") == id) {
+        return this;
+    }
+    for (auto& ch : children) {
+        if (auto n = ch.find(id)) {
+            return n;
+        }
+    }
+    return nullptr;
+}
+
+void Node::walk(
+    const std::function<void(const Node&, size_t)>& fn, size_t depth) const
+{
+    fn(*this, depth);
+    for (auto& ch : children) {
+        ch.walk(fn, depth + 1);
+    }
+}
+
+TreeStats Node::stats() const
+{
+    TreeStats st;
+    walk([&st](const Node& n, size_t depth) {
+        st.nodes++;
+        if (depth > st.max_depth) {
+            st.max_depth = depth;
+        }
+        st.tags[n.tag]++;
+        st.bytes += n.tag.size();
+        for (auto & [ k, v ] : n.attrs) {
+            st.bytes += k.size() + v.size();
+        }
+        if (n.inner_html) {
+            st.bytes += n.inner_html->size();
+        }
+    });
+    return st;
+}
+
+TreeStats& TreeStats::operator+=(const TreeStats& st)
+{
+    nodes += st.nodes;
+    if (st.max_depth > max_depth) {
+        max_depth = st.max_depth;
+    }
+    for (auto & [ tag, n ] : st.tags) {
+        tags[tag] += n;
+    }
+    bytes += st.bytes;
+    patched += st.patched;
+    return *this;
+}
+
+// Returns the position of a class in a class list or npos
+static size_t find_class(const std::string& list, const std::string& name)
+{
+    size_t i = 0;
+    while ((i = list.find(name, i)) != std::string::npos) {
+        const auto end = i + name.size();
+        if ((i == 0 || list[i - 1] == ' ')
+            && (end == list.size() || list[end] == ' ')) {
+            return i;
+        }
+        i = end;
+    }
+    return std::string::npos;
+}
+
+bool Node::has_class(const std::string& name) const
+{
+    return attrs.count("class")
+        && find_class(attrs.at("class"), name) != std::string::npos;
+}
+
+void Node::add_class(const std::string& name)
+{
+    if (has_class(name)) {
+        return;
+    }
+    auto& cls = attrs["class"];
+    if (cls.size()) {
+        cls += ' ';
+    }
+    cls += name;
+}
+
+void Node::remove_class(const std::string& name)
+{
+    if (!attrs.count("class")) {
+        return;
+    }
+    auto& cls = attrs.at("class");
+    const auto i = find_class(cls, name);
+    if (i == std::string::npos) {
+        return;
+    }
+
+    // Also remove one adjacent separator
+    auto start = i, end = i + name.size();
+    if (end < cls.size()) {
+        end++;
+    } else if (start > 0) {
+        start--;
+    }
+    cls.erase(start, end - start);
+    if (cls.empty()) {
+        attrs.erase("class");
+    }
+}
+
+void Node::apply_props()
+{
+    if (props.size()) {
+        const auto& id = attrs.at("id");
+        for (auto & [ key, val ] : props) {
+            set_prop(id, key, val);
+        }
+    }
+    for (auto& ch : children) {
+        ch.apply_props();
+    }
+}
+
+void Node::patch_props(Props&& new_props)
+{
+    const auto& id = attrs.at("id");
+
+    for (auto & [ key, val ] : new_props) {
+        if (!props.count(key) || !props.at(key).strictlyEquals(val)) {
+            set_prop(id, key, val);
+        }
+    }
+    for (auto & [ key, _ ] : props) {
+        if (!new_props.count(key)) {
+            set_prop(id, key, emscripten::val::undefined());
+        }
+    }
+    props = std::move(new_props);
+}
 }
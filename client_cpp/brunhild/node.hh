@@ -1,10 +1,17 @@
 #pragma once
 
+#include "aria.hh"
+#include "attr.hh"
 #include "util.hh"
+#include <emscripten/val.h>
+#include <functional>
+#include <initializer_list>
 #include <optional>
 #include <sstream>
 #include <string>
+#include <type_traits>
 #include <unordered_map>
+#include <utility>
 #include <vector>
 
 namespace brunhild {
@@ -12,6 +19,10 @@ namespace brunhild {
 // Generate a new unique element ID
 std::string new_id();
 
+// Set the prefix of IDs generated by new_id(). Defaults to "bh-". Separate
+// module instances sharing a document must use distinct prefixes.
+void set_id_prefix(std::string prefix);
+
 // Helper for serializing to HTML
 class HTMLWriter {
 public:
@@ -20,20 +31,112 @@ public:
 
     // Write as HTML to stream
     virtual void write_html(Rope&) = 0;
+
+    // Render HTML incrementally to sink, like a chunked HTTP response, instead
+    // of building the entire string in memory first. Chunks of at least
+    // chunk_size bytes are written between child nodes and views.
+    void stream_html(Rope::Sink sink, size_t chunk_size = 16 << 10);
 };
 
-// Element attributes
+// Element attributes. Values are stored unescaped.
 class Attrs : public std::unordered_map<std::string, std::string>,
               public HTMLWriter {
     typedef std::unordered_map<std::string, std::string> Base;
     using Base::Base;
 
 public:
-    // Write attrs as HTML to stream
+    // Build Attrs from typed attributes
+    static Attrs of(std::initializer_list<Attr>);
+
+    // Write attrs as HTML to stream with HTML-escaped values
     void write_html(Rope&);
 
-    // Diff attributes with new value and apply patches to the DOM
-    void patch(Attrs&& attrs);
+    // Set a typed attribute or remove it, if it is a boolean attribute set to
+    // false
+    Attrs& set(const Attr&);
+
+    // Diff attributes with new value and apply patches to the DOM. Returns,
+    // if any attributes were patched.
+    bool patch(Attrs&& attrs);
+
+    // Set a data-* attribute. key can be in either camelCase, as in
+    // HTMLElement.dataset, or kebab-case.
+    Attrs& set_data(const std::string& key, const std::string& val);
+
+    // Numeric overload of set_data()
+    template <class T>
+    std::enable_if_t<std::is_arithmetic_v<T>, Attrs&> set_data(
+        const std::string& key, T val)
+    {
+        return set_data(key, std::to_string(val));
+    }
+
+    // Returns the value of a data-* attribute, if set. key can be in either
+    // camelCase or kebab-case.
+    std::optional<std::string> data(const std::string& key) const;
+
+    // Set the "role" attribute
+    Attrs& role(Role);
+
+    // Set an aria-* attribute. key can be with or without the "aria-" prefix.
+    Attrs& aria(const std::string& key, const std::string& val);
+
+    // Typed setters of common ARIA attributes. Return the Attrs for chaining.
+    Attrs& aria_label(const std::string&);
+    Attrs& aria_labelledby(const std::string& id);
+    Attrs& aria_describedby(const std::string& id);
+    Attrs& aria_controls(const std::string& id);
+    Attrs& aria_owns(const std::string& id);
+    Attrs& aria_activedescendant(const std::string& id);
+    Attrs& aria_expanded(bool);
+    Attrs& aria_hidden(bool);
+    Attrs& aria_disabled(bool);
+    Attrs& aria_selected(bool);
+    Attrs& aria_busy(bool);
+    Attrs& aria_modal(bool);
+    Attrs& aria_haspopup(bool);
+    Attrs& aria_checked(Tristate);
+    Attrs& aria_checked(bool);
+    Attrs& aria_pressed(Tristate);
+    Attrs& aria_pressed(bool);
+    Attrs& aria_live(Live);
+    Attrs& aria_level(int);
+    Attrs& aria_valuenow(double);
+    Attrs& aria_valuemin(double);
+    Attrs& aria_valuemax(double);
+    Attrs& aria_valuetext(const std::string&);
+};
+
+// Convert a camelCase or kebab-case key into a data-* attribute name
+std::string data_attr(const std::string& key);
+
+// Convert a data-* attribute name or kebab-case key into the camelCase key
+// used by HTMLElement.dataset
+std::string dataset_key(const std::string& key);
+
+// Element properties. Set on the element after insertion into the DOM.
+typedef std::unordered_map<std::string, emscripten::val> Props;
+
+// Statistics of a node tree
+struct TreeStats {
+    // Number of nodes
+    size_t nodes = 0;
+
+    // Depth of the deepest node. The root node has a depth of 1.
+    size_t max_depth = 0;
+
+    // Number of nodes by tag
+    std::unordered_map<std::string, size_t> tags;
+
+    // Total length of retained tag, attribute and inner HTML strings
+    size_t bytes = 0;
+
+    // Number of nodes patched by the last diff. Only set by
+    // VirtualView::stats().
+    size_t patched = 0;
+
+    // Merge statistics of another tree
+    TreeStats& operator+=(const TreeStats&);
 };
 
 // Represents an HTML element. Can be used to construct node trees more easily.
@@ -42,36 +145,43 @@ public:
     // Tag of the Element
     std::string tag;
 
-    // Attributes and properties of the Element
+    // Attributes of the Element
     Attrs attrs;
 
+    // Properties of the Element. Useful for passing non-string data to custom
+    // elements.
+    Props props;
+
     // Children of the element
     std::vector<Node> children;
 
     // Inner HTML of the Element. If set, children are ignored
     std::optional<std::string> inner_html;
 
-    // Creates a Node with optional attributes and children
+    // Creates a Node with optional attributes and children.
+    // Arguments are taken by value and moved from, so passing temporaries
+    // does not copy them. Tags and attribute keys of static literals are
+    // short enough to not allocate.
     Node(std::string tag, Attrs attrs = {}, std::vector<Node> children = {})
-        : tag(tag)
-        , attrs(attrs)
-        , children(children)
+        : tag(std::move(tag))
+        , attrs(std::move(attrs))
+        , children(std::move(children))
     {
     }
 
     // Creates a Node with html set as the inner contents.
     // Escaped specifies, if the text should be escaped.
     Node(std::string tag, Attrs attrs, std::string html, bool escape = false)
-        : tag(tag)
-        , attrs(attrs)
-        , inner_html(escape ? brunhild::escape(html) : html)
+        : tag(std::move(tag))
+        , attrs(std::move(attrs))
+        , inner_html(escape ? brunhild::escape(html) : std::move(html))
     {
     }
 
     // Creates a Node with html set as the inner contents.
     // Escaped specifies, if the text should be escaped.
     Node(std::string tag, std::string html, bool escape = false)
-        : Node(tag, {}, html, escape)
+        : Node(std::move(tag), {}, std::move(html), escape)
     {
     }
 
@@ -91,6 +201,39 @@ public:
 
     // Shortcut for setting a node as hidden
     void hide();
+
+    // Mark the node to have its scroll position preserved, when an ancestor's
+    // subtree HTML is replaced. The node must have an ID.
+    void keep_scroll();
+
+    // Find a node in the subtree by ID. Returns NULL, if not found.
+    Node* find(const std::string& id);
+
+    // Call fn with the node and each node of its subtree in document order
+    // and their depth relative to this node, starting at depth
+    void walk(const std::function<void(const Node&, size_t depth)>& fn,
+        size_t depth = 1) const;
+
+    // Compute statistics of the node and its subtree
+    TreeStats stats() const;
+
+    // Returns, if the node has a class in its "class" attribute
+    bool has_class(const std::string& name) const;
+
+    // Add a class to the "class" attribute of the node. Does not patch the
+    // DOM.
+    void add_class(const std::string& name);
+
+    // Remove a class from the "class" attribute of the node. Does not patch
+    // the DOM.
+    void remove_class(const std::string& name);
+
+    // Set properties of the node and its subtree on the DOM elements.
+    // Must be called after the node has been inserted into the DOM.
+    void apply_props();
+
+    // Diff properties with new values and apply patches to the DOM
+    void patch_props(Props&& props);
 };
 
 // Subtree of a Node
@@ -0,0 +1,164 @@
+#include "notify.hh"
+#include "promise.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::notify {
+
+static long id_counter = 0;
+
+// Click handlers of open notifications by ID
+static std::unordered_map<long, std::function<void()>> handlers;
+
+// Returns the JS notification helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_notify) {
+            return;
+        }
+
+        // Open notifications by ID
+        var open = {};
+
+        window.__bh_notify = {
+            permission : function()
+            {
+                return window.Notification ? Notification.permission
+                                           : "unsupported";
+            },
+            request : function()
+            {
+                if (!window.Notification) {
+                    return Promise.resolve("unsupported");
+                }
+                if (Notification.permission != "default") {
+                    return Promise.resolve(Notification.permission);
+                }
+                return new Promise(function(resolve) {
+                    // Older browsers only support the callback form
+                    var p = Notification.requestPermission(resolve);
+                    if (p && p.then) {
+                        p.then(resolve);
+                    }
+                });
+            },
+            show : function(id, opts)
+            {
+                if (!window.Notification
+                    || Notification.permission != "granted") {
+                    return false;
+                }
+                var title = opts.title;
+                delete opts.title;
+                var n;
+                try {
+                    n = new Notification(title, opts);
+                } catch (e) {
+                    // Constructor not allowed, like on Chrome for Android
+                    return false;
+                }
+                n.onclick = function(e)
+                {
+                    e.preventDefault();
+                    window.focus();
+                    Module._bh_notification_clicked(id);
+                    n.close();
+                };
+                n.onclose = function()
+                {
+                    delete open[id];
+                    Module._bh_notification_closed(id);
+                };
+                open[id] = n;
+                return true;
+            },
+            close : function(id)
+            {
+                if (open[id]) {
+                    open[id].close();
+                }
+            }
+        };
+    });
+    return val::global("__bh_notify");
+}
+
+static Permission parse_permission(const string& s)
+{
+    if (s == "granted") {
+        return Permission::granted;
+    }
+    if (s == "denied") {
+        return Permission::denied;
+    }
+    if (s == "default") {
+        return Permission::prompt;
+    }
+    return Permission::unsupported;
+}
+
+Permission permission()
+{
+    return parse_permission(helpers().call<string>("permission"));
+}
+
+void request_permission(std::function<void(Permission)> cb)
+{
+    await(helpers().call<val>("request"), [cb](bool ok, val res) {
+        if (cb) {
+            cb(ok && res.isString() ? parse_permission(res.as<string>())
+                                    : permission());
+        }
+    });
+}
+
+long show(const Options& opts, std::function<void()> on_click)
+{
+    auto o = val::object();
+    o.set("title", opts.title);
+    o.set("body", opts.body);
+    if (opts.icon != "") {
+        o.set("icon", opts.icon);
+    }
+    if (opts.tag != "") {
+        o.set("tag", opts.tag);
+    }
+    o.set("silent", opts.silent);
+    o.set("requireInteraction", opts.require_interaction);
+
+    const long id = id_counter++;
+    if (!helpers().call<bool>("show", id, o)) {
+        return -1;
+    }
+    if (on_click) {
+        handlers[id] = on_click;
+    }
+    return id;
+}
+
+void close(long id) { helpers().call<void>("close", id); }
+
+static void notification_clicked(long id)
+{
+    auto it = handlers.find(id);
+    if (it != handlers.end()) {
+        auto fn = it->second;
+        fn();
+    }
+}
+
+static void notification_closed(long id) { handlers.erase(id); }
+
+EMSCRIPTEN_BINDINGS(module_notify)
+{
+    emscripten::function(
+        "_bh_notification_clicked", &notification_clicked);
+    emscripten::function("_bh_notification_closed", &notification_closed);
+}
+}
@@ -0,0 +1,54 @@
+#pragma once
+
+#include <functional>
+#include <string>
+
+// Desktop notifications through the Notifications API. All functions degrade
+// to no-ops, where the API is not available or permission is denied.
+namespace brunhild::notify {
+
+// Permission to show notifications
+enum class Permission {
+    // Not yet requested or dismissed by the user
+    prompt,
+    granted,
+    denied,
+
+    // Notifications API not available
+    unsupported,
+};
+
+// Returns the current notification permission
+Permission permission();
+
+// Request permission to show notifications, if not yet granted or denied, and
+// pass the result to cb. Most browsers require a user gesture, so call this
+// from event handlers.
+void request_permission(std::function<void(Permission)> cb = nullptr);
+
+// Notification to show
+struct Options {
+    std::string title, body;
+
+    // URL of the notification icon. Optional.
+    std::string icon;
+
+    // Notifications with the same non-empty tag replace each other
+    std::string tag;
+
+    // Do not play a sound or vibrate
+    bool silent = false;
+
+    // Keep the notification open, until the user interacts with it
+    bool require_interaction = false;
+};
+
+// Show a notification. on_click is optional and called, when the user clicks
+// the notification. The page window is focused before on_click is called and
+// the notification closed afterwards.
+// Returns notification ID or -1, if the notification could not be shown.
+long show(const Options& opts, std::function<void()> on_click = nullptr);
+
+// Close a shown notification by ID
+void close(long id);
+}
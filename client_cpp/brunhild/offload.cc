@@ -0,0 +1,237 @@
+#include "offload.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <memory>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+// Convert a Node and its subtree to a structured-cloneable JS object.
+// Properties are dropped.
+static val to_val(const Node& n)
+{
+    auto o = val::object();
+    o.set("tag", n.tag);
+    auto attrs = val::object();
+    for (auto & [ k, v ] : n.attrs) {
+        attrs.set(k, v);
+    }
+    o.set("attrs", attrs);
+    if (n.inner_html) {
+        o.set("html", *n.inner_html);
+    } else {
+        auto ch = val::array();
+        for (size_t i = 0; i < n.children.size(); i++) {
+            ch.set(i, to_val(n.children[i]));
+        }
+        o.set("children", ch);
+    }
+    return o;
+}
+
+// Inverse of to_val()
+static Node from_val(val o)
+{
+    Node n;
+    n.tag = o["tag"].as<string>();
+    auto attrs = o["attrs"];
+    auto keys = emscripten::vecFromJSArray<string>(
+        val::global("Object").call<val>("keys", attrs));
+    for (auto& k : keys) {
+        n.attrs[k] = attrs[k].as<string>();
+    }
+    if (!o["html"].isUndefined()) {
+        n.inner_html = o["html"].as<string>();
+    } else {
+        auto ch = o["children"];
+        const auto len = ch["length"].as<size_t>();
+        n.children.reserve(len);
+        for (size_t i = 0; i < len; i++) {
+            n.children.push_back(from_val(ch[i]));
+        }
+    }
+    return n;
+}
+
+namespace offload {
+
+    // Worker-side copy of an offloaded view's saved subtree
+    class Differ : public VirtualView {
+    public:
+        Differ(Node&& n)
+            : VirtualView(n.attrs.at("id"))
+        {
+            saved = std::move(n);
+        }
+
+        Node render() { return saved; }
+
+        using VirtualView::diff;
+    };
+
+    // Saved subtrees by view ID. Only used in the worker.
+    static std::unordered_map<string, std::unique_ptr<Differ>> differs;
+
+    static bool is_running = false;
+
+    bool in_worker()
+    {
+        return (bool)EM_ASM_INT({
+            return typeof document == "undefined"
+                && typeof importScripts == "function";
+        });
+    }
+
+    void serve()
+    {
+        // Keep IDs of nodes created by the worker distinct from those created
+        // by the main thread
+        set_id_prefix("bhw-");
+
+        EM_ASM({
+            self.onmessage = function(e)
+            {
+                var res = Module._bh_offload_request(e.data);
+                if (res) {
+                    self.postMessage(res);
+                }
+            };
+        });
+    }
+
+    void start(const string& script_url)
+    {
+        if (is_running) {
+            return;
+        }
+        is_running = (bool)EM_ASM_INT(
+            {
+                if (!window.Worker) {
+                    return 0;
+                }
+                var w = new Worker(UTF8ToString($0));
+                w.onmessage = function(e)
+                {
+                    Module._bh_offload_response(e.data);
+                };
+                w.onerror = function(e) { console.error(e); };
+                window.__bh_offload = w;
+                return 1;
+            },
+            script_url.c_str());
+    }
+
+    void stop()
+    {
+        if (!is_running) {
+            return;
+        }
+        is_running = false;
+        EM_ASM({
+            window.__bh_offload.terminate();
+            delete window.__bh_offload;
+        });
+    }
+
+    bool running() { return is_running; }
+
+    // Post a request to the worker
+    static void post(const string& op, const string& id, val tree)
+    {
+        auto o = val::object();
+        o.set("op", op);
+        o.set("id", id);
+        o.set("tree", tree);
+        val::global("__bh_offload").call<void>("postMessage", o);
+    }
+
+    // Handle a request from the main thread. Returns the response to post
+    // back or null.
+    static val handle_request(val req)
+    {
+        const auto op = req["op"].as<string>();
+        const auto id = req["id"].as<string>();
+        if (op == "init") {
+            differs[id] = std::make_unique<Differ>(from_val(req["tree"]));
+        } else if (op == "drop") {
+            differs.erase(id);
+        } else if (op == "diff") {
+            auto it = differs.find(id);
+            if (it == differs.end()) {
+                return val::null();
+            }
+            it->second->diff(from_val(req["tree"]));
+            auto res = val::object();
+            res.set("id", id);
+            res.set("mutations", take_mutations());
+            return res;
+        }
+        return val::null();
+    }
+
+    // Buffer mutations produced by the worker
+    static void handle_response(val res)
+    {
+        put_mutations(res["mutations"]);
+    }
+
+    EMSCRIPTEN_BINDINGS(module_offload)
+    {
+        emscripten::function("_bh_offload_request", &handle_request);
+        emscripten::function("_bh_offload_response", &handle_response);
+    }
+}
+
+OffloadedView::~OffloadedView()
+{
+    if (is_offloaded && offload::running()) {
+        offload::post("drop", id, val::null());
+    }
+}
+
+void OffloadedView::patch()
+{
+    if (!is_offloaded) {
+        VirtualView::patch();
+        return;
+    }
+
+    perf::Measure m("diff", id);
+    debug::count_render(id, typeid(*this).name());
+    Node node;
+    {
+        perf::Sample s(perf::Phase::render, typeid(*this));
+        node = render();
+    }
+    validate(node, id);
+    node.attrs["id"] = id;
+    debug::stamp_type(node.attrs, typeid(*this));
+    offload::post("diff", id, to_val(node));
+}
+
+void OffloadedView::mount()
+{
+    VirtualView::mount();
+    if (!offload::running()) {
+        return;
+    }
+    offload::post("init", id, to_val(saved));
+    saved.clear();
+    is_offloaded = true;
+}
+
+void OffloadedView::unmount()
+{
+    if (is_offloaded) {
+        is_offloaded = false;
+        if (offload::running()) {
+            offload::post("drop", id, val::null());
+        }
+    }
+    VirtualView::unmount();
+}
+}
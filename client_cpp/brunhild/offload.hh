@@ -0,0 +1,82 @@
+#pragma once
+
+#include "view.hh"
+#include <string>
+
+// Diffing of large view subtrees in a Web Worker hosting a second instance of
+// the module. The main thread renders and serializes the new subtree, the
+// worker diffs it against its copy of the saved subtree and sends back the
+// resulting DOM mutations, which the main thread buffers for the next flush.
+//
+// The worker loads the same script as the main thread. main() must call
+// serve() and return early, if in_worker() is true:
+//
+//     int main()
+//     {
+//         if (brunhild::offload::in_worker()) {
+//             brunhild::offload::serve();
+//             return 0;
+//         }
+//         brunhild::init();
+//         brunhild::offload::start("/assets/wasm/main.js");
+//         ...
+//     }
+namespace brunhild::offload {
+
+// Returns, if the module is running inside a Web Worker
+bool in_worker();
+
+// Serve diff requests from the main thread. Must only be called in a worker.
+void serve();
+
+// Start the diff worker from the module's script URL. No-op, where Web
+// Workers are not supported, in which case OffloadedViews diff on the main
+// thread.
+void start(const std::string& script_url);
+
+// Terminate the diff worker. OffloadedViews initialized after this diff on
+// the main thread. Must not be called, while any offloaded views are mounted.
+void stop();
+
+// Returns, if the diff worker is running
+bool running();
+}
+
+namespace brunhild {
+
+// VirtualView, that diffs its subtree in the offload worker, if it is running
+// when the view is mounted. Only worth it for very large subtrees, as the
+// rendered subtree is copied to the worker on each patch() call.
+//
+// The saved subtree is only kept in the worker, so add_class(), set_data()
+// and friends, Node properties and foreign mutation watching are not
+// supported for offloaded views. Offloaded views can not be reinserted after
+// removal. Mutations arrive asynchronously and are applied on the first flush
+// after the worker responds.
+class OffloadedView : public VirtualView {
+public:
+    OffloadedView(std::string id = new_id())
+        : VirtualView(id)
+    {
+    }
+
+    ~OffloadedView();
+
+    // Render the subtree and send it to the worker for diffing. Diffs on the
+    // main thread, if the view is not offloaded.
+    void patch();
+
+    // Returns, if the view is diffed in the worker
+    bool offloaded() const { return is_offloaded; }
+
+protected:
+    // Send the saved subtree to the worker and free it on the main thread
+    void mount();
+
+    // Drop the saved subtree in the worker
+    void unmount();
+
+private:
+    bool is_offloaded = false;
+};
+}
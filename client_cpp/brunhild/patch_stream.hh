@@ -0,0 +1,103 @@
+#pragma once
+
+#include "bus.hh"
+#include <deque>
+#include <functional>
+#include <map>
+#include <string>
+#include <string_view>
+#include <utility>
+
+namespace brunhild {
+
+// Applies a stream of structured patch messages, like live thread updates
+// received over a WebSocket, to application state and views. Each message has
+// an operation key of type K, like an enum of message types, and a payload.
+//
+// Operation handlers mutate the state they target and mark the views
+// rendering it dirty with mark_dirty() instead of patching them directly, so
+// that bursts of messages touching the same view result in a single patch on
+// the next flush:
+//
+//     PatchStream<Message> stream;
+//     stream.on(Message::close_thread, [](std::string_view) {
+//         thread.locked = true;
+//         mark_dirty(thread_view);
+//     });
+//     ...
+//     stream.apply(type, data);
+template <class K> class PatchStream {
+public:
+    // Handles the payload of an operation
+    typedef std::function<void(std::string_view data)> Op;
+
+    // Register the handler of an operation. Replaces any previous handler.
+    void on(K key, Op op) { ops[key] = op; }
+
+    // Remove the handler of an operation
+    void off(K key) { ops.erase(key); }
+
+    // Set a handler for messages with no registered operation handler
+    void on_unknown(std::function<void(K key, std::string_view data)> fn)
+    {
+        unknown = fn;
+    }
+
+    // Apply a message or buffer it, if the stream is paused. Returns false,
+    // if the message has no registered operation handler.
+    bool apply(K key, std::string_view data)
+    {
+        if (paused) {
+            buffered.emplace_back(key, std::string(data));
+            return ops.count(key);
+        }
+        return run(key, data);
+    }
+
+    // Buffer all messages, until resume() is called. Useful, while the state
+    // the stream applies to is being replaced, like during a resync.
+    void pause() { paused = true; }
+
+    // Apply all buffered messages in order and stop buffering
+    void resume()
+    {
+        paused = false;
+
+        // Handlers might pause the stream again
+        while (!paused && buffered.size()) {
+            auto msg = std::move(buffered.front());
+            buffered.pop_front();
+            run(msg.first, msg.second);
+        }
+    }
+
+    // Discard all buffered messages
+    void discard() { buffered.clear(); }
+
+    // Returns, if the stream is paused
+    bool is_paused() const { return paused; }
+
+    // Returns the number of buffered messages
+    size_t pending() const { return buffered.size(); }
+
+private:
+    bool paused = false;
+    std::map<K, Op> ops;
+    std::function<void(K, std::string_view)> unknown;
+    std::deque<std::pair<K, std::string>> buffered;
+
+    bool run(K key, std::string_view data)
+    {
+        auto it = ops.find(key);
+        if (it == ops.end()) {
+            if (auto fn = unknown) {
+                fn(key, data);
+            }
+            return false;
+        }
+        auto op = it->second; // Handler might unregister itself
+        op(data);
+        return true;
+    }
+};
+}
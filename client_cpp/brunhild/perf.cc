@@ -0,0 +1,134 @@
+#include "perf.hh"
+#include "debug.hh"
+#include "log.hh"
+#include <algorithm>
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+#include <unordered_map>
+
+namespace brunhild::perf {
+
+bool is_enabled = false;
+
+// Counter for generating unique mark names
+static unsigned long mark_counter = 0;
+
+void set_enabled(bool enable) { is_enabled = enable; }
+
+void Measure::start(const char* phase, const std::string& id)
+{
+    this->phase = phase;
+    this->id = id;
+    mark = ++mark_counter;
+    EM_ASM_INT({ performance.mark('bh-' + $0); }, mark);
+}
+
+void Measure::end()
+{
+    EM_ASM_INT(
+        {
+            var mark = 'bh-' + $0;
+            var name = 'bh:' + UTF8ToString($1);
+            var id = UTF8ToString($2);
+            if (id) {
+                name += ' ' + id;
+            }
+            try {
+                performance.measure(name, mark);
+            } catch (e) {
+                // Mark cleared externally
+            }
+            performance.clearMarks(mark);
+        },
+        mark, phase, id.c_str());
+}
+
+bool is_profiling = false;
+
+// Accumulated profiling data by view type
+static std::unordered_map<const std::type_info*, ProfileEntry> profile;
+
+// Total time spent flushing
+static double flush_time = 0;
+
+void set_profiling(bool enable) { is_profiling = enable; }
+
+void reset_profile()
+{
+    profile.clear();
+    flush_time = 0;
+}
+
+void Sample::start(Phase phase, const std::type_info* type)
+{
+    started = true;
+    this->phase = phase;
+    this->type = type;
+    start_time = emscripten_get_now();
+}
+
+void Sample::end()
+{
+    const double d = emscripten_get_now() - start_time;
+    if (!type) {
+        flush_time += d;
+        return;
+    }
+
+    auto& e = profile[type];
+    e.samples++;
+    switch (phase) {
+    case Phase::render:
+        e.render += d;
+        break;
+    case Phase::state:
+        e.state += d;
+        break;
+    case Phase::diff:
+        e.diff += d;
+        break;
+    }
+}
+
+ProfileReport profile_report()
+{
+    ProfileReport r;
+    r.flush = flush_time;
+    r.views.reserve(profile.size());
+    for (auto & [ type, e ] : profile) {
+        auto& entry = r.views.emplace_back(e);
+        entry.type = debug::type_name(*type);
+    }
+    std::sort(r.views.begin(), r.views.end(),
+        [](auto& a, auto& b) { return a.total() > b.total(); });
+    return r;
+}
+
+void log_profile()
+{
+    using emscripten::val;
+
+    const auto r = profile_report();
+    auto rows = val::array();
+    for (auto& e : r.views) {
+        auto row = val::object();
+        row.set("type", e.type);
+        row.set("total", e.total());
+        row.set("render", e.render);
+        row.set("state", e.state);
+        row.set("diff", e.diff);
+        row.set("samples", e.samples);
+        rows.call<void>("push", row);
+    }
+    val::global("console").call<void>("table", rows);
+    BH_INFO("DOM flush: ", r.flush, "ms");
+}
+
+EMSCRIPTEN_BINDINGS(module_perf)
+{
+    emscripten::function("_bh_set_perf_instrumentation", &set_enabled);
+    emscripten::function("_bh_set_profiling", &set_profiling);
+    emscripten::function("_bh_log_profile", &log_profile);
+}
+}
@@ -0,0 +1,152 @@
+#pragma once
+
+#include <string>
+#include <typeinfo>
+#include <vector>
+
+// Instrumentation of brunhild rendering passes with the browser User Timing
+// API, so their cost shows up in the performance timeline of browser devtools
+namespace brunhild::perf {
+
+// Toggle instrumentation. Disabled by default. Can also be toggled from the
+// browser console with Module._bh_set_perf_instrumentation(bool).
+void set_enabled(bool);
+
+// Instrumentation is enabled. Use set_enabled() to modify.
+extern bool is_enabled;
+
+// Returns, if instrumentation is enabled
+inline bool enabled() { return is_enabled; }
+
+// Measures a rendering pass from construction till destruction as
+// "bh:<phase> <id>", if instrumentation is enabled
+class Measure {
+public:
+    // phase: name of the rendering pass
+    // id: ID of the view or element being rendered
+    Measure(const char* phase, const std::string& id)
+    {
+        if (enabled()) {
+            start(phase, id);
+        }
+    }
+
+    ~Measure()
+    {
+        if (mark) {
+            end();
+        }
+    }
+
+    Measure(const Measure&) = delete;
+    Measure& operator=(const Measure&) = delete;
+
+private:
+    // ID of the starting mark. 0, if not measuring.
+    unsigned long mark = 0;
+
+    const char* phase;
+    std::string id;
+
+    void start(const char* phase, const std::string& id);
+    void end();
+};
+
+// Profiled phases of view rendering
+enum class Phase {
+    render, // Rendering the view's node tree or HTML
+    state, // Computing the inputs of a render, like props or child lists
+    diff, // Diffing the rendered tree against the previous one
+};
+
+// Accumulated time in milliseconds a view type has spent in each phase.
+// The diff time of parent views includes patching their child views.
+struct ProfileEntry {
+    // Demangled type name of the view
+    std::string type;
+
+    double render = 0, state = 0, diff = 0;
+
+    // Number of samples recorded
+    unsigned long samples = 0;
+
+    // Returns the total time spent in all phases
+    double total() const { return render + state + diff; }
+};
+
+// Profiling report sorted by total time spent, greatest first
+struct ProfileReport {
+    std::vector<ProfileEntry> views;
+
+    // Total time spent flushing DOM mutations
+    double flush = 0;
+};
+
+// Toggle accumulation of profiling data. Disabled by default. Can also be
+// toggled from the browser console with Module._bh_set_profiling(bool).
+void set_profiling(bool);
+
+// Profiling is enabled. Use set_profiling() to modify.
+extern bool is_profiling;
+
+// Returns, if profiling is enabled
+inline bool profiling() { return is_profiling; }
+
+// Clear all accumulated profiling data
+void reset_profile();
+
+// Returns the accumulated profiling data
+ProfileReport profile_report();
+
+// Log the accumulated profiling data to the console as a table. Can also be
+// called from the browser console with Module._bh_log_profile().
+void log_profile();
+
+// Accumulates the time from construction till destruction to a view type's
+// phase, if profiling is enabled
+class Sample {
+public:
+    Sample(Phase phase, const std::type_info& type)
+    {
+        if (profiling()) {
+            start(phase, &type);
+        }
+    }
+
+    ~Sample()
+    {
+        if (started) {
+            end();
+        }
+    }
+
+    Sample(const Sample&) = delete;
+    Sample& operator=(const Sample&) = delete;
+
+protected:
+    // Used by the DOM flush sample
+    Sample() = default;
+
+    bool started = false;
+    Phase phase;
+
+    // NULL for the DOM flush
+    const std::type_info* type = nullptr;
+
+    double start_time;
+
+    void start(Phase, const std::type_info*);
+    void end();
+};
+
+// Accumulates the time spent flushing DOM mutations, if profiling is enabled
+class FlushSample : public Sample {
+public:
+    FlushSample()
+    {
+        if (profiling()) {
+            start(Phase::render, nullptr);
+        }
+    }
+};
+}
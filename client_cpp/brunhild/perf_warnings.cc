@@ -0,0 +1,204 @@
+#include "perf_warnings.hh"
+
+#ifndef NDEBUG
+
+#include "debug.hh"
+#include "log.hh"
+#include <emscripten/bind.h>
+#include <functional>
+#include <unordered_map>
+#include <unordered_set>
+
+using std::string;
+
+namespace brunhild::perf {
+
+static bool warnings_enabled = false;
+
+static WarningThresholds thresholds;
+
+// Number of flushes since page load
+static unsigned long frame = 0;
+
+// Render history of a view
+struct ViewRecord {
+    // Hash of the last rendered tree
+    size_t hash = 0;
+
+    // Frame the rendered tree last changed in
+    unsigned long changed_frame = 0;
+
+    // Number of consecutive frames the rendered tree changed in
+    unsigned churn = 0;
+
+    // Number of consecutive diffs with identically rebuilt attributes
+    unsigned identical_attrs = 0;
+
+    // Warnings already logged for the view
+    bool warned_churn = false, warned_depth = false, warned_unkeyed = false,
+         warned_attrs = false;
+};
+
+static std::unordered_map<string, ViewRecord> records;
+
+void set_warnings(bool enable, WarningThresholds t)
+{
+    warnings_enabled = enable;
+    thresholds = t;
+    if (!enable) {
+        records.clear();
+    }
+}
+
+bool warnings() { return warnings_enabled; }
+
+void end_frame() { frame++; }
+
+static void warn(
+    const string& id, const std::type_info& type, const string& msg)
+{
+    BH_WARN("performance: view ", id, " (", debug::type_name(type), "): ", msg);
+}
+
+// Hash a tree independent of attribute iteration order
+static size_t hash_tree(const Node& n)
+{
+    std::hash<string> h;
+    size_t hash = h(n.tag);
+    size_t attrs = 0;
+    for (auto & [ k, v ] : n.attrs) {
+        attrs += h(k) * 31 + h(v);
+    }
+    hash = hash * 31 + attrs;
+    if (n.inner_html) {
+        hash = hash * 31 + h(*n.inner_html);
+    }
+    for (auto& ch : n.children) {
+        hash = hash * 31 + hash_tree(ch);
+    }
+    return hash;
+}
+
+static unsigned depth(const Node& n)
+{
+    unsigned max = 0;
+    for (auto& ch : n.children) {
+        const auto d = depth(ch);
+        if (d > max) {
+            max = d;
+        }
+    }
+    return max + 1;
+}
+
+// Returns the most children without an "id" attribute of any node in the tree
+static size_t max_unkeyed(const Node& n)
+{
+    size_t unkeyed = 0, max = 0;
+    for (auto& ch : n.children) {
+        if (!ch.attrs.count("id")) {
+            unkeyed++;
+        }
+        const auto m = max_unkeyed(ch);
+        if (m > max) {
+            max = m;
+        }
+    }
+    return unkeyed > max ? unkeyed : max;
+}
+
+// Returns, if the attributes of nodes present in both trees are identical.
+// Sets non_empty, if any attributes were compared.
+static bool same_attrs(const Node& old, const Node& node, bool& non_empty)
+{
+    if (old.tag != node.tag) {
+        return false;
+    }
+    for (auto & [ k, v ] : node.attrs) {
+        if (k == "id") {
+            continue;
+        }
+        auto it = old.attrs.find(k);
+        if (it == old.attrs.end() || it->second != v) {
+            return false;
+        }
+        non_empty = true;
+    }
+    for (size_t i = 0; i < old.children.size() && i < node.children.size();
+         i++) {
+        if (!same_attrs(old.children[i], node.children[i], non_empty)) {
+            return false;
+        }
+    }
+    return true;
+}
+
+void check_render(const string& id, const std::type_info& type,
+    const Node& node, const Node* old)
+{
+    if (!warnings_enabled) {
+        return;
+    }
+    auto& r = records[id];
+
+    if (!r.warned_depth && depth(node) > thresholds.max_depth) {
+        r.warned_depth = true;
+        warn(id, type,
+            "rendered tree deeper than " + std::to_string(thresholds.max_depth)
+                + " levels");
+    }
+    if (!r.warned_unkeyed
+        && max_unkeyed(node) > thresholds.unkeyed_children) {
+        r.warned_unkeyed = true;
+        warn(id, type,
+            "more than " + std::to_string(thresholds.unkeyed_children)
+                + " children without an id attribute; diffing them by"
+                  " position causes excessive patching on insertion"
+                  " and removal");
+    }
+
+    const auto hash = hash_tree(node);
+    if (old && hash != r.hash) {
+        if (r.changed_frame + 1 == frame) {
+            r.churn++;
+        } else if (r.changed_frame != frame) {
+            r.churn = 1;
+        }
+        r.changed_frame = frame;
+        if (!r.warned_churn && r.churn >= thresholds.churn_frames) {
+            r.warned_churn = true;
+            warn(id, type,
+                "rendered tree changed on " + std::to_string(r.churn)
+                    + " consecutive frames");
+        }
+    }
+    r.hash = hash;
+
+    if (old) {
+        bool non_empty = false;
+        if (same_attrs(*old, node, non_empty) && non_empty) {
+            r.identical_attrs++;
+        } else {
+            r.identical_attrs = 0;
+        }
+        if (!r.warned_attrs
+            && r.identical_attrs >= thresholds.identical_attrs) {
+            r.warned_attrs = true;
+            warn(id, type,
+                "attributes rebuilt identically on "
+                    + std::to_string(r.identical_attrs)
+                    + " consecutive diffs; consider moving static parts"
+                      " into a separate view");
+        }
+    }
+}
+
+static void set_warnings_default(bool enable) { set_warnings(enable); }
+
+EMSCRIPTEN_BINDINGS(module_perf_warnings)
+{
+    emscripten::function("_bh_set_perf_warnings", &set_warnings_default);
+}
+}
+
+#endif
@@ -0,0 +1,53 @@
+#pragma once
+
+#include "node.hh"
+#include <string>
+#include <typeinfo>
+
+// Opt-in heuristics, that log warnings about rendering patterns likely to
+// hurt performance. Each warning is logged once per view with BH_WARN().
+// Compiled out, if NDEBUG is defined.
+namespace brunhild::perf {
+
+// Thresholds of the performance warning heuristics
+struct WarningThresholds {
+    // Number of consecutive frames a view's rendered tree must change in
+    unsigned churn_frames = 30;
+
+    // Maximum depth of a view's rendered tree
+    unsigned max_depth = 32;
+
+    // Maximum number of children of a node without an explicit "id"
+    // attribute to match them by
+    unsigned unkeyed_children = 200;
+
+    // Number of consecutive diffs of a view, that rebuild all attributes
+    // identically
+    unsigned identical_attrs = 100;
+};
+
+#ifndef NDEBUG
+// Toggle the performance warnings. Disabled by default. Can also be toggled
+// from the browser console with Module._bh_set_perf_warnings(bool).
+void set_warnings(bool enable, WarningThresholds thresholds = {});
+
+// Returns, if performance warnings are enabled
+bool warnings();
+
+// Check a rendered tree of a view. Called by brunhild views on each render.
+// old: previously rendered tree or NULL, if this is the first render
+void check_render(const std::string& id, const std::type_info& type,
+    const Node& node, const Node* old);
+
+// Advance the frame counter. Called by brunhild on each flush.
+void end_frame();
+#else
+inline void set_warnings(bool, WarningThresholds = {}) {}
+inline bool warnings() { return false; }
+inline void check_render(
+    const std::string&, const std::type_info&, const Node&, const Node*)
+{
+}
+inline void end_frame() {}
+#endif
+}
@@ -0,0 +1,171 @@
+#include "persist.hh"
+#include "promise.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::detail {
+
+// Returns the JS storage helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_persist) {
+            return;
+        }
+
+        var db = null;
+        var open = function()
+        {
+            if (!db) {
+                db = new Promise(function(resolve, reject) {
+                    var r = indexedDB.open("brunhild", 1);
+                    r.onupgradeneeded = function()
+                    {
+                        r.result.createObjectStore("persist");
+                    };
+                    r.onsuccess = function() { resolve(r.result); };
+                    r.onerror = function() { reject(r.error); };
+                });
+            }
+            return db;
+        };
+
+        window.__bh_persist = {
+            local_get : function(keys)
+            {
+                var out = {};
+                try {
+                    keys.forEach(function(k) {
+                        var v = localStorage.getItem(k);
+                        if (v !== null) {
+                            out[k] = v;
+                        }
+                    });
+                } catch (e) {
+                    console.error(e);
+                }
+                return out;
+            },
+            local_set : function(entries)
+            {
+                try {
+                    for (var k in entries) {
+                        localStorage.setItem(k, entries[k]);
+                    }
+                } catch (e) {
+                    console.error(e);
+                }
+            },
+            idb_get : function(keys)
+            {
+                return open().then(function(db) {
+                    return new Promise(function(resolve, reject) {
+                        var tx = db.transaction("persist", "readonly");
+                        var s = tx.objectStore("persist");
+                        var out = {};
+                        keys.forEach(function(k) {
+                            var r = s.get(k);
+                            r.onsuccess = function()
+                            {
+                                if (typeof r.result == "string") {
+                                    out[k] = r.result;
+                                }
+                            };
+                        });
+                        tx.oncomplete = function() { resolve(out); };
+                        tx.onerror = function() { reject(tx.error); };
+                    });
+                });
+            },
+            idb_set : function(entries)
+            {
+                open()
+                    .then(function(db) {
+                        var tx = db.transaction("persist", "readwrite");
+                        var s = tx.objectStore("persist");
+                        for (var k in entries) {
+                            s.put(entries[k], k);
+                        }
+                    })
+                    .catch(function(e) { console.error(e); });
+            }
+        };
+    });
+    return val::global("__bh_persist");
+}
+
+// Storage key of a slice
+static string storage_key(const string& prefix, const string& key)
+{
+    return prefix + '.' + key;
+}
+
+// Storage key of the stored version
+static string version_key(const string& prefix)
+{
+    return storage_key(prefix, "@version");
+}
+
+// Convert loaded entries to the version and slices by key
+static void parse(const string& prefix, const std::vector<string>& keys,
+    val entries, std::function<void(unsigned, Raw)> cb)
+{
+    unsigned version = 0;
+    auto v = entries[version_key(prefix)];
+    if (v.isString()) {
+        version = strtoul(v.as<string>().c_str(), nullptr, 10);
+    }
+    Raw raw;
+    for (auto& k : keys) {
+        auto e = entries[storage_key(prefix, k)];
+        if (e.isString()) {
+            raw[k] = e.as<string>();
+        }
+    }
+    cb(version, std::move(raw));
+}
+
+void persist_load(PersistBackend backend, const string& prefix,
+    const std::vector<string>& keys,
+    std::function<void(unsigned version, Raw)> cb)
+{
+    auto arr = val::array();
+    arr.call<void>("push", version_key(prefix));
+    for (auto& k : keys) {
+        arr.call<void>("push", storage_key(prefix, k));
+    }
+
+    switch (backend) {
+    case PersistBackend::local_storage:
+        parse(prefix, keys, helpers().call<val>("local_get", arr), cb);
+        break;
+    case PersistBackend::indexed_db:
+        await(helpers().call<val>("idb_get", arr),
+            [=](bool ok, val entries) {
+                if (!ok) {
+                    val::global("console").call<void>("error", entries);
+                    entries = val::object();
+                }
+                parse(prefix, keys, entries, cb);
+            });
+        break;
+    }
+}
+
+void persist_save(PersistBackend backend, const string& prefix,
+    unsigned version, const Raw& changed)
+{
+    auto entries = val::object();
+    entries.set(version_key(prefix), std::to_string(version));
+    for (auto & [ k, v ] : changed) {
+        entries.set(storage_key(prefix, k), v);
+    }
+    helpers().call<void>(
+        backend == PersistBackend::local_storage ? "local_set" : "idb_set",
+        entries);
+}
+}
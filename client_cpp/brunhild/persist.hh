@@ -0,0 +1,200 @@
+#pragma once
+
+#include "debounce.hh"
+#include "store.hh"
+#include <functional>
+#include <map>
+#include <memory>
+#include <string>
+#include <unordered_map>
+#include <vector>
+
+namespace brunhild {
+
+// Storage backend of persisted state
+enum class PersistBackend {
+    // Synchronous. Rehydrates during Persist construction.
+    local_storage,
+
+    // Asynchronous. Rehydrates some time after Persist construction.
+    indexed_db,
+};
+
+namespace detail {
+    // Serialized slices by key
+    typedef std::unordered_map<std::string, std::string> Raw;
+
+    // Load the stored version and slices of a prefix. cb is called
+    // synchronously for PersistBackend::local_storage. version is 0, if
+    // nothing is stored.
+    void persist_load(PersistBackend, const std::string& prefix,
+        const std::vector<std::string>& keys,
+        std::function<void(unsigned version, Raw)> cb);
+
+    // Write the version and changed slices of a prefix
+    void persist_save(PersistBackend, const std::string& prefix,
+        unsigned version, const Raw& changed);
+}
+
+// Persists selected slices of a Store's state to browser storage, whenever
+// they change, and rehydrates them on construction. Writes are debounced.
+//
+// Stored data is versioned. When rehydrating data of an older version, the
+// migrations to each newer version are applied in order to the serialized
+// slices.
+template <class S> class Persist {
+public:
+    // Persisted part of the state
+    struct Slice {
+        // Unique key of the slice
+        std::string key;
+
+        // Serialize the slice from the state
+        std::function<std::string(const S&)> save;
+
+        // Deserialize the slice into the state
+        std::function<void(S&, const std::string&)> load;
+    };
+
+    // Migrates the serialized slices by key from the previous version.
+    // Migrations may add, remove and rename keys.
+    typedef std::function<void(detail::Raw&)> Migration;
+
+    struct Options {
+        PersistBackend backend = PersistBackend::local_storage;
+
+        // Version of the current serialization format. Must be at least 1.
+        unsigned version = 1;
+
+        // Migrations by the version they migrate to
+        std::map<unsigned, Migration> migrations;
+
+        // Delay writes, until the state has not changed for this many
+        // milliseconds
+        unsigned debounce_ms = 500;
+    };
+
+    // prefix: unique prefix of the storage keys of the persisted slices
+    Persist(Store<S>& store, std::string prefix, std::vector<Slice> slices,
+        Options opts = {})
+        : store(store)
+        , prefix(prefix)
+        , slices(slices)
+        , opts(opts)
+        , save_debounced([this]() { save(); }, opts.debounce_ms)
+    {
+        std::vector<std::string> keys;
+        keys.reserve(slices.size());
+        for (auto& s : slices) {
+            keys.push_back(s.key);
+        }
+        detail::persist_load(opts.backend, prefix, keys,
+            [this, alive = std::weak_ptr<bool>(alive)](
+                unsigned version, detail::Raw raw) {
+                if (alive.lock()) {
+                    rehydrate(version, std::move(raw));
+                }
+            });
+        sub_id = store.subscribe([this](const S&, const std::string& action) {
+            if (action != "@rehydrate") {
+                save_debounced();
+            }
+        });
+    }
+
+    // Writes any pending changes
+    ~Persist()
+    {
+        store.unsubscribe(sub_id);
+        save_debounced.flush();
+    }
+
+    Persist(const Persist&) = delete;
+    Persist& operator=(const Persist&) = delete;
+
+    // Returns, if the stored state has been loaded into the store
+    bool rehydrated() const { return is_rehydrated; }
+
+    // Call fn, once the stored state has been loaded into the store. Called
+    // immediately, if already rehydrated.
+    void on_rehydrated(std::function<void()> fn)
+    {
+        if (is_rehydrated) {
+            fn();
+        } else {
+            rehydrated_cb = fn;
+        }
+    }
+
+    // Write pending changes immediately
+    void flush() { save_debounced.flush(); }
+
+private:
+    Store<S>& store;
+    const std::string prefix;
+    const std::vector<Slice> slices;
+    const Options opts;
+    long sub_id;
+    bool is_rehydrated = false;
+    std::function<void()> rehydrated_cb;
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+    Debounced<> save_debounced;
+
+    // Last written serialized slices
+    detail::Raw saved;
+
+    void rehydrate(unsigned version, detail::Raw raw)
+    {
+        if (version && version < opts.version) {
+            for (auto it = opts.migrations.upper_bound(version);
+                 it != opts.migrations.end() && it->first <= opts.version;
+                 ++it) {
+                it->second(raw);
+            }
+        }
+        if (raw.size()) {
+            store.update(
+                [&](S& state) {
+                    for (auto& s : slices) {
+                        auto it = raw.find(s.key);
+                        if (it != raw.end()) {
+                            s.load(state, it->second);
+                        }
+                    }
+                },
+                "@rehydrate");
+        }
+        saved = std::move(raw);
+        is_rehydrated = true;
+
+        // Persist migrated data and any writes made before rehydration
+        if (version != opts.version || save_debounced.pending()) {
+            save_debounced.cancel();
+            save();
+        }
+        if (auto fn = rehydrated_cb) {
+            rehydrated_cb = nullptr;
+            fn();
+        }
+    }
+
+    // Write all slices, that changed since the last write
+    void save()
+    {
+        // Writing before rehydration would overwrite the stored data
+        if (!is_rehydrated) {
+            return;
+        }
+        detail::Raw changed;
+        for (auto& s : slices) {
+            auto v = s.save(store.get());
+            auto it = saved.find(s.key);
+            if (it == saved.end() || it->second != v) {
+                saved[s.key] = v;
+                changed[s.key] = std::move(v);
+            }
+        }
+        detail::persist_save(opts.backend, prefix, opts.version, changed);
+    }
+};
+}
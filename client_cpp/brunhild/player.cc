@@ -0,0 +1,277 @@
+#include "player.hh"
+#include "mutations.hh"
+#include "promise.hh"
+#include "view.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All live players by ID
+static std::unordered_map<long, Player*> players;
+
+// Event types always listened to
+static const char* const builtin_events[] = { "timeupdate", "ended", "error" };
+
+// Returns the controlled element or null
+static val get_el(const string& id) { return val::global("__bh_get")(id); }
+
+Player::Player(string id)
+    : id(id)
+    , player_id(id_counter++)
+{
+    players[player_id] = this;
+    if (!attach()) {
+        const long p_id = player_id;
+        defer([p_id]() {
+            if (players.count(p_id)) {
+                players.at(p_id)->attach();
+            }
+        });
+    }
+}
+
+Player::Player(View& view, string id)
+    : Player(id)
+{
+    view.on_next_unmount([this, alive = alive]() {
+        if (*alive) {
+            detach();
+        }
+    });
+}
+
+Player::~Player()
+{
+    *alive = false;
+    detach();
+    players.erase(player_id);
+}
+
+bool Player::attach()
+{
+    if (attached) {
+        return true;
+    }
+    const bool ok = EM_ASM_INT(
+        {
+            if (!window.__bh_players) {
+                window.__bh_players = {};
+            }
+            var el = window.__bh_get(UTF8ToString($0));
+            if (!el) {
+                return 0;
+            }
+            window.__bh_players[$1] = { el : el, listeners : {} };
+            return 1;
+        },
+        id.c_str(), player_id);
+    if (!ok) {
+        return false;
+    }
+    attached = true;
+    for (auto type : builtin_events) {
+        listen(type);
+    }
+    for (auto & [ type, _ ] : handlers) {
+        listen(type);
+    }
+    return true;
+}
+
+void Player::listen(const string& type)
+{
+    if (!attached) {
+        return;
+    }
+    EM_ASM_INT(
+        {
+            var p = window.__bh_players[$0];
+            var type = UTF8ToString($1);
+            if (!p || p.listeners[type]) {
+                return;
+            }
+            var id = $0;
+            var fn = function() { Module._bh_media_event(id, type); };
+            p.listeners[type] = fn;
+            p.el.addEventListener(type, fn);
+        },
+        player_id, type.c_str());
+}
+
+void Player::detach()
+{
+    if (!attached) {
+        return;
+    }
+    attached = false;
+    EM_ASM_INT(
+        {
+            var s = window.__bh_players;
+            var p = s && s[$0];
+            if (!p) {
+                return;
+            }
+            for (var type in p.listeners) {
+                p.el.removeEventListener(type, p.listeners[type]);
+            }
+            delete s[$0];
+        },
+        player_id);
+}
+
+template <class T> void Player::set(const char* key, T v)
+{
+    auto el = get_el(id);
+    if (!el.isNull()) {
+        el.set(key, v);
+    }
+}
+
+void Player::play(std::function<void(bool)> on_result)
+{
+    auto el = get_el(id);
+    if (el.isNull()) {
+        if (on_result) {
+            on_result(false);
+        }
+        return;
+    }
+    auto res = el.call<val>("play");
+
+    // Older browsers do not return a promise
+    if (res.isUndefined()) {
+        if (on_result) {
+            on_result(true);
+        }
+        return;
+    }
+    await(res, [on_result](bool ok, val) {
+        if (on_result) {
+            on_result(ok);
+        }
+    });
+}
+
+void Player::pause()
+{
+    auto el = get_el(id);
+    if (!el.isNull()) {
+        el.call<void>("pause");
+    }
+}
+
+void Player::seek(double seconds) { set("currentTime", seconds); }
+
+void Player::set_volume(double v)
+{
+    set("volume", v < 0 ? 0 : (v > 1 ? 1 : v));
+}
+
+void Player::set_muted(bool m) { set("muted", m); }
+
+void Player::set_playback_rate(double r) { set("playbackRate", r); }
+
+void Player::set_loop(bool l) { set("loop", l); }
+
+// Read a numeric property of the element or return 0
+static double get_double(const string& id, const char* key)
+{
+    auto el = get_el(id);
+    if (el.isNull()) {
+        return 0;
+    }
+    const auto v = el[key];
+    return v.isNumber() ? v.as<double>() : 0;
+}
+
+// Read a boolean property of the element or return false
+static bool get_bool(const string& id, const char* key)
+{
+    auto el = get_el(id);
+    return !el.isNull() && el[key].as<bool>();
+}
+
+double Player::current_time() const { return get_double(id, "currentTime"); }
+
+double Player::duration() const
+{
+    // NaN, until metadata is loaded
+    const double d = get_double(id, "duration");
+    return d != d ? 0 : d;
+}
+
+double Player::volume() const { return get_double(id, "volume"); }
+bool Player::muted() const { return get_bool(id, "muted"); }
+double Player::playback_rate() const { return get_double(id, "playbackRate"); }
+bool Player::paused() const { return get_bool(id, "paused"); }
+bool Player::ended() const { return get_bool(id, "ended"); }
+
+void Player::on_time_update(std::function<void(double)> fn)
+{
+    time_update_cb = fn;
+}
+
+void Player::on_ended(std::function<void()> fn) { ended_cb = fn; }
+
+void Player::on_error(std::function<void(MediaError)> fn) { error_cb = fn; }
+
+void Player::on(string type, std::function<void()> fn)
+{
+    handlers[type] = fn;
+    listen(type);
+}
+
+void run_media_event(long id, string type)
+{
+    if (!players.count(id)) {
+        return;
+    }
+    auto p = players.at(id);
+
+    // Handlers might destroy the player, so copy them first
+    if (type == "timeupdate") {
+        if (auto fn = p->time_update_cb) {
+            fn(p->current_time());
+        }
+    } else if (type == "ended") {
+        if (auto fn = p->ended_cb) {
+            fn();
+        }
+    } else if (type == "error") {
+        if (auto fn = p->error_cb) {
+            MediaError err = { MediaError::aborted, "" };
+            auto el = get_el(p->id);
+            auto e = el.isNull() ? val::null() : el["error"];
+            if (!e.isNull() && !e.isUndefined()) {
+                err.code = MediaError::Code(e["code"].as<int>());
+                const auto msg = e["message"];
+                if (msg.isString()) {
+                    err.message = msg.as<string>();
+                }
+            }
+            fn(err);
+        }
+    }
+    if (!players.count(id)) {
+        return;
+    }
+    auto it = p->handlers.find(type);
+    if (it != p->handlers.end()) {
+        auto fn = it->second;
+        fn();
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_player)
+{
+    emscripten::function("_bh_media_event", &run_media_event);
+}
+}
@@ -0,0 +1,122 @@
+#pragma once
+
+#include <functional>
+#include <memory>
+#include <string>
+#include <unordered_map>
+
+namespace brunhild {
+
+class View;
+
+// Error of a media element. Mirrors the MediaError JS object.
+struct MediaError {
+    // MEDIA_ERR_* code of the error
+    enum Code {
+        aborted = 1,
+        network = 2,
+        decode = 3,
+        src_not_supported = 4,
+    } code;
+
+    // Browser-specific diagnostic message. Might be empty.
+    std::string message;
+};
+
+// Control of an <audio> or <video> element by ID.
+// The element is looked up on each call, so it may be rerendered. All methods
+// are no-ops and all getters return zero values, if the element does not
+// exist. If the element does not exist yet, event listeners are attached after
+// the next flush of DOM mutations. Listeners are detached on destruction.
+class Player {
+public:
+    // ID of controlled element
+    const std::string id;
+
+    // Control an element by ID
+    Player(std::string id);
+
+    // Control an element in the subtree of a view. Also detaches listeners,
+    // when the view is unmounted.
+    Player(View& view, std::string id);
+
+    ~Player();
+
+    Player(const Player&) = delete;
+    Player& operator=(const Player&) = delete;
+
+    // Start playback. on_result is optional and receives false, if the
+    // browser refused playback, for example due to autoplay restrictions.
+    void play(std::function<void(bool ok)> on_result = nullptr);
+
+    void pause();
+
+    // Seek to a position in seconds
+    void seek(double seconds);
+
+    // Set volume in the range [0, 1]. Values out of range are clamped.
+    void set_volume(double);
+
+    void set_muted(bool);
+
+    // Set playback speed. 1 is normal speed.
+    void set_playback_rate(double);
+
+    void set_loop(bool);
+
+    // Returns the current position in seconds
+    double current_time() const;
+
+    // Returns the duration in seconds, 0, if not yet known, or INFINITY for
+    // streams
+    double duration() const;
+
+    double volume() const;
+    bool muted() const;
+    double playback_rate() const;
+    bool paused() const;
+    bool ended() const;
+
+    // Receives the current position in seconds, as playback progresses.
+    // Replaces any previous handler.
+    void on_time_update(std::function<void(double)>);
+
+    // Called, when playback reaches the end. Replaces any previous handler.
+    void on_ended(std::function<void()>);
+
+    // Called, when loading or decoding fails. Replaces any previous handler.
+    void on_error(std::function<void(MediaError)>);
+
+    // Call fn on any other media event type, like "play", "pause",
+    // "volumechange" or "loadedmetadata". Replaces any previous handler for
+    // the type.
+    void on(std::string type, std::function<void()> fn);
+
+    // Detach all event listeners
+    void detach();
+
+private:
+    const long player_id;
+    bool attached = false;
+
+    std::function<void(double)> time_update_cb;
+    std::function<void()> ended_cb;
+    std::function<void(MediaError)> error_cb;
+    std::unordered_map<std::string, std::function<void()>> handlers;
+
+    // Guards the view's unmount hook against running after destruction
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Attach listeners for all event types with handlers. Returns false, if
+    // the element does not exist.
+    bool attach();
+
+    // Attach a listener for an event type, if attached
+    void listen(const std::string& type);
+
+    // Set a property on the element
+    template <class T> void set(const char* key, T val);
+
+    friend void run_media_event(long, std::string);
+};
+}
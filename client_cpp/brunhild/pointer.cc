@@ -0,0 +1,101 @@
+#include "pointer.hh"
+#include "ref.hh"
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+Pointer::Pointer(val& event)
+    : id(event["pointerId"].as<long>())
+    , type(event["pointerType"].as<string>())
+    , x(event["clientX"].as<double>())
+    , y(event["clientY"].as<double>())
+    , buttons(event["buttons"].as<int>())
+    , pressure(event["pressure"].as<double>())
+    , primary(event["isPrimary"].as<bool>())
+    , target(event["target"]["id"].as<string>())
+{
+}
+
+void set_pointer_capture(const string& id, long pointer_id)
+{
+    auto el = Ref(id).el();
+    if (!el.isNull()) {
+        el.call<void>("setPointerCapture", pointer_id);
+    }
+}
+
+void set_pointer_capture(val& event)
+{
+    event["target"].call<void>("setPointerCapture", event["pointerId"]);
+}
+
+void release_pointer_capture(const string& id, long pointer_id)
+{
+    auto el = Ref(id).el();
+    if (!el.isNull() && el.call<bool>("hasPointerCapture", pointer_id)) {
+        el.call<void>("releasePointerCapture", pointer_id);
+    }
+}
+
+bool has_pointer_capture(const string& id, long pointer_id)
+{
+    auto el = Ref(id).el();
+    return !el.isNull() && el.call<bool>("hasPointerCapture", pointer_id);
+}
+
+PointerDrag::PointerDrag(
+    string selector, Callback on_start, Callback on_move, EndCallback on_end)
+    : selector(selector)
+    , on_start(on_start)
+    , on_move(on_move)
+    , on_end(on_end)
+{
+    // Captured pointers keep targeting the element they were pressed on, so
+    // the same selector matches all further events of the drag
+    handlers.push_back(register_handler("pointerdown",
+        [this](val& event) {
+            const Pointer p(event);
+            if (p.type == "mouse" && event["button"].as<int>() != 0) {
+                return;
+            }
+            set_pointer_capture(event);
+            active.insert(p.id);
+            if (this->on_start) {
+                this->on_start(p);
+            }
+        },
+        selector));
+    handlers.push_back(register_handler("pointermove",
+        [this](val& event) {
+            const Pointer p(event);
+            if (active.count(p.id) && this->on_move) {
+                this->on_move(p);
+            }
+        },
+        selector));
+    handlers.push_back(register_handler(
+        "pointerup", [this](val& event) { end(event, false); }, selector));
+    handlers.push_back(register_handler(
+        "pointercancel", [this](val& event) { end(event, true); }, selector));
+}
+
+PointerDrag::~PointerDrag()
+{
+    for (auto id : handlers) {
+        unregister_handler(id);
+    }
+}
+
+void PointerDrag::end(val& event, bool cancelled)
+{
+    const Pointer p(event);
+    if (!active.erase(p.id)) {
+        return;
+    }
+    if (on_end) {
+        on_end(p, cancelled);
+    }
+}
+}
@@ -0,0 +1,89 @@
+#pragma once
+
+#include "events.hh"
+#include <emscripten/val.h>
+#include <functional>
+#include <string>
+#include <unordered_set>
+#include <vector>
+
+namespace brunhild {
+
+// Fields of a PointerEvent common to mouse, touch and pen input
+struct Pointer {
+    // pointerId of the event
+    long id;
+
+    // "mouse", "touch" or "pen"
+    std::string type;
+
+    // Position relative to the viewport
+    double x, y;
+
+    // Bit mask of pressed buttons
+    int buttons;
+
+    // Pressure in the range [0, 1]
+    double pressure;
+
+    // Primary pointer of its type, like the first finger of a multi-touch
+    bool primary;
+
+    // ID of the target element. Empty, if it has no ID.
+    std::string target;
+
+    // Read from a PointerEvent passed to a Handler
+    Pointer(emscripten::val& event);
+};
+
+// Route all further events of a pointer to the element until released or the
+// pointer is lifted. No-op, if the element does not exist.
+void set_pointer_capture(const std::string& id, long pointer_id);
+
+// Capture the pointer of a PointerEvent to its target element
+void set_pointer_capture(emscripten::val& event);
+
+// Release a pointer captured by the element
+void release_pointer_capture(const std::string& id, long pointer_id);
+
+// Returns, if the element has captured the pointer
+bool has_pointer_capture(const std::string& id, long pointer_id);
+
+// Captures pointers pressed on elements matching a CSS selector and reports
+// their movement until lifted. Works the same for mouse, touch and pen.
+// Elements should have the "touch-action: none" CSS rule, so touch pointers
+// are not taken over by the browser for scrolling.
+class PointerDrag {
+public:
+    // Called with the pointer on pointerdown, pointermove or pointerup
+    typedef std::function<void(const Pointer&)> Callback;
+
+    // Called on pointerup or pointercancel. cancelled is set on the latter.
+    typedef std::function<void(const Pointer&, bool cancelled)> EndCallback;
+
+    // CSS selector of elements to start drags on
+    const std::string selector;
+
+    PointerDrag(std::string selector, Callback on_start, Callback on_move,
+        EndCallback on_end = nullptr);
+    ~PointerDrag();
+
+    PointerDrag(const PointerDrag&) = delete;
+    PointerDrag& operator=(const PointerDrag&) = delete;
+
+    // Returns, if any pointers are currently being dragged
+    bool dragging() const { return !active.empty(); }
+
+private:
+    Callback on_start, on_move;
+    EndCallback on_end;
+
+    // IDs of currently captured pointers
+    std::unordered_set<long> active;
+
+    // IDs of registered event handlers
+    std::vector<long> handlers;
+
+    void end(emscripten::val& event, bool cancelled);
+};
+}
@@ -0,0 +1,38 @@
+#include "portal.hh"
+#include "mutations.hh"
+
+namespace brunhild {
+
+Portal::Portal(std::string target, std::shared_ptr<View> child, std::string id)
+    : View(id)
+    , target(target)
+    , child(child)
+{
+}
+
+void Portal::write_html(Rope& s)
+{
+    if (!is_initialized) {
+        is_initialized = true;
+        schedule_mount();
+        append(target, child->html());
+    }
+    s << "<template id=\"" << id << "\"></template>";
+}
+
+void Portal::patch()
+{
+    if (is_initialized) {
+        child->patch();
+    }
+}
+
+void Portal::unmount()
+{
+    if (is_initialized) {
+        child->remove();
+        is_initialized = false;
+    }
+    View::unmount();
+}
+}
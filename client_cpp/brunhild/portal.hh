@@ -0,0 +1,41 @@
+#pragma once
+
+#include "view.hh"
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// Renders a child view under an arbitrary target element, while it is still
+// owned by the parent view of the portal. Useful for modals, tooltips and other
+// elements, that need to live at the document body level.
+// Only an invisible placeholder element is rendered in the portal's position.
+// The child is patched with the portal and removed, when the portal is
+// removed or its parent view unmounted.
+class Portal : public View {
+public:
+    // ID of the element the child is appended to
+    const std::string target;
+
+    // Creates a new portal rendering child into the target element
+    Portal(std::string target, std::shared_ptr<View> child,
+        std::string id = new_id());
+
+    // Writes the placeholder element and appends the child to the target
+    void write_html(Rope&);
+
+    // Patch the child view
+    void patch();
+
+    // Returns the child view
+    View& get_child() { return *child; }
+
+protected:
+    // Removes the child view from the target element
+    void unmount();
+
+private:
+    std::shared_ptr<View> child;
+    bool is_initialized = false;
+};
+}
@@ -0,0 +1,178 @@
+#include "position.hh"
+#include "mutations.hh"
+#include "resize.hh"
+#include <emscripten.h>
+
+using std::string;
+
+namespace brunhild {
+
+// Returns the opposite side
+static Side opposite(Side s)
+{
+    switch (s) {
+    case Side::top:
+        return Side::bottom;
+    case Side::bottom:
+        return Side::top;
+    case Side::left:
+        return Side::right;
+    default:
+        return Side::left;
+    }
+}
+
+// Returns, if the side places the floating element above or below the
+// reference
+static bool vertical(Side s) { return s == Side::top || s == Side::bottom; }
+
+// Returns the space available between the reference and the viewport edge
+// on a side
+static double space(const Rect& ref, Size vp, Side s, double padding)
+{
+    switch (s) {
+    case Side::top:
+        return ref.top - padding;
+    case Side::bottom:
+        return vp.height - ref.bottom - padding;
+    case Side::left:
+        return ref.left - padding;
+    default:
+        return vp.width - ref.right - padding;
+    }
+}
+
+static double clamp(double v, double min, double max)
+{
+    if (max < min) {
+        return min;
+    }
+    return v < min ? min : (v > max ? max : v);
+}
+
+Position compute_position(
+    const Rect& ref, Size fl, Size vp, const PositionOptions& opts)
+{
+    // Flip, if the preferred side does not fit and the opposite fits better
+    Side side = opts.side;
+    if (opts.flip) {
+        const double need
+            = (vertical(side) ? fl.height : fl.width) + opts.offset;
+        const double have = space(ref, vp, side, opts.padding);
+        if (have < need
+            && space(ref, vp, opposite(side), opts.padding) > have) {
+            side = opposite(side);
+        }
+    }
+
+    Position p;
+    p.side = side;
+    switch (side) {
+    case Side::top:
+        p.y = ref.top - fl.height - opts.offset;
+        break;
+    case Side::bottom:
+        p.y = ref.bottom + opts.offset;
+        break;
+    case Side::left:
+        p.x = ref.left - fl.width - opts.offset;
+        break;
+    case Side::right:
+        p.x = ref.right + opts.offset;
+        break;
+    }
+
+    // Alignment along the side
+    const bool v = vertical(side);
+    const double ref_start = v ? ref.left : ref.top;
+    const double ref_len = v ? ref.width : ref.height;
+    const double fl_len = v ? fl.width : fl.height;
+    double along;
+    switch (opts.align) {
+    case Align::start:
+        along = ref_start;
+        break;
+    case Align::end:
+        along = ref_start + ref_len - fl_len;
+        break;
+    default:
+        along = ref_start + (ref_len - fl_len) / 2;
+    }
+    if (opts.shift) {
+        const double vp_len = v ? vp.width : vp.height;
+        along = clamp(along, opts.padding, vp_len - fl_len - opts.padding);
+    }
+    (v ? p.x : p.y) = along;
+
+    // Point the arrow at the center of the reference, but keep it off the
+    // corners of the floating element
+    const double half = opts.arrow_size / 2;
+    p.arrow = clamp(ref_start + ref_len / 2 - along, half, fl_len - half);
+    return p;
+}
+
+Anchor::Anchor(string floating_id, string reference_id, PositionOptions opts,
+    string arrow_id)
+    : floating_id(floating_id)
+    , reference_id(reference_id)
+    , arrow_id(arrow_id)
+    , opts(opts)
+    , on_scroll([this](ScrollOffset) { update(); })
+    , on_resize([this](Size) { update(); })
+    , observer(
+          std::make_unique<Resize>(floating_id, [this](Size) { update(); }))
+{
+    if (Ref(floating_id).exists()) {
+        update();
+    } else {
+        defer([this, alive = alive]() {
+            if (*alive) {
+                update();
+            }
+        });
+    }
+}
+
+Anchor::~Anchor() { *alive = false; }
+
+void Anchor::set_options(PositionOptions o)
+{
+    opts = o;
+    update();
+}
+
+void Anchor::update()
+{
+    const auto ref = Ref(reference_id).bounding_rect();
+    const auto fl = Ref(floating_id).offset_size();
+    if (!ref || !fl) {
+        return;
+    }
+    const auto p = compute_position(*ref, *fl, viewport_size(), opts);
+    last = p;
+
+    static const char* const sides[] = { "top", "bottom", "left", "right" };
+    EM_ASM_INT(
+        {
+            var el = window.__bh_get(UTF8ToString($0));
+            if (!el) {
+                return;
+            }
+            var side = UTF8ToString($4);
+            el.style.position = "fixed";
+            el.style.left = $1 + "px";
+            el.style.top = $2 + "px";
+            el.setAttribute("data-side", side);
+
+            var arrow = $5 ? window.__bh_get(UTF8ToString($5)) : null;
+            if (arrow) {
+                var v = side == "top" || side == "bottom";
+                arrow.style.position = "absolute";
+                arrow.style.left = v ? $3 + "px" : "";
+                arrow.style.top = v ? "" : $3 + "px";
+            }
+        },
+        floating_id.c_str(), p.x, p.y, p.arrow, sides[int(p.side)],
+        arrow_id.size() ? arrow_id.c_str() : nullptr);
+}
+}
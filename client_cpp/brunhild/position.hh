@@ -0,0 +1,99 @@
+#pragma once
+
+#include "ref.hh"
+#include "window.hh"
+#include <memory>
+#include <optional>
+#include <string>
+
+namespace brunhild {
+
+// Side of the reference element a floating element is placed on
+enum class Side { top, bottom, left, right };
+
+// Alignment of a floating element along the side of the reference element
+enum class Align { start, center, end };
+
+// Options of floating element positioning
+struct PositionOptions {
+    // Preferred placement
+    Side side = Side::bottom;
+    Align align = Align::center;
+
+    // Distance between the reference and floating element
+    double offset = 8;
+
+    // Move to the opposite side, if the preferred one does not have enough
+    // space
+    bool flip = true;
+
+    // Shift along the side to keep the floating element inside the viewport
+    bool shift = true;
+
+    // Minimum distance to keep from the viewport edges
+    double padding = 4;
+
+    // Size of an arrow element pointing at the reference. Used to keep the
+    // arrow away from the floating element's corners.
+    double arrow_size = 0;
+};
+
+// Computed position of a floating element
+struct Position {
+    // Offset of the floating element's top left corner from the viewport's
+    double x, y;
+
+    // Side actually placed on after flipping
+    Side side;
+
+    // Offset of the arrow's center along the edge of the floating element
+    // facing the reference
+    double arrow;
+};
+
+// Compute the position of a floating element of a given size next to a
+// reference rectangle in a viewport of a given size
+Position compute_position(const Rect& reference, Size floating, Size viewport,
+    const PositionOptions& opts = {});
+
+class Resize;
+
+// Keeps a floating element positioned next to a reference element. The
+// floating element is given fixed positioning. Recomputes on window scrolls
+// and resizes and size changes of the floating element.
+//
+// The side placed on is set as the floating element's "data-side" attribute.
+// If arrow_id is set, the arrow element is positioned along the floating
+// element's edge facing the reference.
+class Anchor {
+public:
+    // IDs of the floating, reference and optional arrow elements
+    const std::string floating_id, reference_id, arrow_id;
+
+    Anchor(std::string floating_id, std::string reference_id,
+        PositionOptions opts = {}, std::string arrow_id = "");
+    ~Anchor();
+
+    Anchor(const Anchor&) = delete;
+    Anchor& operator=(const Anchor&) = delete;
+
+    // Recompute the position now. No-op, if either element does not exist.
+    void update();
+
+    // Set new options and recompute
+    void set_options(PositionOptions);
+
+    // Returns the last computed position, if any
+    const std::optional<Position>& position() const { return last; }
+
+private:
+    PositionOptions opts;
+    std::optional<Position> last;
+    OnScroll on_scroll;
+    OnResize on_resize;
+    std::unique_ptr<Resize> observer;
+
+    // Guards deferred functions against running after destruction
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+};
+}
@@ -0,0 +1,150 @@
+#include "press.hh"
+#include <emscripten.h>
+
+using std::string;
+
+namespace brunhild {
+
+const char* const long_press_event = "bh-longpress";
+const char* const double_tap_event = "bh-doubletap";
+
+Press::Press(emscripten::val& event)
+    : x(event["detail"]["x"].as<double>())
+    , y(event["detail"]["y"].as<double>())
+    , pointer_type(event["detail"]["pointerType"].as<string>())
+{
+}
+
+void enable_press_events(PressOptions opts)
+{
+    EM_ASM_INT(
+        {
+            var o = {
+                long_press : $0,
+                double_tap : $1,
+                tolerance : $2
+            };
+            if (window.__bh_press) {
+                window.__bh_press.opts = o;
+                return;
+            }
+
+            var r = window.__bh_press = { opts : o, listeners : {} };
+            var s = null;         // Current press
+            var last = null;      // Last completed tap
+            var suppress = false; // Suppress click after long press
+
+            var dispatch = function(el, type, e)
+            {
+                el.dispatchEvent(new CustomEvent(type, {
+                    bubbles : true,
+                    composed : true,
+                    detail : {
+                        x : e.clientX,
+                        y : e.clientY,
+                        pointerType : e.pointerType
+                    }
+                }));
+            };
+            var cancel = function()
+            {
+                if (s) {
+                    clearTimeout(s.timer);
+                    s = null;
+                }
+            };
+
+            r.listeners.pointerdown = function(e)
+            {
+                cancel();
+                if (!e.isPrimary || e.button) {
+                    return;
+                }
+                var el = e.composedPath ? e.composedPath()[0] : e.target;
+                if (!el.tagName) {
+                    return;
+                }
+                suppress = false;
+                s = { el : el, x : e.clientX, y : e.clientY };
+                s.timer = setTimeout(function() {
+                    suppress = true;
+                    last = null;
+                    dispatch(s.el, "bh-longpress", e);
+                    s = null;
+                }, r.opts.long_press);
+            };
+            r.listeners.pointermove = function(e)
+            {
+                if (!s || !e.isPrimary) {
+                    return;
+                }
+                var dx = e.clientX - s.x;
+                var dy = e.clientY - s.y;
+                if (Math.sqrt(dx * dx + dy * dy) > r.opts.tolerance) {
+                    cancel();
+                }
+            };
+            r.listeners.pointerup = function(e)
+            {
+                if (!s || !e.isPrimary) {
+                    return;
+                }
+                var el = s.el;
+                cancel();
+                var now = Date.now();
+                if (last && last.el == el && now - last.time
+                        <= r.opts.double_tap
+                    && Math.abs(e.clientX - last.x) <= r.opts.tolerance
+                    && Math.abs(e.clientY - last.y) <= r.opts.tolerance) {
+                    last = null;
+                    dispatch(el, "bh-doubletap", e);
+                } else {
+                    last = {
+                        el : el,
+                        time : now,
+                        x : e.clientX,
+                        y : e.clientY
+                    };
+                }
+            };
+            r.listeners.pointercancel = cancel;
+            for (var type in r.listeners) {
+                document.addEventListener(
+                    type, r.listeners[type], { passive : true });
+            }
+
+            // Need to be able to call preventDefault()
+            r.suppress = function(e)
+            {
+                if (suppress) {
+                    e.preventDefault();
+                    e.stopPropagation();
+                    if (e.type == "click") {
+                        suppress = false;
+                    }
+                }
+            };
+            document.addEventListener("click", r.suppress, true);
+            document.addEventListener("contextmenu", r.suppress, true);
+        },
+        opts.long_press_duration, opts.double_tap_interval,
+        opts.move_tolerance);
+}
+
+void disable_press_events()
+{
+    EM_ASM({
+        var r = window.__bh_press;
+        if (!r) {
+            return;
+        }
+        for (var type in r.listeners) {
+            document.removeEventListener(
+                type, r.listeners[type], { passive : true });
+        }
+        document.removeEventListener("click", r.suppress, true);
+        document.removeEventListener("contextmenu", r.suppress, true);
+        delete window.__bh_press;
+    });
+}
+}
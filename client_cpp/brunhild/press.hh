@@ -0,0 +1,47 @@
+#pragma once
+
+#include <emscripten/val.h>
+#include <string>
+
+namespace brunhild {
+
+// DOM event type dispatched on an element pressed and held without moving
+extern const char* const long_press_event;
+
+// DOM event type dispatched on an element tapped or clicked twice in quick
+// succession
+extern const char* const double_tap_event;
+
+// Thresholds of press recognition
+struct PressOptions {
+    // Minimum duration of a long press and maximum interval between the taps
+    // of a double tap in milliseconds
+    double long_press_duration = 500, double_tap_interval = 300;
+
+    // Maximum pointer movement in CSS pixels during a press
+    double move_tolerance = 10;
+};
+
+// Position and pointer type of a recognized press
+struct Press {
+    // Position relative to the viewport
+    double x, y;
+
+    // "mouse", "touch" or "pen"
+    std::string pointer_type;
+
+    // Read from a long_press_event or double_tap_event passed to a Handler
+    Press(emscripten::val& event);
+};
+
+// Start recognizing long presses and double taps from pointer events on the
+// entire document. Recognized presses are dispatched as bubbling DOM events of
+// type long_press_event and double_tap_event on the pressed element and can
+// be handled with View::on() and register_handler() like any other event.
+// The click and context menu following a long press are suppressed.
+// Calling again updates the options.
+void enable_press_events(PressOptions opts = {});
+
+// Stop recognizing long presses and double taps
+void disable_press_events();
+}
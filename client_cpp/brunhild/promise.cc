@@ -0,0 +1,48 @@
+#include "promise.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using emscripten::val;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// Callbacks of pending promises by ID
+static std::unordered_map<long, Settle> pending;
+
+void await(val promise, Settle cb)
+{
+    EM_ASM({
+        if (window.__bh_await) {
+            return;
+        }
+        window.__bh_await = function(p, id)
+        {
+            Promise.resolve(p).then(
+                function(v) { Module._bh_settle(id, true, v); },
+                function(e) { Module._bh_settle(id, false, e); });
+        };
+    });
+
+    const long id = id_counter++;
+    pending[id] = cb;
+    val::global("__bh_await")(promise, id);
+}
+
+static void settle(long id, bool ok, val value)
+{
+    if (!pending.count(id)) {
+        return;
+    }
+    auto cb = std::move(pending.at(id));
+    pending.erase(id);
+    cb(ok, value);
+}
+
+EMSCRIPTEN_BINDINGS(module_promise)
+{
+    emscripten::function("_bh_settle", &settle);
+}
+}
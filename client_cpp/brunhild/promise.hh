@@ -0,0 +1,15 @@
+#pragma once
+
+#include <emscripten/val.h>
+#include <functional>
+
+namespace brunhild {
+
+// Receives the settled value of a JS promise. ok is false, if the promise was
+// rejected, in which case value is the rejection reason.
+typedef std::function<void(bool ok, emscripten::val value)> Settle;
+
+// Call cb, once a JS promise settles. Non-promise values settle
+// asynchronously as resolved.
+void await(emscripten::val promise, Settle cb);
+}
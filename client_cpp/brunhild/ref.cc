@@ -0,0 +1,102 @@
+#include "ref.hh"
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+val Ref::el() const { return val::global("window").call<val>("__bh_get", id); }
+
+bool Ref::exists() const { return !el().isNull(); }
+
+std::optional<Rect> Ref::bounding_rect() const
+{
+    auto e = el();
+    if (e.isNull()) {
+        return std::nullopt;
+    }
+    auto r = e.call<val>("getBoundingClientRect");
+    return Rect{
+        r["x"].as<double>(),
+        r["y"].as<double>(),
+        r["width"].as<double>(),
+        r["height"].as<double>(),
+        r["top"].as<double>(),
+        r["right"].as<double>(),
+        r["bottom"].as<double>(),
+        r["left"].as<double>(),
+    };
+}
+
+std::optional<Size> Ref::offset_size() const
+{
+    auto e = el();
+    if (e.isNull()) {
+        return std::nullopt;
+    }
+    return Size{ e["offsetWidth"].as<double>(), e["offsetHeight"].as<double>() };
+}
+
+std::optional<Size> Ref::client_size() const
+{
+    auto e = el();
+    if (e.isNull()) {
+        return std::nullopt;
+    }
+    return Size{ e["clientWidth"].as<double>(), e["clientHeight"].as<double>() };
+}
+
+std::optional<double> Ref::scroll_top() const
+{
+    auto e = el();
+    if (e.isNull()) {
+        return std::nullopt;
+    }
+    return e["scrollTop"].as<double>();
+}
+
+void Ref::set_scroll_top(double v) const
+{
+    auto e = el();
+    if (!e.isNull()) {
+        e.set("scrollTop", v);
+    }
+}
+
+std::optional<double> Ref::scroll_left() const
+{
+    auto e = el();
+    if (e.isNull()) {
+        return std::nullopt;
+    }
+    return e["scrollLeft"].as<double>();
+}
+
+void Ref::set_scroll_left(double v) const
+{
+    auto e = el();
+    if (!e.isNull()) {
+        e.set("scrollLeft", v);
+    }
+}
+
+std::optional<Size> Ref::scroll_size() const
+{
+    auto e = el();
+    if (e.isNull()) {
+        return std::nullopt;
+    }
+    return Size{ e["scrollWidth"].as<double>(), e["scrollHeight"].as<double>() };
+}
+
+std::optional<string> Ref::computed_style(const string& prop) const
+{
+    auto e = el();
+    if (e.isNull()) {
+        return std::nullopt;
+    }
+    return val::global("window")
+        .call<val>("getComputedStyle", e)
+        .call<string>("getPropertyValue", prop);
+}
+}
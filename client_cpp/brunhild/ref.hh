@@ -0,0 +1,66 @@
+#pragma once
+
+#include <emscripten/val.h>
+#include <optional>
+#include <string>
+
+namespace brunhild {
+
+// Rectangle in CSS pixels relative to the viewport
+struct Rect {
+    double x, y, width, height, top, right, bottom, left;
+};
+
+// Dimensions in CSS pixels
+struct Size {
+    double width, height;
+};
+
+// Handle to an element for reading its geometry and computed style.
+// Reads the current state of the DOM, so any pending mutations are not
+// reflected until flushed. All methods return an empty value, if the element
+// does not exist.
+class Ref {
+public:
+    // ID of the referenced element
+    const std::string id;
+
+    Ref(std::string id)
+        : id(id)
+    {
+    }
+
+    // Returns, if the element currently exists in the DOM
+    bool exists() const;
+
+    // Returns the element's getBoundingClientRect()
+    std::optional<Rect> bounding_rect() const;
+
+    // Returns the element's offsetWidth and offsetHeight
+    std::optional<Size> offset_size() const;
+
+    // Returns the element's clientWidth and clientHeight
+    std::optional<Size> client_size() const;
+
+    // Returns the element's scrollTop
+    std::optional<double> scroll_top() const;
+
+    // Set the element's scrollTop
+    void set_scroll_top(double) const;
+
+    // Returns the element's scrollLeft
+    std::optional<double> scroll_left() const;
+
+    // Set the element's scrollLeft
+    void set_scroll_left(double) const;
+
+    // Returns the element's scrollWidth and scrollHeight
+    std::optional<Size> scroll_size() const;
+
+    // Returns the computed value of a CSS property, like "font-size"
+    std::optional<std::string> computed_style(const std::string& prop) const;
+
+    // Returns the underlying element or null
+    emscripten::val el() const;
+};
+}
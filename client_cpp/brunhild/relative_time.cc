@@ -0,0 +1,129 @@
+#include "relative_time.hh"
+#include "schedule.hh"
+#include "timer.hh"
+#include <cmath>
+#include <emscripten/val.h>
+#include <unordered_set>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+// Mounted instances
+static std::unordered_set<RelativeTime*> instances;
+
+static unsigned interval = 30000;
+
+// ID of the shared ticker or -1, if not running
+static long ticker = -1;
+
+// Returns the current Unix timestamp in seconds
+static double now() { return val::global("Date").call<double>("now") / 1000; }
+
+// Start or stop the shared ticker depending on the mounted instances
+static void update_ticker()
+{
+    if (instances.size() && ticker == -1) {
+        // Pausable, as nobody sees the text of a hidden page. Outdated text
+        // is updated on the first tick after the page becomes visible.
+        ticker = set_interval(tick_relative_times, interval, true);
+    } else if (instances.empty() && ticker != -1) {
+        clear_timer(ticker);
+        ticker = -1;
+    }
+}
+
+void set_tick_interval(unsigned ms)
+{
+    interval = ms;
+    if (ticker != -1) {
+        clear_timer(ticker);
+        ticker = -1;
+        update_ticker();
+    }
+}
+
+unsigned tick_interval() { return interval; }
+
+void tick_relative_times()
+{
+    for (auto v : instances) {
+        if (v->text() != v->shown) {
+            mark_dirty(*v, Priority::background);
+        }
+    }
+}
+
+RelativeTime::RelativeTime(
+    double timestamp, RelativeTimeOptions opts, string id)
+    : VirtualView(id)
+    , ts(timestamp)
+    , opts(opts)
+{
+}
+
+RelativeTime::~RelativeTime()
+{
+    instances.erase(this);
+    update_ticker();
+}
+
+string RelativeTime::text() const
+{
+    const double diff = ts - now();
+    if (-diff > opts.absolute_after) {
+        return intl::format_date(ts * 1000, opts.date);
+    }
+
+    // Largest unit, that fits into the difference
+    static const std::pair<const char*, double> units[] = {
+        { "year", 365 * 24 * 60 * 60 },
+        { "month", 30 * 24 * 60 * 60 },
+        { "week", 7 * 24 * 60 * 60 },
+        { "day", 24 * 60 * 60 },
+        { "hour", 60 * 60 },
+        { "minute", 60 },
+    };
+    const double abs = std::fabs(diff);
+    for (auto & [ unit, secs ] : units) {
+        if (abs >= secs) {
+            return intl::format_relative(
+                std::trunc(diff / secs), unit, !opts.phrases);
+        }
+    }
+    return intl::format_relative(std::trunc(diff), "second", !opts.phrases);
+}
+
+Node RelativeTime::render()
+{
+    shown = text();
+    const string iso = val::global("Date")
+                           .new_(ts * 1000)
+                           .call<string>("toISOString");
+    return Node("time",
+        Attrs::of({ Attr::custom("datetime", iso),
+            Attr::title(intl::format_date(ts * 1000, opts.date)) }),
+        shown, true);
+}
+
+void RelativeTime::set_timestamp(double timestamp)
+{
+    ts = timestamp;
+    if (is_mounted()) {
+        patch();
+    }
+}
+
+void RelativeTime::on_mount()
+{
+    instances.insert(this);
+    update_ticker();
+}
+
+void RelativeTime::on_unmount()
+{
+    instances.erase(this);
+    update_ticker();
+}
+}
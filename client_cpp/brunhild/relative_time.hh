@@ -0,0 +1,72 @@
+#pragma once
+
+#include "intl.hh"
+#include "view.hh"
+#include <string>
+
+namespace brunhild {
+
+// Options of RelativeTime
+struct RelativeTimeOptions {
+    // Display an absolute date for timestamps more than this many seconds in
+    // the past. Defaults to a week.
+    double absolute_after = 7 * 24 * 60 * 60;
+
+    // Format of absolute dates, also shown as the element's tooltip
+    intl::DateOptions date;
+
+    // Use phrases like "yesterday", where available
+    bool phrases = false;
+};
+
+// Displays a Unix timestamp relative to the current time, like
+// "5 minutes ago", inside a <time> element. All mounted instances share one
+// ticker, that marks only the instances with outdated text dirty every
+// tick_interval() milliseconds with background priority.
+class RelativeTime : public VirtualView {
+public:
+    // timestamp: Unix timestamp in seconds
+    RelativeTime(double timestamp, RelativeTimeOptions opts = {},
+        std::string id = new_id());
+
+    ~RelativeTime();
+
+    Node render();
+
+    // Returns the displayed timestamp
+    double timestamp() const { return ts; }
+
+    // Set the displayed timestamp and patch the view, if mounted
+    void set_timestamp(double timestamp);
+
+    // Returns the displayed text for the current time
+    std::string text() const;
+
+protected:
+    // Register with the shared ticker
+    void on_mount();
+
+    // Unregister from the shared ticker
+    void on_unmount();
+
+private:
+    double ts;
+    const RelativeTimeOptions opts;
+
+    // Text of the last render
+    std::string shown;
+
+    friend void tick_relative_times();
+};
+
+// Set the interval of the shared RelativeTime ticker in milliseconds.
+// Defaults to 30000.
+void set_tick_interval(unsigned ms);
+
+// Returns the interval of the shared RelativeTime ticker in milliseconds
+unsigned tick_interval();
+
+// Mark all mounted RelativeTime views with outdated text dirty. Called by the
+// shared ticker.
+void tick_relative_times();
+}
@@ -0,0 +1,99 @@
+#include "resize.hh"
+#include "mutations.hh"
+#include "view.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All active observers by ID
+static std::unordered_map<long, Resize*> observers;
+
+Resize::Resize(string id, Handler handler)
+    : id(id)
+    , observer_id(id_counter++)
+    , handler(handler)
+{
+    observers[observer_id] = this;
+    if (!observe()) {
+        const long o_id = observer_id;
+        defer([o_id]() {
+            if (observers.count(o_id)) {
+                observers.at(o_id)->observe();
+            }
+        });
+    }
+}
+
+Resize::Resize(View& view, Handler handler)
+    : Resize(view.id, handler)
+{
+    view.on_next_unmount([this, alive = alive]() {
+        if (*alive) {
+            disconnect();
+        }
+    });
+}
+
+Resize::~Resize()
+{
+    *alive = false;
+    disconnect();
+}
+
+bool Resize::observe()
+{
+    return (bool)EM_ASM_INT(
+        {
+            if (!window.__bh_resize) {
+                window.__bh_resize = {};
+            }
+            var el = window.__bh_get(UTF8ToString($0));
+            if (!el || !window.ResizeObserver) {
+                return 0;
+            }
+            var id = $1;
+            var ro = new ResizeObserver(function(entries) {
+                var r = entries[entries.length - 1].contentRect;
+                Module._bh_resize(id, r.width, r.height);
+            });
+            ro.observe(el);
+            window.__bh_resize[id] = ro;
+            return 1;
+        },
+        id.c_str(), observer_id);
+}
+
+void Resize::disconnect()
+{
+    if (!observers.erase(observer_id)) {
+        return;
+    }
+    EM_ASM_INT(
+        {
+            var s = window.__bh_resize;
+            if (s && s[$0]) {
+                s[$0].disconnect();
+                delete s[$0];
+            }
+        },
+        observer_id);
+}
+
+void run_resize_handler(long id, double width, double height)
+{
+    if (observers.count(id)) {
+        observers.at(id)->handler({ width, height });
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_resize)
+{
+    emscripten::function("_bh_resize", &run_resize_handler);
+}
+}
@@ -0,0 +1,50 @@
+#pragma once
+
+#include "ref.hh"
+#include <functional>
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+class View;
+
+// Observes content box size changes of an element with ResizeObserver.
+// If the element does not exist yet, observation starts after the next flush
+// of DOM mutations. Disconnects on destruction.
+class Resize {
+public:
+    // ID of observed element
+    const std::string id;
+
+    // Receives the new content box size
+    typedef std::function<void(Size)> Handler;
+
+    // Observe an element by ID
+    Resize(std::string id, Handler handler);
+
+    // Observe the root element of a view. Also disconnects, when the view is
+    // unmounted.
+    Resize(View& view, Handler handler);
+
+    ~Resize();
+
+    Resize(const Resize&) = delete;
+    Resize& operator=(const Resize&) = delete;
+
+    // Stop observing
+    void disconnect();
+
+private:
+    const long observer_id;
+    const Handler handler;
+
+    // Guards the view's unmount hook against running after destruction
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Start observing. Returns false, if the element does not exist.
+    bool observe();
+
+    friend void run_resize_handler(long, double, double);
+};
+}
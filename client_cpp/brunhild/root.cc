@@ -0,0 +1,36 @@
+#include "root.hh"
+#include "mutations.hh"
+
+namespace brunhild {
+
+void Root::release()
+{
+    if (!view) {
+        return;
+    }
+    view->remove();
+
+    // Keep the view alive, until its deferred functions have run
+    defer([v = std::move(view)]() {});
+}
+
+void Root::replace_root(std::shared_ptr<View> v)
+{
+    release();
+    view = v;
+    if (view) {
+        set_inner_html(container, view->html());
+    } else {
+        set_inner_html(container, "");
+    }
+}
+
+void Root::clear() { replace_root(nullptr); }
+
+void Root::patch()
+{
+    if (view && view->is_mounted()) {
+        view->patch();
+    }
+}
+}
@@ -0,0 +1,49 @@
+#pragma once
+
+#include "view.hh"
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// Mounts a view of any type as the only content of a container element and
+// allows swapping it for a different view, like when routing between pages.
+class Root {
+public:
+    // ID of the container element
+    const std::string container;
+
+    Root(std::string container)
+        : container(container)
+    {
+    }
+
+    // Removes the mounted view, if any
+    ~Root() { clear(); }
+
+    Root(const Root&) = delete;
+    Root& operator=(const Root&) = delete;
+
+    // Replace the container's contents with the view. Any previously mounted
+    // view is unmounted and released first.
+    void replace_root(std::shared_ptr<View> view);
+
+    // Unmount and release the mounted view, if any, and empty the container
+    void clear();
+
+    // Patch the mounted view, if any
+    void patch();
+
+    // Returns the mounted view or NULL
+    View* get() const { return view.get(); }
+
+    // Returns the mounted view, if it is of type V, or NULL
+    template <class V> V* get_as() const { return dynamic_cast<V*>(get()); }
+
+private:
+    std::shared_ptr<View> view;
+
+    // Unmount and release the mounted view, if any
+    void release();
+};
+}
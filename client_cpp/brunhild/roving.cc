@@ -0,0 +1,139 @@
+#include "roving.hh"
+#include "focus.hh"
+#include "mutations.hh"
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+RovingTabindex::RovingTabindex(View& container, size_t columns, bool wrap)
+    : columns(columns ? columns : 1)
+    , wrap(wrap)
+{
+    // Not passive, as arrow key navigation prevents page scrolling
+    ListenerOptions active;
+    active.passive = false;
+    container.on("keydown", "", [this](val& e) { on_keydown(e); }, active);
+    container.on("focusin", "", [this](val& e) { on_focusin(e); });
+}
+
+void RovingTabindex::set_items(std::vector<View*> items)
+{
+    this->items = std::move(items);
+    if (current >= this->items.size()) {
+        current = this->items.size() ? this->items.size() - 1 : 0;
+    }
+}
+
+const char* RovingTabindex::tabindex(const View& item) const
+{
+    if (current < items.size() && items[current] == &item) {
+        return "0";
+    }
+    return "-1";
+}
+
+void RovingTabindex::set_active(size_t i, bool focus)
+{
+    if (i >= items.size()) {
+        return;
+    }
+    if (i != current) {
+        const auto prev = current;
+        current = i;
+        for (auto j : { prev, current }) {
+            if (j < items.size() && items[j]->is_mounted()) {
+                items[j]->patch();
+            }
+        }
+        if (change_cb) {
+            change_cb(current);
+        }
+    }
+    if (focus) {
+        defer([id = items[i]->id]() { brunhild::focus(id); });
+    }
+}
+
+long RovingTabindex::find_target(val& event) const
+{
+    for (auto el = event["target"]; !el.isNull() && !el.isUndefined();
+         el = el["parentElement"]) {
+        const auto id = el["id"].as<string>();
+        if (id == "") {
+            continue;
+        }
+        for (size_t i = 0; i < items.size(); i++) {
+            if (items[i]->id == id) {
+                return i;
+            }
+        }
+    }
+    return -1;
+}
+
+void RovingTabindex::on_keydown(val& event)
+{
+    if (items.empty() || event["altKey"].as<bool>()
+        || event["ctrlKey"].as<bool>() || event["metaKey"].as<bool>()) {
+        return;
+    }
+    const long from = find_target(event);
+    if (from < 0) {
+        return;
+    }
+
+    // Only navigate from the item root, not from inputs inside items
+    if (event["target"]["id"].as<string>() != items[from]->id) {
+        return;
+    }
+
+    const long n = items.size();
+    long step = 0, to;
+    const auto key = event["key"].as<string>();
+    if (key == "ArrowRight") {
+        step = 1;
+    } else if (key == "ArrowLeft") {
+        step = -1;
+    } else if (key == "ArrowDown") {
+        step = columns;
+    } else if (key == "ArrowUp") {
+        step = -long(columns);
+    }
+    if (step) {
+        to = from + step;
+        if (to < 0 || to >= n) {
+            if (!wrap) {
+                to = to < 0 ? 0 : n - 1;
+            } else if (columns == 1) {
+                to = (to + n) % n;
+            } else {
+                // Keep the column, when wrapping vertically
+                to = to < 0 ? from + (n - 1 - from) / columns * columns
+                            : from % columns;
+                if (step == 1 || step == -1) {
+                    to = step == 1 ? 0 : n - 1;
+                }
+            }
+        }
+    } else if (key == "Home") {
+        to = 0;
+    } else if (key == "End") {
+        to = n - 1;
+    } else {
+        return;
+    }
+
+    event.call<void>("preventDefault");
+    set_active(to, true);
+}
+
+void RovingTabindex::on_focusin(val& event)
+{
+    const long i = find_target(event);
+    if (i >= 0) {
+        set_active(i);
+    }
+}
+}
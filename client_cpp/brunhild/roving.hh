@@ -0,0 +1,63 @@
+#pragma once
+
+#include "view.hh"
+#include <functional>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Arrow key navigation with a single tab stop across a list or grid of child
+// views. Only the root element of the active item is reachable with Tab, all
+// others have tabindex="-1". Items must set their tabindex from tabindex() in
+// their render(), so changes are applied through the differ.
+//
+// Arrow keys move between items, Home and End to the first and last item. In a
+// list, all arrow keys move to the previous or next item. In a grid, Up and
+// Down move by a row. Focusing an item, like by clicking it, makes it active.
+//
+// Store as a member of the container view.
+class RovingTabindex {
+public:
+    // Receives the new active index
+    typedef std::function<void(size_t)> OnChange;
+
+    // Handle key and focus events inside the container.
+    // columns: number of columns of a grid. 1 for a list.
+    // wrap: move from the last to the first item and vice versa
+    RovingTabindex(View& container, size_t columns = 1, bool wrap = true);
+
+    // Set the navigated items in order. Clamps the active index to the new
+    // item count. Items must stay valid, until replaced.
+    void set_items(std::vector<View*> items);
+
+    // Returns the tabindex attribute value of an item
+    const char* tabindex(const View& item) const;
+
+    // Returns the index of the active item
+    size_t active() const { return current; }
+
+    // Set the active item and patch the previous and new active items. If
+    // focus is set, the item is focused after the next flush of DOM
+    // mutations.
+    void set_active(size_t i, bool focus = false);
+
+    // Set the number of grid columns
+    void set_columns(size_t n) { columns = n ? n : 1; }
+
+    // Called, when the active item changes
+    void on_change(OnChange fn) { change_cb = fn; }
+
+private:
+    size_t columns, current = 0;
+    const bool wrap;
+    std::vector<View*> items;
+    OnChange change_cb;
+
+    // Returns the index of the item containing the event target or -1
+    long find_target(emscripten::val& event) const;
+
+    void on_keydown(emscripten::val& event);
+    void on_focusin(emscripten::val& event);
+};
+}
@@ -0,0 +1,80 @@
+#include "runtime.hh"
+#include <algorithm>
+
+using std::string;
+
+namespace brunhild {
+
+Runtime& runtime()
+{
+    static Runtime r;
+    return r;
+}
+
+Root& Runtime::add(const string& name, string container, int order)
+{
+    remove(name);
+    auto& e = roots[name];
+    e.order = order;
+    e.seq = seq_counter++;
+    e.root = std::make_unique<Root>(container);
+    return *e.root;
+}
+
+Root* Runtime::get(const string& name)
+{
+    auto it = roots.find(name);
+    return it == roots.end() ? nullptr : it->second.root.get();
+}
+
+void Runtime::remove(const string& name) { roots.erase(name); }
+
+void Runtime::schedule(const string& name)
+{
+    auto it = roots.find(name);
+    if (it != roots.end()) {
+        it->second.scheduled = true;
+    }
+}
+
+void Runtime::schedule_all()
+{
+    for (auto & [ _, e ] : roots) {
+        e.scheduled = true;
+    }
+}
+
+void Runtime::flush()
+{
+    std::vector<Entry*> due;
+    for (auto & [ _, e ] : roots) {
+        if (e.scheduled) {
+            due.push_back(&e);
+        }
+    }
+    if (due.empty()) {
+        return;
+    }
+    std::sort(due.begin(), due.end(), [](Entry* a, Entry* b) {
+        return a->order != b->order ? a->order < b->order : a->seq < b->seq;
+    });
+
+    // Patching might schedule, add or remove roots, so look each up again
+    std::vector<long> seqs;
+    seqs.reserve(due.size());
+    for (auto e : due) {
+        seqs.push_back(e->seq);
+    }
+    for (auto seq : seqs) {
+        for (auto & [ _, e ] : roots) {
+            if (e.seq == seq) {
+                if (e.scheduled) {
+                    e.scheduled = false;
+                    e.root->patch();
+                }
+                break;
+            }
+        }
+    }
+}
+}
@@ -0,0 +1,56 @@
+#pragma once
+
+#include "root.hh"
+#include <map>
+#include <memory>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+// Owns the independent regions of an application, like the banner, thread,
+// side panel and modals, each mounted with its own Root. Roots scheduled for
+// patching are patched once on the next flush in ascending order, so the DOM
+// mutations of all regions are applied in the same frame in a defined order.
+class Runtime {
+public:
+    Runtime() = default;
+    Runtime(const Runtime&) = delete;
+    Runtime& operator=(const Runtime&) = delete;
+
+    // Create a root for the container element under a unique name. Roots are
+    // patched in ascending order, then in order of creation. Replaces any
+    // root with the same name.
+    Root& add(const std::string& name, std::string container, int order = 0);
+
+    // Returns a root by name or NULL
+    Root* get(const std::string& name);
+
+    // Clear and destroy a root by name
+    void remove(const std::string& name);
+
+    // Schedule a root to be patched on the next flush
+    void schedule(const std::string& name);
+
+    // Schedule all roots to be patched on the next flush
+    void schedule_all();
+
+    // Patch all scheduled roots. Called automatically on flush for the
+    // global runtime.
+    void flush();
+
+private:
+    struct Entry {
+        int order;
+        long seq; // Creation order
+        std::unique_ptr<Root> root;
+        bool scheduled = false;
+    };
+
+    long seq_counter = 0;
+    std::map<std::string, Entry> roots;
+};
+
+// Returns the global runtime
+Runtime& runtime();
+}
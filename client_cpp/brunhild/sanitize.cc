@@ -0,0 +1,154 @@
+#include "sanitize.hh"
+#include <emscripten.h>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+const SanitizePolicy& default_sanitize_policy()
+{
+    static const SanitizePolicy p = {
+        {
+            "a", "abbr", "b", "blockquote", "br", "caption", "code", "del",
+            "div", "em", "figcaption", "figure", "h1", "h2", "h3", "h4", "h5",
+            "h6", "hr", "i", "img", "li", "ol", "p", "pre", "q", "s", "small",
+            "span", "strong", "sub", "sup", "table", "tbody", "td", "tfoot",
+            "th", "thead", "tr", "u", "ul",
+        },
+        {
+            "script", "style", "template", "iframe", "frame", "frameset",
+            "object", "embed", "applet", "noscript", "svg", "math", "form",
+            "input", "button", "select", "textarea", "link", "meta", "base",
+        },
+        {
+            { "*", { "title", "lang", "dir" } },
+            { "a", { "href" } },
+            { "img", { "src", "alt", "width", "height" } },
+            { "td", { "colspan", "rowspan" } },
+            { "th", { "colspan", "rowspan", "scope" } },
+            { "ol", { "start", "reversed" } },
+        },
+        { "http", "https", "mailto" },
+    };
+    return p;
+}
+
+// Convert a set of strings to a JS object with the strings as keys
+static val to_set(const std::unordered_set<string>& set)
+{
+    auto o = val::object();
+    for (auto& s : set) {
+        o.set(s, true);
+    }
+    return o;
+}
+
+// Returns the JS sanitizer, installing it, if needed
+static val sanitizer()
+{
+    EM_ASM({
+        if (window.__bh_sanitize) {
+            return;
+        }
+
+        var has = function(set, key)
+        {
+            return Object.prototype.hasOwnProperty.call(set, key);
+        };
+
+        // Returns, if a URL is relative or has an allowed scheme
+        var allowedURL = function(url, schemes)
+        {
+            // Strip whitespace and control characters browsers ignore in
+            // schemes
+            var s = "";
+            for (var i = 0; i < url.length; i++) {
+                var c = url.charCodeAt(i);
+                if (c > 32 && c != 127) {
+                    s += url[i];
+                }
+            }
+            var m = /^([a-zA-Z][a-zA-Z0-9+.-]*):/.exec(s);
+            return !m || has(schemes, m[1].toLowerCase());
+        };
+
+        var clean = function(parent, p)
+        {
+            var node = parent.firstChild;
+            while (node) {
+                var next = node.nextSibling;
+                switch (node.nodeType) {
+                case Node.ELEMENT_NODE:
+                    var tag = node.localName;
+                    if (has(p.drop_tags, tag)) {
+                        parent.removeChild(node);
+                    } else if (!has(p.tags, tag)) {
+                        // Replace with children and clean those next
+                        next = node.firstChild || next;
+                        while (node.firstChild) {
+                            parent.insertBefore(node.firstChild, node);
+                        }
+                        parent.removeChild(node);
+                    } else {
+                        var allowed = p.attrs[tag] || {};
+                        var global = p.attrs["*"] || {};
+                        var attrs
+                            = Array.prototype.slice.call(node.attributes);
+                        for (var i = 0; i < attrs.length; i++) {
+                            var name = attrs[i].name;
+                            var ok = has(allowed, name) || has(global, name);
+                            if (ok && (name == "href" || name == "src")) {
+                                ok = allowedURL(attrs[i].value, p.schemes);
+                            }
+                            if (!ok) {
+                                node.removeAttribute(name);
+                            }
+                        }
+                        clean(node, p);
+                    }
+                    break;
+                case Node.TEXT_NODE:
+                    break;
+                default:
+                    parent.removeChild(node);
+                }
+                node = next;
+            }
+        };
+
+        window.__bh_sanitize = function(html, p)
+        {
+            var t = document.createElement("template");
+            t.innerHTML = html;
+            clean(t.content, p);
+            t.content.normalize();
+            var div = document.createElement("div");
+            div.appendChild(t.content);
+            return div.innerHTML;
+        };
+    });
+    return val::global("__bh_sanitize");
+}
+
+string sanitize_html(const string& html, const SanitizePolicy& policy)
+{
+    auto p = val::object();
+    p.set("tags", to_set(policy.tags));
+    p.set("drop_tags", to_set(policy.drop_tags));
+    auto attrs = val::object();
+    for (auto & [ tag, set ] : policy.attrs) {
+        attrs.set(tag, to_set(set));
+    }
+    p.set("attrs", attrs);
+    p.set("schemes", to_set(policy.schemes));
+    return sanitizer()(html, p).as<string>();
+}
+
+Node sanitized_html(
+    string tag, Attrs attrs, const string& html, const SanitizePolicy& policy)
+{
+    return Node(tag, attrs, sanitize_html(html, policy));
+}
+}
@@ -0,0 +1,41 @@
+#pragma once
+
+#include "node.hh"
+#include <string>
+#include <unordered_map>
+#include <unordered_set>
+
+namespace brunhild {
+
+// Allowlist of HTML permitted by sanitize_html()
+struct SanitizePolicy {
+    // Allowed element tags. Other elements are replaced by their children.
+    std::unordered_set<std::string> tags;
+
+    // Elements removed together with their children, instead of being
+    // replaced by them
+    std::unordered_set<std::string> drop_tags;
+
+    // Allowed attributes by element tag. Attributes under "*" are allowed on
+    // all elements.
+    std::unordered_map<std::string, std::unordered_set<std::string>> attrs;
+
+    // Allowed URL schemes of href and src attributes, like "https". Relative
+    // URLs are always allowed.
+    std::unordered_set<std::string> schemes;
+};
+
+// Returns a policy allowing basic text formatting, links, images and tables
+const SanitizePolicy& default_sanitize_policy();
+
+// Sanitize HTML from an untrusted source, removing all elements, attributes
+// and URLs not allowed by policy, as well as comments. Parsing is inert, so
+// no scripts are run and no resources loaded.
+std::string sanitize_html(const std::string& html,
+    const SanitizePolicy& policy = default_sanitize_policy());
+
+// Creates a Node with sanitized html as the inner contents. Safe alternative
+// to setting Node::inner_html to HTML from an untrusted source, like embeds.
+Node sanitized_html(std::string tag, Attrs attrs, const std::string& html,
+    const SanitizePolicy& policy = default_sanitize_policy());
+}
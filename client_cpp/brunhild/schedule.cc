@@ -0,0 +1,149 @@
+#include "schedule.hh"
+#include "view.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+#include <unordered_set>
+#include <vector>
+
+using std::string;
+
+namespace brunhild {
+
+// IDs of views to patch before DOM mutations are applied in this frame
+static std::unordered_set<string> user_input;
+
+// IDs of views to patch on the next flush
+static std::unordered_set<string> dirty;
+
+// IDs of views to patch during idle time by time of marking
+static std::unordered_map<string, double> background;
+
+static unsigned starvation_timeout = 1000;
+
+static bool idle_scheduled = false;
+
+// Patch a view by ID, if still mounted
+static void patch_view(const string& id)
+{
+    auto& views = rendered_views();
+    auto it = views.find(id);
+    if (it != views.end() && it->second->is_mounted()) {
+        it->second->patch();
+    }
+}
+
+// Request an idle callback to patch background updates
+static void schedule_idle()
+{
+    if (idle_scheduled) {
+        return;
+    }
+    idle_scheduled = true;
+    EM_ASM({
+        var run = function(deadline)
+        {
+            Module._bh_patch_idle(
+                deadline ? deadline.timeRemaining() : 10);
+        };
+        if (window.requestIdleCallback) {
+            requestIdleCallback(run);
+        } else {
+            setTimeout(run, 50);
+        }
+    });
+}
+
+// Patch background updates, while time_remaining milliseconds of idle time
+// are left
+static void patch_idle(double time_remaining)
+{
+    idle_scheduled = false;
+    const double end = emscripten_get_now() + time_remaining;
+    while (background.size() && emscripten_get_now() < end - 1) {
+        auto it = background.begin();
+        const auto id = it->first;
+        background.erase(it);
+        patch_view(id);
+    }
+    if (background.size()) {
+        schedule_idle();
+    }
+}
+
+void mark_dirty(const View& view, Priority priority)
+{
+    mark_dirty(view.id, priority);
+}
+
+void mark_dirty(const string& id, Priority priority)
+{
+    switch (priority) {
+    case Priority::user_input:
+        background.erase(id);
+        dirty.erase(id);
+        user_input.insert(id);
+        break;
+    case Priority::normal:
+        background.erase(id);
+        if (!user_input.count(id)) {
+            dirty.insert(id);
+        }
+        break;
+    case Priority::background:
+        if (!user_input.count(id) && !dirty.count(id)
+            && !background.count(id)) {
+            background[id] = emscripten_get_now();
+            schedule_idle();
+        }
+        break;
+    }
+}
+
+void set_starvation_timeout(unsigned ms) { starvation_timeout = ms; }
+
+void patch_dirty()
+{
+    // Promote background updates, that have waited for too long
+    if (background.size()) {
+        const double now = emscripten_get_now();
+        for (auto it = background.begin(); it != background.end();) {
+            if (now - it->second >= starvation_timeout) {
+                dirty.insert(it->first);
+                it = background.erase(it);
+            } else {
+                ++it;
+            }
+        }
+    }
+
+    patch_user_input();
+    if (dirty.empty()) {
+        return;
+    }
+
+    // Patching might mark more views dirty for the next flush
+    auto ids = std::move(dirty);
+    dirty.clear();
+    for (auto& id : ids) {
+        patch_view(id);
+    }
+}
+
+void patch_user_input()
+{
+    // Patching might mark more views dirty with user input priority
+    while (user_input.size()) {
+        auto ids = std::move(user_input);
+        user_input.clear();
+        for (auto& id : ids) {
+            patch_view(id);
+        }
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_schedule)
+{
+    emscripten::function("_bh_patch_idle", &patch_idle);
+}
+}
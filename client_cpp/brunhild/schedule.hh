@@ -0,0 +1,46 @@
+#pragma once
+
+#include <string>
+
+// Scheduling of view patches by priority. Views marked dirty are patched
+// once, no matter how many times they were marked, at a time depending on the
+// highest priority they were marked with.
+namespace brunhild {
+
+class View;
+
+// Priority of a view update
+enum class Priority {
+    // Updates in direct response to user input. Patched right before the DOM
+    // mutations of the current frame are applied, even if marked by frame
+    // hooks, tweens or deferred functions during the flush.
+    user_input,
+
+    // Patched at the start of the next flush
+    normal,
+
+    // Updates not visible to the user right away, like offscreen content or
+    // counters. Patched, when the browser is idle, or on the first flush
+    // after having waited for the starvation timeout.
+    background,
+};
+
+// Mark a view dirty to be patched once, if still mounted
+void mark_dirty(const View& view, Priority priority = Priority::normal);
+
+// Mark a view dirty by ID
+void mark_dirty(const std::string& id, Priority priority = Priority::normal);
+
+// Set the maximum time in milliseconds background updates wait for the
+// browser to become idle. Defaults to 1000.
+void set_starvation_timeout(unsigned ms);
+
+// Patch all views marked dirty with user input or normal priority and
+// background updates, that have waited for longer than the starvation timeout.
+// Called automatically on flush.
+void patch_dirty();
+
+// Patch all views marked dirty with user input priority. Called automatically
+// on flush before applying DOM mutations.
+void patch_user_input();
+}
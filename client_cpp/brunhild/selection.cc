@@ -0,0 +1,137 @@
+#include "selection.hh"
+#include <emscripten.h>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+// Install helpers for converting between DOM positions and text offsets
+static void init()
+{
+    EM_ASM({
+        if (window.__bh_selection) {
+            return;
+        }
+        window.__bh_selection = {
+            // Returns the ID and text offset of a DOM position
+            offset : function(node, off)
+            {
+                var el = node.nodeType == 1 ? node : node.parentElement;
+                while (el && !el.id) {
+                    el = el.parentElement;
+                }
+                if (!el) {
+                    return null;
+                }
+                var r = document.createRange();
+                r.selectNodeContents(el);
+                r.setEnd(node, off);
+                return { id : el.id, offset : r.toString().length };
+            },
+
+            // Returns the DOM position of a text offset into an element
+            position : function(el, target)
+            {
+                var w = document.createTreeWalker(el, NodeFilter.SHOW_TEXT);
+                var pos = 0;
+                var n;
+                while ((n = w.nextNode())) {
+                    if (pos + n.length >= target) {
+                        return [ n, target - pos ];
+                    }
+                    pos += n.length;
+                }
+                return [ el, el.childNodes.length ];
+            },
+
+            get : function()
+            {
+                var sel = window.getSelection();
+                if (!sel || !sel.rangeCount) {
+                    return null;
+                }
+                var a = this.offset(sel.anchorNode, sel.anchorOffset);
+                var f = this.offset(sel.focusNode, sel.focusOffset);
+                if (!a || !f) {
+                    return null;
+                }
+                return {
+                    text : sel.toString(),
+                    anchor_id : a.id,
+                    anchor_offset : a.offset,
+                    focus_id : f.id,
+                    focus_offset : f.offset,
+                    collapsed : sel.isCollapsed
+                };
+            },
+
+            set : function(anchor_id, anchor_off, focus_id, focus_off)
+            {
+                var a = window.__bh_get(anchor_id);
+                var f = window.__bh_get(focus_id);
+                if (!a || !f) {
+                    return;
+                }
+                var start = this.position(a, anchor_off);
+                var end = this.position(f, focus_off);
+                window.getSelection().setBaseAndExtent(
+                    start[0], start[1], end[0], end[1]);
+            }
+        };
+    });
+}
+
+std::optional<Selection> get_selection()
+{
+    init();
+    auto s = val::global("__bh_selection").call<val>("get");
+    if (s.isNull()) {
+        return std::nullopt;
+    }
+    return Selection{
+        s["text"].as<string>(),
+        s["anchor_id"].as<string>(),
+        s["focus_id"].as<string>(),
+        s["anchor_offset"].as<unsigned>(),
+        s["focus_offset"].as<unsigned>(),
+        s["collapsed"].as<bool>(),
+    };
+}
+
+string selected_text()
+{
+    return val::global("getSelection")()
+        .call<val>("toString")
+        .as<string>();
+}
+
+void select(const string& anchor_id, unsigned anchor_offset,
+    const string& focus_id, unsigned focus_offset)
+{
+    init();
+    val::global("__bh_selection")
+        .call<void>("set", anchor_id, anchor_offset, focus_id, focus_offset);
+}
+
+void select_contents(const string& id)
+{
+    EM_ASM_INT(
+        {
+            var el = window.__bh_get(UTF8ToString($0));
+            if (!el) {
+                return;
+            }
+            var sel = window.getSelection();
+            sel.removeAllRanges();
+            sel.selectAllChildren(el);
+        },
+        id.c_str());
+}
+
+void clear_selection()
+{
+    EM_ASM({ window.getSelection().removeAllRanges(); });
+}
+}
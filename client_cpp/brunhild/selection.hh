@@ -0,0 +1,48 @@
+#pragma once
+
+#include <optional>
+#include <string>
+
+namespace brunhild {
+
+// Current text selection of the document. Endpoints are described as offsets
+// into the text content of the closest ancestor element with an ID.
+struct Selection {
+    // Selected text
+    std::string text;
+
+    // IDs of the elements containing the start and end of the selection.
+    // Can be reversed, if the user selected backwards.
+    std::string anchor_id, focus_id;
+
+    // Offsets of the endpoints into the text content of their elements
+    unsigned anchor_offset, focus_offset;
+
+    // Selection is only a caret
+    bool collapsed;
+};
+
+// Returns the current selection or none, if there is no selection or the
+// endpoints are not contained in any element with an ID
+std::optional<Selection> get_selection();
+
+// Returns the currently selected text or an empty string
+std::string selected_text();
+
+// Select text between offsets into the text content of two elements.
+// No-op, if either element does not exist.
+void select(const std::string& anchor_id, unsigned anchor_offset,
+    const std::string& focus_id, unsigned focus_offset);
+
+// Select text between offsets into the text content of an element
+inline void select(const std::string& id, unsigned start, unsigned end)
+{
+    select(id, start, id, end);
+}
+
+// Select the entire contents of an element
+void select_contents(const std::string& id);
+
+// Remove any selection from the document
+void clear_selection();
+}
@@ -0,0 +1,237 @@
+#include "service_worker.hh"
+#include "mutations.hh"
+#include "promise.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::service_worker {
+
+static long id_counter = 0;
+
+// Registered message handlers by ID
+static std::map<long, std::function<void(val&)>> handlers;
+
+static std::function<void()> update_handler;
+
+// Returns the JS service worker helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_sw) {
+            return;
+        }
+        var c = navigator.serviceWorker;
+        var reg = null;
+
+        // Report updated workers, that are installed, while another worker
+        // controls the page
+        var watch = function(r)
+        {
+            reg = r;
+            var track = function(w)
+            {
+                w.addEventListener("statechange", function() {
+                    if (w.state == "installed" && c.controller) {
+                        Module._bh_sw_update_available();
+                    }
+                });
+            };
+            if (r.installing) {
+                track(r.installing);
+            }
+            r.addEventListener("updatefound", function() {
+                track(r.installing);
+            });
+        };
+
+        var reloading = false;
+        window.__bh_sw = {
+            supported : !!c,
+            register : function(url, scope)
+            {
+                if (!c) {
+                    return Promise.reject(new Error("not supported"));
+                }
+                var opts = scope ? { scope : scope } : undefined;
+                return c.register(url, opts).then(watch);
+            },
+            registration : function() { return reg; },
+            update : function()
+            {
+                return reg ? reg.update()
+                           : Promise.reject(new Error("not registered"));
+            },
+            unregister : function()
+            {
+                return reg ? reg.unregister() : Promise.resolve(false);
+            },
+            reloadOnControllerChange : function()
+            {
+                c.addEventListener("controllerchange", function() {
+                    if (!reloading) {
+                        reloading = true;
+                        location.reload();
+                    }
+                });
+            }
+        };
+        if (c) {
+            c.addEventListener("message", function(e) {
+                Module._bh_sw_message(e.data);
+            });
+        }
+    });
+    return val::global("__bh_sw");
+}
+
+static Settle on_settled(std::function<void(bool)> cb)
+{
+    return [cb](bool ok, val) {
+        if (cb) {
+            cb(ok);
+        }
+    };
+}
+
+// Returns the current registration or null
+static val registration() { return helpers().call<val>("registration"); }
+
+// Returns navigator.serviceWorker.controller or null
+static val controller()
+{
+    if (!supported()) {
+        return val::null();
+    }
+    auto c = val::global("navigator")["serviceWorker"]["controller"];
+    return c.isUndefined() ? val::null() : c;
+}
+
+bool supported() { return helpers()["supported"].as<bool>(); }
+
+void register_worker(
+    const string& url, const string& scope, std::function<void(bool)> cb)
+{
+    await(helpers().call<val>("register", url, scope), on_settled(cb));
+}
+
+void update(std::function<void(bool)> cb)
+{
+    await(helpers().call<val>("update"), on_settled(cb));
+}
+
+void unregister(std::function<void(bool)> cb)
+{
+    await(helpers().call<val>("unregister"), [cb](bool ok, val res) {
+        if (cb) {
+            cb(ok && res.as<bool>());
+        }
+    });
+}
+
+State state()
+{
+    auto r = registration();
+    if (r.isNull()) {
+        return State::none;
+    }
+    auto w = r["active"];
+    if (w.isNull()) {
+        w = r["waiting"];
+    }
+    if (w.isNull()) {
+        w = r["installing"];
+    }
+    if (w.isNull()) {
+        return State::none;
+    }
+    const auto s = w["state"].as<string>();
+    if (s == "installing") {
+        return State::installing;
+    }
+    if (s == "installed") {
+        return State::installed;
+    }
+    if (s == "activating") {
+        return State::activating;
+    }
+    if (s == "activated") {
+        return State::activated;
+    }
+    return State::redundant;
+}
+
+bool controlled() { return !controller().isNull(); }
+
+bool update_waiting()
+{
+    auto r = registration();
+    return !r.isNull() && !r["waiting"].isNull() && controlled();
+}
+
+void on_update_available(std::function<void()> handler)
+{
+    update_handler = handler;
+    if (update_waiting()) {
+        defer([]() {
+            if (update_handler && update_waiting()) {
+                update_handler();
+            }
+        });
+    }
+}
+
+void activate_update(val message)
+{
+    auto r = registration();
+    if (r.isNull() || r["waiting"].isNull()) {
+        return;
+    }
+    helpers().call<void>("reloadOnControllerChange");
+    r["waiting"].call<void>("postMessage", message);
+}
+
+bool post_message(val message)
+{
+    auto c = controller();
+    if (c.isNull()) {
+        return false;
+    }
+    c.call<void>("postMessage", message);
+    return true;
+}
+
+long on_message(std::function<void(val&)> handler)
+{
+    helpers(); // Ensure listener is attached
+    const long id = id_counter++;
+    handlers[id] = handler;
+    return id;
+}
+
+void remove_message_handler(long id) { handlers.erase(id); }
+
+static void message_received(val data)
+{
+    auto copy = handlers; // Handler might invalidate this iterator
+    for (auto & [ _, h ] : copy) {
+        h(data);
+    }
+}
+
+static void update_available()
+{
+    if (auto fn = update_handler) {
+        fn();
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_service_worker)
+{
+    emscripten::function("_bh_sw_message", &message_received);
+    emscripten::function("_bh_sw_update_available", &update_available);
+}
+}
@@ -0,0 +1,69 @@
+#pragma once
+
+#include <emscripten/val.h>
+#include <functional>
+#include <string>
+
+// Service worker registration and messaging. All functions degrade to no-ops,
+// where service workers are not supported, like on pages not served over
+// HTTPS.
+namespace brunhild::service_worker {
+
+// State of a service worker. Mirrors ServiceWorker.state.
+enum class State {
+    // No worker
+    none,
+    installing,
+    installed,
+    activating,
+    activated,
+    redundant,
+};
+
+// Returns, if service workers are supported
+bool supported();
+
+// Register the service worker script at url with an optional scope. cb is
+// optional and receives, if registration succeeded.
+void register_worker(const std::string& url, const std::string& scope = "",
+    std::function<void(bool)> cb = nullptr);
+
+// Check the server for an updated worker script. cb is optional and receives,
+// if the check succeeded.
+void update(std::function<void(bool)> cb = nullptr);
+
+// Unregister the worker. cb is optional and receives, if a worker was
+// unregistered.
+void unregister(std::function<void(bool)> cb = nullptr);
+
+// Returns the state of the active worker
+State state();
+
+// Returns, if the page is controlled by a service worker
+bool controlled();
+
+// Returns, if an updated worker is installed and waiting to activate
+bool update_waiting();
+
+// Register a handler called once an updated worker has been installed and is
+// waiting for the current one to be released. Called immediately after the
+// next flush of DOM mutations, if one is already waiting. Replaces any
+// previous handler.
+void on_update_available(std::function<void()> handler);
+
+// Post message to the waiting worker, which is expected to call
+// self.skipWaiting() on receipt, and reload the page, once it has taken
+// control.
+void activate_update(emscripten::val message);
+
+// Post message to the worker controlling the page. Returns false, if the page
+// is not controlled.
+bool post_message(emscripten::val message);
+
+// Register a handler receiving the data of messages posted by the worker.
+// Returns handler ID.
+long on_message(std::function<void(emscripten::val&)> handler);
+
+// Remove a message handler by ID
+void remove_message_handler(long id);
+}
@@ -0,0 +1,65 @@
+#include "shadow.hh"
+#include "debug.hh"
+#include <emscripten.h>
+
+namespace brunhild {
+
+ShadowView::ShadowView(
+    std::shared_ptr<View> child, std::string css, std::string tag, std::string id)
+    : View(id)
+    , tag(tag)
+    , child(child)
+    , css(css)
+{
+}
+
+void ShadowView::write_html(Rope& s)
+{
+    if (!is_initialized) {
+        is_initialized = true;
+        schedule_mount();
+    }
+    s << '<' << tag << " id=\"" << id << '"';
+    debug::write_type_attr(s, typeid(*this));
+    s << "></" << tag << '>';
+}
+
+void ShadowView::mount()
+{
+    Rope s;
+    if (css != "") {
+        s << "<style>" << css << "</style>";
+    }
+    child->write_html(s);
+
+    EM_ASM_INT(
+        {
+            var id = UTF8ToString($0);
+            var host = window.__bh_get(id);
+            if (!host) {
+                return;
+            }
+            var root = host.shadowRoot || host.attachShadow({ mode : 'open' });
+            root.innerHTML = UTF8ToString($1);
+            window.__bh_shadow_roots[id] = root;
+        },
+        id.c_str(), s.str().c_str());
+
+    View::mount();
+}
+
+void ShadowView::unmount()
+{
+    unmount_child(*child);
+    EM_ASM_INT({ delete window.__bh_shadow_roots[UTF8ToString($0)]; },
+        id.c_str());
+    View::unmount();
+}
+
+void ShadowView::patch()
+{
+    if (is_mounted()) {
+        child->patch();
+    }
+}
+}
@@ -0,0 +1,42 @@
+#pragma once
+
+#include "view.hh"
+#include <memory>
+#include <string>
+
+namespace brunhild {
+
+// Renders a child view into a shadow root attached to the root element of this
+// view. The child's subtree neither inherits nor leaks page CSS. Styles for the
+// subtree must be passed as the css argument.
+// Elements inside the shadow root are still patchable by ID through the
+// functions in mutations.hh.
+class ShadowView : public View {
+public:
+    // Tag of root node
+    const std::string tag;
+
+    // Creates a new shadow root host for a child view.
+    // css: stylesheet scoped to the shadow root
+    ShadowView(std::shared_ptr<View> child, std::string css = "",
+        std::string tag = "div", std::string id = new_id());
+
+    // Writes only the host element. The subtree is inserted into the shadow
+    // root on mount.
+    void write_html(Rope&);
+
+    // Patch the child view
+    void patch();
+
+protected:
+    // Attach the shadow root and insert the child's subtree
+    void mount();
+
+    void unmount();
+
+private:
+    std::shared_ptr<View> child;
+    const std::string css;
+    bool is_initialized = false;
+};
+}
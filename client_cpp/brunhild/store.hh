@@ -0,0 +1,186 @@
+#pragma once
+
+#include "bus.hh"
+#include "view.hh"
+#include <functional>
+#include <map>
+#include <memory>
+#include <optional>
+#include <string>
+#include <type_traits>
+#include <unordered_map>
+
+namespace brunhild {
+
+// Container of application state of type S. All writes go through the store,
+// which notifies subscribers after each write.
+template <class S> class Store {
+public:
+    // Called after each write with the new state and the name of the action,
+    // that wrote it
+    typedef std::function<void(const S&, const std::string& action)> Listener;
+
+    Store(S state = S())
+        : state(std::move(state))
+    {
+    }
+
+    Store(const Store&) = delete;
+    Store& operator=(const Store&) = delete;
+
+    // Returns the current state
+    const S& get() const { return state; }
+
+    // Modify the state with fn and notify subscribers. action optionally names
+    // the write for debugging.
+    void update(std::function<void(S&)> fn, const std::string& action = "")
+    {
+        fn(state);
+        notify(action);
+    }
+
+    // Replace the entire state and notify subscribers
+    void replace(S s, const std::string& action = "")
+    {
+        state = std::move(s);
+        notify(action);
+    }
+
+    // Call fn after each write. Returns subscription ID.
+    long subscribe(Listener fn)
+    {
+        const long id = id_counter++;
+        listeners[id] = fn;
+        return id;
+    }
+
+    // Remove a subscription by ID
+    void unsubscribe(long id) { listeners.erase(id); }
+
+    // Mark a view dirty with priority on every write. Prefer
+    // Selector::watch() to only patch the view, when the state it renders
+    // changes. Returns subscription ID.
+    long watch(View& view, Priority priority = Priority::normal)
+    {
+        return subscribe(
+            [id = view.id, priority](const S&, const std::string&) {
+                mark_dirty(id, priority);
+            });
+    }
+
+private:
+    S state;
+    long id_counter = 0;
+    std::map<long, Listener> listeners;
+
+    void notify(const std::string& action)
+    {
+        // Listeners might subscribe or unsubscribe
+        auto copy = listeners;
+        for (auto & [ id, fn ] : copy) {
+            if (listeners.count(id)) {
+                fn(state, action);
+            }
+        }
+    }
+};
+
+// Memoized value derived from the state of a Store. The value is only
+// recomputed, when the input slice selected from the state changes, and
+// watching views are only patched, when the derived value changes.
+// S: store state type
+// In: input slice of the state. Must be equality comparable. Use a std::tuple
+//     to select multiple slices.
+// Out: derived value. Must be equality comparable.
+template <class S, class In, class Out> class Selector {
+public:
+    // Selects the input slice from the state
+    typedef std::function<In(const S&)> Input;
+
+    // Computes the derived value from the input slice
+    typedef std::function<Out(const In&)> Compute;
+
+    Selector(Store<S>& store, Input input, Compute compute)
+        : store(store)
+        , input(input)
+        , compute(compute)
+    {
+        sub_id = store.subscribe(
+            [this](const S&, const std::string&) { on_write(); });
+    }
+
+    ~Selector() { store.unsubscribe(sub_id); }
+
+    Selector(const Selector&) = delete;
+    Selector& operator=(const Selector&) = delete;
+
+    // Returns the derived value, recomputing it, if the input slice has
+    // changed
+    const Out& get()
+    {
+        update();
+        return *value;
+    }
+
+    // Mark a view dirty with priority, whenever the derived value changes
+    void watch(View& view, Priority priority = Priority::normal)
+    {
+        watchers[view.id] = priority;
+    }
+
+    // Stop marking a view dirty
+    void unwatch(View& view) { watchers.erase(view.id); }
+
+private:
+    Store<S>& store;
+    Input input;
+    Compute compute;
+    long sub_id;
+    std::optional<In> last_input;
+    std::optional<Out> value;
+
+    // IDs of views to mark dirty on changes
+    // Priorities of watching views by ID
+    std::unordered_map<std::string, Priority> watchers;
+
+    // Recompute the value, if the input slice has changed. Returns, if the
+    // value has changed.
+    bool update()
+    {
+        auto in = input(store.get());
+        if (last_input && *last_input == in) {
+            return false;
+        }
+        auto out = compute(in);
+        last_input = std::move(in);
+        if (value && *value == out) {
+            return false;
+        }
+        value = std::move(out);
+        return true;
+    }
+
+    void on_write()
+    {
+        // Computed lazily, if nothing is watching
+        if (watchers.empty()) {
+            return;
+        }
+        const bool initial = !value;
+        if (update() && !initial) {
+            for (auto & [ id, priority ] : watchers) {
+                mark_dirty(id, priority);
+            }
+        }
+    }
+};
+
+// Construct a Selector on the heap with deduced template arguments
+template <class S, class I, class C>
+auto make_selector(Store<S>& store, I input, C compute)
+{
+    typedef std::decay_t<decltype(input(store.get()))> In;
+    typedef std::decay_t<decltype(compute(input(store.get())))> Out;
+    return std::make_unique<Selector<S, In, Out>>(store, input, compute);
+}
+}
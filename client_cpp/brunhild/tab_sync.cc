@@ -0,0 +1,148 @@
+#include "tab_sync.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::detail {
+
+// Message handlers of open channels by ID
+static std::unordered_map<long, std::function<void(Raw)>> channels;
+
+static long id_counter = 0;
+
+// Returns the JS channel helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_tab_sync) {
+            return;
+        }
+
+        var channels = {};
+
+        // Fall back to storage events, which fire in all other tabs on
+        // writes to localStorage
+        var storage_channel = function(name, deliver)
+        {
+            var key = "__bh_sync." + name;
+            var listener = function(e)
+            {
+                if (e.key != key || !e.newValue) {
+                    return;
+                }
+                try {
+                    deliver(JSON.parse(e.newValue).slices);
+                } catch (err) {
+                    console.error(err);
+                }
+            };
+            addEventListener("storage", listener);
+            return {
+                post : function(slices)
+                {
+                    try {
+                        // Nonce ensures the value changes and the event fires
+                        localStorage.setItem(key, JSON.stringify({
+                            nonce : Math.random(),
+                            slices : slices
+                        }));
+                        localStorage.removeItem(key);
+                    } catch (err) {
+                        console.error(err);
+                    }
+                },
+                close : function()
+                {
+                    removeEventListener("storage", listener);
+                }
+            };
+        };
+
+        window.__bh_tab_sync = {
+            open : function(id, name)
+            {
+                var deliver = function(slices)
+                {
+                    Module._bh_tab_sync_receive(id, slices);
+                };
+                if (!window.BroadcastChannel) {
+                    channels[id] = storage_channel(name, deliver);
+                    return;
+                }
+                var c = new BroadcastChannel("bh:" + name);
+                c.onmessage = function(e) { deliver(e.data); };
+                channels[id] = {
+                    post : function(slices) { c.postMessage(slices); },
+                    close : function() { c.close(); }
+                };
+            },
+            post : function(id, slices)
+            {
+                if (channels[id]) {
+                    channels[id].post(slices);
+                }
+            },
+            close : function(id)
+            {
+                if (channels[id]) {
+                    channels[id].close();
+                    delete channels[id];
+                }
+            }
+        };
+    });
+    return val::global("__bh_tab_sync");
+}
+
+long tab_channel_open(const string& name, std::function<void(Raw)> on_message)
+{
+    const long id = id_counter++;
+    channels[id] = on_message;
+    helpers().call<void>("open", id, name);
+    return id;
+}
+
+void tab_channel_post(long id, const Raw& changed)
+{
+    auto slices = val::object();
+    for (auto & [ k, v ] : changed) {
+        slices.set(k, v);
+    }
+    helpers().call<void>("post", id, slices);
+}
+
+void tab_channel_close(long id)
+{
+    channels.erase(id);
+    helpers().call<void>("close", id);
+}
+
+// Receive changed slices posted by another tab
+static void receive(long id, val slices)
+{
+    auto it = channels.find(id);
+    if (it == channels.end() || slices.isNull() || slices.isUndefined()) {
+        return;
+    }
+    Raw raw;
+    auto keys = emscripten::vecFromJSArray<string>(
+        val::global("Object").call<val>("keys", slices));
+    for (auto& k : keys) {
+        auto v = slices[k];
+        if (v.isString()) {
+            raw[k] = v.as<string>();
+        }
+    }
+    auto fn = it->second; // Might close the channel
+    fn(std::move(raw));
+}
+
+EMSCRIPTEN_BINDINGS(module_tab_sync)
+{
+    emscripten::function("_bh_tab_sync_receive", &receive);
+}
+}
@@ -0,0 +1,128 @@
+#pragma once
+
+#include "persist.hh"
+#include "store.hh"
+#include <functional>
+#include <memory>
+#include <string>
+#include <vector>
+
+namespace brunhild {
+
+namespace detail {
+    // Open a channel to other tabs of the same origin. on_message receives
+    // the changed slices posted by other tabs. Returns channel ID.
+    long tab_channel_open(
+        const std::string& name, std::function<void(Raw)> on_message);
+
+    // Post changed slices to all other tabs on the channel
+    void tab_channel_post(long id, const Raw& changed);
+
+    // Close a channel by ID
+    void tab_channel_close(long id);
+}
+
+// Shares selected slices of a Store's state with other open tabs of the same
+// origin, that construct a TabSync with the same channel name. Uses a
+// BroadcastChannel, where supported, and storage events otherwise.
+//
+// Slices changed by another tab are written to the store with the "@sync"
+// action.
+template <class S> class TabSync {
+public:
+    // Shared part of the state
+    struct Slice {
+        // Unique key of the slice
+        std::string key;
+
+        // Serialize the slice from the state
+        std::function<std::string(const S&)> save;
+
+        // Deserialize the slice into the state
+        std::function<void(S&, const std::string&)> load;
+    };
+
+    // channel: name of the channel shared between tabs
+    TabSync(Store<S>& store, std::string channel, std::vector<Slice> slices)
+        : store(store)
+        , slices(slices)
+    {
+        for (auto& s : slices) {
+            synced[s.key] = s.save(store.get());
+        }
+        channel_id = detail::tab_channel_open(channel,
+            [this, alive = std::weak_ptr<bool>(alive)](detail::Raw raw) {
+                if (alive.lock()) {
+                    receive(std::move(raw));
+                }
+            });
+        sub_id = store.subscribe([this](const S&, const std::string& action) {
+            if (action != "@sync") {
+                post();
+            }
+        });
+    }
+
+    ~TabSync()
+    {
+        store.unsubscribe(sub_id);
+        detail::tab_channel_close(channel_id);
+    }
+
+    TabSync(const TabSync&) = delete;
+    TabSync& operator=(const TabSync&) = delete;
+
+private:
+    Store<S>& store;
+    const std::vector<Slice> slices;
+    long channel_id, sub_id;
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Last posted or received serialized slices
+    detail::Raw synced;
+
+    // Post all slices, that changed since they were last synced
+    void post()
+    {
+        detail::Raw changed;
+        for (auto& s : slices) {
+            auto v = s.save(store.get());
+            auto& prev = synced[s.key];
+            if (prev != v) {
+                prev = v;
+                changed[s.key] = std::move(v);
+            }
+        }
+        if (changed.size()) {
+            detail::tab_channel_post(channel_id, changed);
+        }
+    }
+
+    // Apply slices changed by another tab
+    void receive(detail::Raw raw)
+    {
+        bool changed = false;
+        for (auto& s : slices) {
+            auto it = raw.find(s.key);
+            if (it != raw.end() && synced[s.key] != it->second) {
+                changed = true;
+                break;
+            }
+        }
+        if (!changed) {
+            return;
+        }
+        store.update(
+            [&](S& state) {
+                for (auto& s : slices) {
+                    auto it = raw.find(s.key);
+                    if (it != raw.end() && synced[s.key] != it->second) {
+                        s.load(state, it->second);
+                        synced[s.key] = std::move(it->second);
+                    }
+                }
+            },
+            "@sync");
+    }
+};
+}
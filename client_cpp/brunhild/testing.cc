@@ -0,0 +1,72 @@
+#include "testing.hh"
+
+#ifdef BRUNHILD_HEADLESS
+
+#include "error.hh"
+#include "headless.hh"
+#include <fstream>
+#include <sstream>
+#include <stdlib.h>
+
+using std::string;
+
+namespace brunhild::testing {
+
+string normalize(const string& html)
+{
+    string s;
+    for (auto& el : headless::parse(html)) {
+        s += el->outer_html();
+    }
+    return s;
+}
+
+// Throw a mismatch error
+[[noreturn]] static void fail(
+    const string& location, const string& expected, const string& got)
+{
+    string msg;
+    if (location != "") {
+        msg = location + ": ";
+    }
+    msg += "rendered HTML mismatch\nexpected:\n" + expected + "\ngot:\n" + got;
+    throw Error(msg);
+}
+
+void assert_renders(HTMLWriter& w, const string& expected, const string& loc)
+{
+    const auto want = normalize(expected);
+    const auto got = normalize(w.html());
+    if (got != want) {
+        fail(loc, want, got);
+    }
+}
+
+void assert_snapshot(HTMLWriter& w, const string& path, const string& loc)
+{
+    const auto got = normalize(w.html());
+
+    std::ifstream in(path);
+    if (!in || getenv("BRUNHILD_UPDATE_SNAPSHOTS")) {
+        in.close();
+        std::ofstream out(path);
+        if (!out) {
+            throw Error("could not write snapshot: " + path);
+        }
+        out << got << '\n';
+        return;
+    }
+
+    std::stringstream buf;
+    buf << in.rdbuf();
+    auto want = buf.str();
+    if (want.size() && want.back() == '\n') {
+        want.pop_back();
+    }
+    if (got != want) {
+        fail(loc + (loc != "" ? ": " : "") + path, want, got);
+    }
+}
+}
+
+#endif
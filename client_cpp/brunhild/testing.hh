@@ -0,0 +1,46 @@
+#pragma once
+
+// Utilities for testing rendered HTML. Require BRUNHILD_HEADLESS and are only
+// built into the host-compiled tests in client_cpp/test, as snapshots are
+// read from and written to the file system.
+
+#ifdef BRUNHILD_HEADLESS
+
+#include "node.hh"
+#include <string>
+
+// Assert a view or node renders to the expected HTML. Throws brunhild::Error
+// with the source location on mismatch.
+#define BH_ASSERT_RENDERS(writer, expected)                                    \
+    brunhild::testing::assert_renders(                                         \
+        writer, expected, __FILE__ ":" BH_STRINGIFY(__LINE__))
+
+// Assert a view or node renders to the HTML stored in a snapshot file
+#define BH_ASSERT_SNAPSHOT(writer, path)                                       \
+    brunhild::testing::assert_snapshot(                                        \
+        writer, path, __FILE__ ":" BH_STRINGIFY(__LINE__))
+
+#define BH_STRINGIFY(x) BH_STRINGIFY_INNER(x)
+#define BH_STRINGIFY_INNER(x) #x
+
+namespace brunhild::testing {
+
+// Normalize HTML for comparison by parsing and reserializing it. Sorts
+// attributes, as their order is undefined in rendered HTML.
+std::string normalize(const std::string& html);
+
+// Throw brunhild::Error, if the HTML rendered by w does not match expected.
+// Both are normalized before comparison.
+// location: optional source location to include in the error message
+void assert_renders(HTMLWriter& w, const std::string& expected,
+    const std::string& location = "");
+
+// Compare HTML rendered by w against the snapshot file at path and throw
+// brunhild::Error on mismatch. If the file does not exist or the
+// BRUNHILD_UPDATE_SNAPSHOTS environment variable is set, the snapshot is
+// written instead.
+void assert_snapshot(HTMLWriter& w, const std::string& path,
+    const std::string& location = "");
+}
+
+#endif
@@ -0,0 +1,156 @@
+#pragma once
+
+#include "store.hh"
+#include "timer.hh"
+#include <deque>
+#include <string>
+#include <utility>
+
+namespace brunhild {
+
+// Development tool recording every write to a Store with a snapshot of the
+// resulting state. Recorded states can be stepped through and replayed, which
+// replaces the store's state and patches all views watching it. Records
+// nothing, if NDEBUG is defined.
+//
+// Writes to the store, while a past state is selected, discard all states
+// after it.
+template <class S> class Recorder {
+public:
+    // Recorded write
+    struct Entry {
+        std::string action;
+        S state;
+    };
+
+    // limit: maximum number of recorded entries. The oldest entries are
+    // discarded first.
+    Recorder(Store<S>& store, size_t limit = 1000)
+        : store(store)
+        , limit(limit ? limit : 1)
+    {
+#ifndef NDEBUG
+        entries.push_back({ "@init", store.get() });
+        sub_id = store.subscribe([this](const S& s, const std::string& a) {
+            record(s, a);
+        });
+#endif
+    }
+
+    ~Recorder()
+    {
+        stop_replay();
+        if (sub_id != -1) {
+            store.unsubscribe(sub_id);
+        }
+    }
+
+    Recorder(const Recorder&) = delete;
+    Recorder& operator=(const Recorder&) = delete;
+
+    // Returns the recorded entries, oldest first
+    const std::deque<Entry>& history() const { return entries; }
+
+    // Returns the index of the selected entry
+    size_t position() const { return pos; }
+
+    // Returns, if the latest entry is selected
+    bool at_latest() const { return pos + 1 >= entries.size(); }
+
+    // Select the entry before the selected one
+    void step_back()
+    {
+        if (pos > 0) {
+            jump(pos - 1);
+        }
+    }
+
+    // Select the entry after the selected one
+    void step_forward()
+    {
+        if (!at_latest()) {
+            jump(pos + 1);
+        }
+    }
+
+    // Select an entry by index and apply its state to the store
+    void jump(size_t i)
+    {
+        if (i >= entries.size()) {
+            return;
+        }
+        pos = i;
+        restoring = true;
+        store.replace(entries[i].state, "@jump");
+        restoring = false;
+    }
+
+    // Step through all entries from the first one, selecting the next entry
+    // every interval milliseconds
+    void replay(unsigned interval = 500)
+    {
+        stop_replay();
+        if (entries.empty()) {
+            return;
+        }
+        jump(0);
+        replay_timer = set_interval(
+            [this]() {
+                if (at_latest()) {
+                    stop_replay();
+                } else {
+                    step_forward();
+                }
+            },
+            interval);
+    }
+
+    // Stop a running replay at the selected entry
+    void stop_replay()
+    {
+        if (replay_timer != -1) {
+            clear_timer(replay_timer);
+            replay_timer = -1;
+        }
+    }
+
+    // Returns, if a replay is running
+    bool replaying() const { return replay_timer != -1; }
+
+    // Discard all entries, except for the selected state
+    void clear()
+    {
+        stop_replay();
+        if (entries.empty()) {
+            return;
+        }
+        auto e = std::move(entries[pos]);
+        entries.clear();
+        entries.push_back(std::move(e));
+        pos = 0;
+    }
+
+private:
+    Store<S>& store;
+    const size_t limit;
+    long sub_id = -1;
+    long replay_timer = -1;
+    bool restoring = false;
+    size_t pos = 0;
+    std::deque<Entry> entries;
+
+    void record(const S& state, const std::string& action)
+    {
+        if (restoring) {
+            return;
+        }
+        stop_replay();
+        entries.erase(entries.begin() + pos + 1, entries.end());
+        entries.push_back({ action, state });
+        while (entries.size() > limit) {
+            entries.pop_front();
+        }
+        pos = entries.size() - 1;
+    }
+};
+}
@@ -0,0 +1,115 @@
+#include "timer.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+namespace brunhild {
+
+struct Timer {
+    std::function<void()> fn;
+    unsigned interval; // 0 for timeouts
+    bool pausable, paused;
+    double due; // As returned by emscripten_get_now()
+    double remaining; // Time left, when paused
+    int handle; // JS timeout handle
+};
+
+static long id_counter = 0;
+
+// All pending timers by ID
+static std::unordered_map<long, Timer> timers;
+
+static bool timers_paused = false;
+
+// Schedule a JS timeout for the timer
+static void schedule(long id, Timer& t, double ms)
+{
+    t.due = emscripten_get_now() + ms;
+    t.handle = EM_ASM_INT(
+        {
+            var id = $0;
+            return setTimeout(function() { Module._bh_run_timer(id); }, $1);
+        },
+        id, ms);
+}
+
+static long add_timer(
+    std::function<void()> fn, unsigned ms, unsigned interval, bool pausable)
+{
+    const long id = id_counter++;
+    auto& t = timers[id] = { fn, interval, pausable, false, 0, 0, 0 };
+    if (pausable && timers_paused) {
+        t.paused = true;
+        t.remaining = ms;
+    } else {
+        schedule(id, t, ms);
+    }
+    return id;
+}
+
+long set_timeout(std::function<void()> fn, unsigned ms, bool pausable)
+{
+    return add_timer(fn, ms, 0, pausable);
+}
+
+long set_interval(std::function<void()> fn, unsigned ms, bool pausable)
+{
+    return add_timer(fn, ms, ms, pausable);
+}
+
+void clear_timer(long id)
+{
+    if (!timers.count(id)) {
+        return;
+    }
+    auto& t = timers.at(id);
+    if (!t.paused) {
+        EM_ASM_INT({ clearTimeout($0); }, t.handle);
+    }
+    timers.erase(id);
+}
+
+void pause_timers()
+{
+    timers_paused = true;
+    const double now = emscripten_get_now();
+    for (auto & [ _, t ] : timers) {
+        if (t.pausable && !t.paused) {
+            EM_ASM_INT({ clearTimeout($0); }, t.handle);
+            t.paused = true;
+            t.remaining = t.due > now ? t.due - now : 0;
+        }
+    }
+}
+
+void resume_timers()
+{
+    timers_paused = false;
+    for (auto & [ id, t ] : timers) {
+        if (t.paused) {
+            t.paused = false;
+            schedule(id, t, t.remaining);
+        }
+    }
+}
+
+static void run_timer(long id)
+{
+    if (!timers.count(id)) {
+        return;
+    }
+    auto& t = timers.at(id);
+    auto fn = t.fn; // Timer might be cleared by fn
+    if (t.interval) {
+        schedule(id, t, t.interval);
+    } else {
+        timers.erase(id);
+    }
+    fn();
+}
+
+EMSCRIPTEN_BINDINGS(module_timer)
+{
+    emscripten::function("_bh_run_timer", &run_timer);
+}
+}
@@ -0,0 +1,27 @@
+#pragma once
+
+#include <functional>
+
+namespace brunhild {
+
+// Run fn once after ms milliseconds. Pausable timers do not advance, while the
+// page is hidden, and resume with their remaining time, when it becomes
+// visible again.
+// Returns timer ID.
+long set_timeout(std::function<void()> fn, unsigned ms, bool pausable = false);
+
+// Run fn every ms milliseconds. See set_timeout() for pausable.
+// Returns timer ID.
+long set_interval(
+    std::function<void()> fn, unsigned ms, bool pausable = false);
+
+// Cancel a timer by ID
+void clear_timer(long id);
+
+// Pause all pausable timers. Called automatically, when the page is hidden.
+void pause_timers();
+
+// Resume all paused timers. Called automatically, when the page becomes
+// visible.
+void resume_timers();
+}
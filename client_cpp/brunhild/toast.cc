@@ -0,0 +1,165 @@
+#include "toast.hh"
+#include "timer.hh"
+#include "view.hh"
+#include <emscripten.h>
+#include <map>
+#include <memory>
+
+using std::string;
+
+namespace brunhild::toast {
+
+static long id_counter = 0;
+
+// ID of the element containing all toasts
+static const char* const root_id = "bh-toasts";
+
+static const char* severity_name(Severity s)
+{
+    switch (s) {
+    case Severity::success:
+        return "success";
+    case Severity::warning:
+        return "warning";
+    case Severity::error:
+        return "error";
+    default:
+        return "info";
+    }
+}
+
+class Toast : public VirtualView {
+public:
+    const long toast_id;
+    const string message, key;
+    const Options opts;
+
+    // Number of times the toast has been pushed
+    unsigned repeats = 1;
+
+    // ID of the dismissal timer or -1
+    long timer = -1;
+
+    Toast(long toast_id, string message, string key, Options opts)
+        : toast_id(toast_id)
+        , message(message)
+        , key(key)
+        , opts(opts)
+    {
+        on("click", ".bh-toast-close",
+            [toast_id](emscripten::val&) { dismiss(toast_id); });
+    }
+
+    Node render()
+    {
+        Attrs attrs = { { "class",
+            string("bh-toast bh-toast-") + severity_name(opts.severity) } };
+        attrs.role(opts.severity == Severity::error ? Role::alert
+                                                    : Role::status);
+
+        Node n("div", attrs,
+            { Node("span", { { "class", "bh-toast-message" } }, message,
+                true) });
+        if (repeats > 1) {
+            n.children.push_back(Node("span", { { "class", "bh-toast-count" } },
+                std::to_string(repeats)));
+        }
+        n.children.push_back(Node("button",
+            Attrs({ { "class", "bh-toast-close" } })
+                .aria_label(opts.close_label),
+            "&times;"));
+        return n;
+    }
+
+    // Unmount the view and remove its element after the exit transition
+    void leave()
+    {
+        unmount();
+        transition_exit(id, opts.transition.exit);
+    }
+};
+
+// Shown toasts by ID in insertion order
+static std::map<long, std::shared_ptr<Toast>> toasts;
+
+// Create the toast container, if needed
+static void install()
+{
+    EM_ASM_INT(
+        {
+            if (document.getElementById(UTF8ToString($0))) {
+                return;
+            }
+            var el = document.createElement("div");
+            el.id = UTF8ToString($0);
+            document.body.appendChild(el);
+        },
+        root_id);
+}
+
+// (Re)start the dismissal timer of a toast
+static void start_timer(Toast& t)
+{
+    if (t.timer >= 0) {
+        clear_timer(t.timer);
+        t.timer = -1;
+    }
+    if (t.opts.timeout) {
+        const long id = t.toast_id;
+        t.timer = set_timeout([id]() { dismiss(id); }, t.opts.timeout, true);
+    }
+}
+
+long push(const string& message, Options opts)
+{
+    const string key = opts.key != ""
+        ? opts.key
+        : string(severity_name(opts.severity)) + ':' + message;
+    for (auto & [ id, t ] : toasts) {
+        if (t->key == key) {
+            t->repeats++;
+            t->patch();
+            start_timer(*t);
+            return id;
+        }
+    }
+
+    install();
+    const long id = id_counter++;
+    auto t = std::make_shared<Toast>(id, message, key, opts);
+    toasts[id] = t;
+    append(root_id, t->html());
+    if (opts.transition.enter != "") {
+        transition_enter(t->id, opts.transition.enter);
+    }
+    start_timer(*t);
+    return id;
+}
+
+void dismiss(long id)
+{
+    auto it = toasts.find(id);
+    if (it == toasts.end()) {
+        return;
+    }
+    auto t = it->second;
+    toasts.erase(it);
+    if (t->timer >= 0) {
+        clear_timer(t->timer);
+    }
+    t->leave();
+
+    // Might be called from the toast's own event handler, so keep it alive
+    // until the handler returns
+    defer([t]() {});
+}
+
+void dismiss_all()
+{
+    while (toasts.size()) {
+        dismiss(toasts.begin()->first);
+    }
+}
+
+size_t count() { return toasts.size(); }
+}
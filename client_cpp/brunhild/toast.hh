@@ -0,0 +1,55 @@
+#pragma once
+
+#include "transition.hh"
+#include <string>
+
+// Transient notification messages. Toasts are rendered into a container
+// appended to the document body, newest last, and removed after a timeout or
+// on clicking their close button. Timeouts do not advance, while the page is
+// hidden.
+//
+// Rendered structure, for styling:
+//
+//     <div id="bh-toasts">
+//         <div class="bh-toast bh-toast-info" role="status">
+//             <span class="bh-toast-message">message</span>
+//             <span class="bh-toast-count">2</span>
+//             <button class="bh-toast-close" aria-label="Dismiss">×</button>
+//         </div>
+//     </div>
+namespace brunhild::toast {
+
+enum class Severity { info, success, warning, error };
+
+// Options of a toast
+struct Options {
+    Severity severity = Severity::info;
+
+    // Milliseconds until the toast is dismissed. 0 to keep it until dismissed
+    // manually.
+    unsigned timeout = 5000;
+
+    // Pushing a toast with the same key as a shown one restarts the shown
+    // toast's timeout and increments its repeat count instead. Defaults to the
+    // message and severity.
+    std::string key;
+
+    // Enter and exit transition classes
+    Transition transition = { "bh-toast-enter", "bh-toast-exit" };
+
+    // Accessible label of the close button
+    std::string close_label = "Dismiss";
+};
+
+// Show a toast with a plain text message. Returns toast ID.
+long push(const std::string& message, Options opts = {});
+
+// Dismiss a toast by ID. No-op, if not shown.
+void dismiss(long id);
+
+// Dismiss all toasts
+void dismiss_all();
+
+// Returns the number of shown toasts
+size_t count();
+}
@@ -0,0 +1,126 @@
+#include "transition.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// Functions to run, when waiting for a transition ends
+static std::unordered_map<long, std::function<void()>> pending;
+
+// Wait for the CSS transition or animation of an element to end after the
+// next flush and call fn
+static void wait(const string& id, bool enter, std::function<void()> fn)
+{
+    const long token = id_counter++;
+    pending[token] = fn;
+    defer([id, enter, token]() {
+        EM_ASM_INT(
+            {
+                var token = $1;
+                var called = false;
+                var done = function()
+                {
+                    if (!called) {
+                        called = true;
+                        Module._bh_transition_end(token);
+                    }
+                };
+                var el = window.__bh_get(UTF8ToString($0));
+                if (!el) {
+                    done();
+                    return;
+                }
+
+                var style = getComputedStyle(el);
+                if ($2 && style.animationName == "none") {
+                    // Let the browser render the starting state first
+                    requestAnimationFrame(
+                        function() { requestAnimationFrame(done); });
+                    return;
+                }
+
+                // Longest total duration of all transitions and animations
+                var max = function(durations, delays)
+                {
+                    var d = durations.split(",");
+                    var l = delays.split(",");
+                    var m = 0;
+                    for (var i = 0; i < d.length; i++) {
+                        var t = (parseFloat(d[i]) || 0)
+                            + (parseFloat(l[i % l.length]) || 0);
+                        m = Math.max(m, t);
+                    }
+                    return m * 1000;
+                };
+                var ms = Math.max(
+                    max(style.transitionDuration, style.transitionDelay),
+                    max(style.animationDuration, style.animationDelay));
+                if (!ms) {
+                    done();
+                    return;
+                }
+                var end = function(e)
+                {
+                    if (e.target === el) {
+                        el.removeEventListener("transitionend", end);
+                        el.removeEventListener("animationend", end);
+                        done();
+                    }
+                };
+                el.addEventListener("transitionend", end);
+                el.addEventListener("animationend", end);
+                setTimeout(done, ms + 50); // Events might never fire
+            },
+            id.c_str(), token, enter);
+    });
+}
+
+void transition_enter(const string& id, const string& cls)
+{
+    if (cls == "") {
+        return;
+    }
+    add_class(id, cls);
+    wait(id, true, [id, cls]() { remove_class(id, cls); });
+}
+
+void transition_exit(
+    const string& id, const string& cls, std::function<void()> done)
+{
+    if (cls == "") {
+        remove(id);
+        if (done) {
+            defer(done);
+        }
+        return;
+    }
+    add_class(id, cls);
+    wait(id, false, [id, done]() {
+        remove(id);
+        if (done) {
+            defer(done);
+        }
+    });
+}
+
+static void transition_end(long token)
+{
+    if (!pending.count(token)) {
+        return;
+    }
+    auto fn = std::move(pending.at(token));
+    pending.erase(token);
+    fn();
+}
+
+EMSCRIPTEN_BINDINGS(module_transition)
+{
+    emscripten::function("_bh_transition_end", &transition_end);
+}
+}
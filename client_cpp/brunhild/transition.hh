@@ -0,0 +1,29 @@
+#pragma once
+
+#include <functional>
+#include <string>
+
+namespace brunhild {
+
+// CSS classes applied to children entering and leaving a ListView
+struct Transition {
+    // Applied on insertion. If the class defines a CSS animation, it is
+    // removed, when the animation ends. Otherwise it is removed on the next
+    // frame, so that it can define the starting state of a CSS transition.
+    // Empty for no enter transition.
+    std::string enter;
+
+    // Applied before removal. The element is removed, once its CSS transition
+    // or animation ends. Empty for immediate removal.
+    std::string exit;
+};
+
+// Apply an enter transition class to an element inserted by the pending
+// mutations
+void transition_enter(const std::string& id, const std::string& cls);
+
+// Apply an exit transition class to an element and remove the element, once
+// the transition ends. done is optional and called after removal.
+void transition_exit(const std::string& id, const std::string& cls,
+    std::function<void()> done = nullptr);
+}
@@ -0,0 +1,114 @@
+#include "tween.hh"
+#include "mutations.hh"
+#include <emscripten.h>
+#include <map>
+#include <vector>
+
+using std::string;
+
+namespace brunhild {
+
+namespace easing {
+double linear(double t) { return t; }
+
+double ease_in_quad(double t) { return t * t; }
+
+double ease_out_quad(double t) { return t * (2 - t); }
+
+double ease_in_out_quad(double t)
+{
+    return t < .5 ? 2 * t * t : -1 + (4 - 2 * t) * t;
+}
+
+double ease_in_cubic(double t) { return t * t * t; }
+
+double ease_out_cubic(double t)
+{
+    const double f = t - 1;
+    return f * f * f + 1;
+}
+
+double ease_in_out_cubic(double t)
+{
+    if (t < .5) {
+        return 4 * t * t * t;
+    }
+    const double f = 2 * t - 2;
+    return .5 * f * f * f + 1;
+}
+}
+
+static long id_counter = 0;
+
+// All running tweens in creation order
+static std::map<long, Tween*> tweens;
+
+Tween::Tween(double from, double to, double duration, OnValue on_value,
+    Easing ease, std::function<void()> on_done)
+    : tween_id(id_counter++)
+    , from(from)
+    , to(to)
+    , duration(duration)
+    , start(emscripten_get_now())
+    , on_value(on_value)
+    , ease(ease)
+    , on_done(on_done)
+{
+    tweens[tween_id] = this;
+}
+
+Tween::Tween(string id, string property, double from, double to, string unit,
+    double duration, Easing ease, std::function<void()> on_done)
+    : Tween(from, to, duration,
+          [id, property, unit](double v) {
+              set_style(id, property, std::to_string(v) + unit);
+          },
+          ease, on_done)
+{
+}
+
+Tween::~Tween() { stop(); }
+
+void Tween::stop() { tweens.erase(tween_id); }
+
+bool Tween::running() const { return tweens.count(tween_id); }
+
+bool Tween::step(double now)
+{
+    double t = duration > 0 ? (now - start) / duration : 1;
+    if (t >= 1) {
+        on_value(to);
+        return false;
+    }
+    on_value(from + (to - from) * ease(t < 0 ? 0 : t));
+    return true;
+}
+
+void step_tweens()
+{
+    if (tweens.empty()) {
+        return;
+    }
+    const double now = emscripten_get_now();
+
+    // Callbacks might create or destroy tweens
+    std::vector<long> ids;
+    ids.reserve(tweens.size());
+    for (auto & [ id, _ ] : tweens) {
+        ids.push_back(id);
+    }
+    for (auto id : ids) {
+        auto it = tweens.find(id);
+        if (it == tweens.end()) {
+            continue;
+        }
+        auto t = it->second;
+        if (!t->step(now) && tweens.count(id)) {
+            tweens.erase(id);
+            if (auto done = t->on_done) { // Might destroy the tween
+                done();
+            }
+        }
+    }
+}
+}
@@ -0,0 +1,69 @@
+#pragma once
+
+#include <functional>
+#include <string>
+
+namespace brunhild {
+
+// Maps animation progress in the range [0, 1] to eased progress
+typedef std::function<double(double)> Easing;
+
+// Common easing functions
+namespace easing {
+double linear(double);
+double ease_in_quad(double);
+double ease_out_quad(double);
+double ease_in_out_quad(double);
+double ease_in_cubic(double);
+double ease_out_cubic(double);
+double ease_in_out_cubic(double);
+}
+
+// Interpolates a value over time. Stepped once per frame by the render loop
+// before DOM mutations are flushed, so any mutations made by the callback are
+// applied in the same frame. Stops on destruction.
+class Tween {
+public:
+    // Receives the interpolated value on each frame
+    typedef std::function<void(double)> OnValue;
+
+    // Interpolate from from to to over duration milliseconds.
+    // on_done is optional and called after the final value has been passed.
+    Tween(double from, double to, double duration, OnValue on_value,
+        Easing ease = easing::ease_in_out_quad,
+        std::function<void()> on_done = nullptr);
+
+    // Interpolate a CSS property of an element, like "opacity" or
+    // "width" with unit "px"
+    Tween(std::string id, std::string property, double from, double to,
+        std::string unit, double duration,
+        Easing ease = easing::ease_in_out_quad,
+        std::function<void()> on_done = nullptr);
+
+    ~Tween();
+
+    Tween(const Tween&) = delete;
+    Tween& operator=(const Tween&) = delete;
+
+    // Stop the tween without calling on_done
+    void stop();
+
+    // Returns, if the tween is still running
+    bool running() const;
+
+private:
+    const long tween_id;
+    const double from, to, duration, start;
+    const OnValue on_value;
+    const Easing ease;
+    const std::function<void()> on_done;
+
+    // Advance to the current time. Returns false, when done.
+    bool step(double now);
+
+    friend void step_tweens();
+};
+
+// Advance all running tweens. Called by the render loop on each frame.
+void step_tweens();
+}
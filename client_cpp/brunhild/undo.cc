@@ -0,0 +1,99 @@
+#include "undo.hh"
+#include "bus.hh"
+#include <emscripten.h>
+
+using std::string;
+
+namespace brunhild {
+
+static const string empty;
+
+void UndoStack::push(Command cmd, const string& group)
+{
+    const double now = emscripten_get_now();
+    redos.clear();
+    if (group.size() && group == last_group && undos.size()
+        && now - last_push < group_ms) {
+        // Merge with the previous edit. The merged edit is reverted in
+        // reverse order.
+        auto& prev = undos.back();
+        auto prev_undo = prev.undo;
+        auto prev_redo = prev.redo;
+        prev.undo = [prev_undo, undo = cmd.undo]() {
+            undo();
+            prev_undo();
+        };
+        prev.redo = [prev_redo, redo = cmd.redo]() {
+            prev_redo();
+            redo();
+        };
+    } else {
+        undos.push_back(std::move(cmd));
+        while (undos.size() > limit) {
+            undos.pop_front();
+        }
+    }
+    last_group = group;
+    last_push = now;
+    changed();
+}
+
+void UndoStack::exec(Command cmd, const string& group)
+{
+    cmd.redo();
+    push(std::move(cmd), group);
+}
+
+bool UndoStack::undo()
+{
+    if (undos.empty()) {
+        return false;
+    }
+    auto cmd = std::move(undos.back());
+    undos.pop_back();
+    break_group();
+    cmd.undo();
+    redos.push_back(std::move(cmd));
+    changed();
+    return true;
+}
+
+bool UndoStack::redo()
+{
+    if (redos.empty()) {
+        return false;
+    }
+    auto cmd = std::move(redos.back());
+    redos.pop_back();
+    break_group();
+    cmd.redo();
+    undos.push_back(std::move(cmd));
+    changed();
+    return true;
+}
+
+const string& UndoStack::undo_name() const
+{
+    return undos.size() ? undos.back().name : empty;
+}
+
+const string& UndoStack::redo_name() const
+{
+    return redos.size() ? redos.back().name : empty;
+}
+
+void UndoStack::clear()
+{
+    undos.clear();
+    redos.clear();
+    break_group();
+    changed();
+}
+
+void UndoStack::changed()
+{
+    for (auto& id : watchers) {
+        mark_dirty(id);
+    }
+}
+}
@@ -0,0 +1,99 @@
+#pragma once
+
+#include "store.hh"
+#include <deque>
+#include <functional>
+#include <string>
+#include <unordered_set>
+
+namespace brunhild {
+
+// Reversible edit
+struct Command {
+    // Human-readable name, like "Insert text"
+    std::string name;
+
+    // Reverts the edit
+    std::function<void()> undo;
+
+    // Applies the edit again after undo
+    std::function<void()> redo;
+};
+
+// Undo/redo history of reversible edits. Rapid edits of the same group, like
+// typed characters, can be merged into a single undo step.
+class UndoStack {
+public:
+    // limit: maximum number of undo steps. Oldest steps are discarded first.
+    // group_ms: maximum time between edits of the same group to merge them
+    UndoStack(size_t limit = 100, unsigned group_ms = 1000)
+        : limit(limit ? limit : 1)
+        , group_ms(group_ms)
+    {
+    }
+
+    // Record an edit, that has already been applied, and clear the redo
+    // history. If group is not empty and matches the group of the previous
+    // edit recorded less than group_ms ago, both are merged into one step.
+    void push(Command cmd, const std::string& group = "");
+
+    // Apply cmd.redo and record the edit. See push().
+    void exec(Command cmd, const std::string& group = "");
+
+    // Revert the last edit. Returns false, if there is none.
+    bool undo();
+
+    // Apply the last reverted edit again. Returns false, if there is none.
+    bool redo();
+
+    bool can_undo() const { return undos.size(); }
+    bool can_redo() const { return redos.size(); }
+
+    // Returns the name of the edit undo() would revert or an empty string
+    const std::string& undo_name() const;
+
+    // Returns the name of the edit redo() would apply or an empty string
+    const std::string& redo_name() const;
+
+    // Prevent the next edit from being merged with the previous one
+    void break_group() { last_group.clear(); }
+
+    // Clear the undo and redo history
+    void clear();
+
+    // Mark a view dirty, whenever the history changes, so it can render
+    // can_undo() and can_redo()
+    void watch(View& view) { watchers.insert(view.id); }
+
+    // Stop marking a view dirty
+    void unwatch(View& view) { watchers.erase(view.id); }
+
+private:
+    const size_t limit;
+    const unsigned group_ms;
+    std::deque<Command> undos, redos;
+    std::string last_group;
+    double last_push = 0;
+    std::unordered_set<std::string> watchers;
+
+    // Mark watching views dirty
+    void changed();
+};
+
+// Create a command restoring the state of store before an edit, that has just
+// been written to the store, and writing the edited state again on redo
+template <class S>
+Command store_command(Store<S>& store, S before, const std::string& name)
+{
+    S after = store.get();
+    return {
+        name,
+        [&store, before = std::move(before)]() {
+            store.replace(before, "@undo");
+        },
+        [&store, after = std::move(after)]() {
+            store.replace(after, "@redo");
+        },
+    };
+}
+}
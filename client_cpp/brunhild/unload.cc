@@ -0,0 +1,62 @@
+#include "unload.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// Registered guards by ID
+static std::map<long, UnloadPredicate> guards;
+
+long add_unload_guard(UnloadPredicate pred)
+{
+    if (guards.empty()) {
+        EM_ASM({
+            window.__bh_unload = function(e)
+            {
+                if (Module._bh_block_unload()) {
+                    e.preventDefault();
+                    e.returnValue = ""; // Chrome requires returnValue set
+                    return "";
+                }
+            };
+            window.addEventListener("beforeunload", window.__bh_unload);
+        });
+    }
+    const long id = id_counter++;
+    guards[id] = pred;
+    return id;
+}
+
+void remove_unload_guard(long id)
+{
+    if (!guards.erase(id) || guards.size()) {
+        return;
+    }
+
+    // Listening to beforeunload disables the back-forward cache in some
+    // browsers, so remove the listener, when not needed
+    EM_ASM({
+        window.removeEventListener("beforeunload", window.__bh_unload);
+        delete window.__bh_unload;
+    });
+}
+
+static bool block_unload()
+{
+    auto copy = guards; // Predicate might invalidate this iterator
+    for (auto & [ _, pred ] : copy) {
+        if (pred()) {
+            return true;
+        }
+    }
+    return false;
+}
+
+EMSCRIPTEN_BINDINGS(module_unload)
+{
+    emscripten::function("_bh_block_unload", &block_unload);
+}
+}
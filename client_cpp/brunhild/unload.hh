@@ -0,0 +1,36 @@
+#pragma once
+
+#include <functional>
+
+namespace brunhild {
+
+// Predicate returning, if leaving the page should be confirmed by the user,
+// like when there is an unsent post
+typedef std::function<bool()> UnloadPredicate;
+
+// Register a predicate consulted, when the page is about to be unloaded.
+// If any predicate returns true, the browser's confirmation dialog is shown.
+// Returns guard ID.
+long add_unload_guard(UnloadPredicate pred);
+
+// Remove an unload guard by ID
+void remove_unload_guard(long id);
+
+// Registers an unload guard for its lifetime. Store as a View member to tie
+// the guard to the View's lifecycle.
+class UnloadGuard {
+public:
+    UnloadGuard(UnloadPredicate pred)
+        : guard_id(add_unload_guard(pred))
+    {
+    }
+
+    ~UnloadGuard() { remove_unload_guard(guard_id); }
+
+    UnloadGuard(const UnloadGuard&) = delete;
+    UnloadGuard& operator=(const UnloadGuard&) = delete;
+
+private:
+    const long guard_id;
+};
+}
@@ -0,0 +1,173 @@
+#include "unread.hh"
+#include "head.hh"
+#include "visibility.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild::unread {
+
+static Options options;
+static unsigned unread = 0;
+
+// A badge is drawn onto the favicon
+static bool badge_drawn = false;
+
+// Returns the JS favicon and focus helpers, installing them, if needed
+static val helpers()
+{
+    EM_ASM({
+        if (window.__bh_unread) {
+            return;
+        }
+
+        window.addEventListener(
+            "focus", function() { Module._bh_unread_focus(); });
+
+        var original;
+        var token = 0;
+
+        window.__bh_unread = {
+            focused : function() { return document.hasFocus(); },
+
+            // Draw text onto the original favicon or restore it, if empty
+            favicon : function(text, color)
+            {
+                var link = document.querySelector('link[rel~="icon"]');
+                if (!link) {
+                    link = document.createElement("link");
+                    link.rel = "icon";
+                    document.head.appendChild(link);
+                }
+                if (original === undefined) {
+                    original = link.getAttribute("href") || "/favicon.ico";
+                }
+                var t = ++token;
+                if (!text) {
+                    link.href = original;
+                    return;
+                }
+
+                var img = new Image();
+                var draw = function(loaded)
+                {
+                    if (t != token) { // Superseded by a later call
+                        return;
+                    }
+                    var size = 32;
+                    var c = document.createElement("canvas");
+                    c.width = c.height = size;
+                    var ctx = c.getContext("2d");
+                    if (loaded) {
+                        ctx.drawImage(img, 0, 0, size, size);
+                    }
+                    ctx.font = "bold 18px sans-serif";
+                    var h = 20;
+                    var w = Math.max(h, ctx.measureText(text).width + 6);
+                    ctx.fillStyle = color;
+                    ctx.fillRect(size - w, size - h, w, h);
+                    ctx.fillStyle = "#fff";
+                    ctx.textAlign = "center";
+                    ctx.textBaseline = "middle";
+                    ctx.fillText(text, size - w / 2, size - h / 2 + 1);
+                    try {
+                        link.href = c.toDataURL("image/png");
+                    } catch (e) { // Tainted by a cross-origin favicon
+                        console.error(e);
+                    }
+                };
+                img.onload = function() { draw(true); };
+                img.onerror = function() { draw(false); };
+                img.src = original;
+            }
+        };
+    });
+    return val::global("__bh_unread");
+}
+
+// Displayed text of the count. Empty for zero.
+static string format()
+{
+    if (!unread) {
+        return "";
+    }
+    if (unread > options.max) {
+        return std::to_string(options.max) + '+';
+    }
+    return std::to_string(unread);
+}
+
+// Display the count according to the options
+static void apply()
+{
+    const auto text = format();
+    head::set_title_prefix(
+        options.title && text != "" ? '(' + text + ") " : "");
+    const auto badge = options.favicon ? text : string();
+    if (badge != "" || badge_drawn) {
+        helpers().call<void>("favicon", badge, options.badge_color);
+        badge_drawn = badge != "";
+    }
+}
+
+// Register the focus and visibility handlers, if not yet done
+static void init()
+{
+    static bool done = false;
+    if (done) {
+        return;
+    }
+    done = true;
+    helpers();
+    on_visibility_change([](bool visible) {
+        if (visible && options.clear_on_focus) {
+            clear();
+        }
+    });
+}
+
+void configure(Options opts)
+{
+    init();
+    options = opts;
+    apply();
+}
+
+void set(unsigned count)
+{
+    init();
+    if (count == unread) {
+        return;
+    }
+    unread = count;
+    apply();
+}
+
+void add(unsigned n)
+{
+    init();
+    if (options.clear_on_focus && helpers().call<bool>("focused")) {
+        return;
+    }
+    set(unread + n);
+}
+
+void clear() { set(0); }
+
+unsigned count() { return unread; }
+
+static void on_focus()
+{
+    if (options.clear_on_focus) {
+        clear();
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_unread)
+{
+    emscripten::function("_bh_unread_focus", &on_focus);
+}
+}
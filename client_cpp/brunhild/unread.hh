@@ -0,0 +1,43 @@
+#pragma once
+
+#include <string>
+
+// Unread item counter displayed as a "(3) " prefix of the document title and
+// optionally as a badge drawn onto the favicon. Cleared, when the tab regains
+// focus.
+namespace brunhild::unread {
+
+// Display options of the counter
+struct Options {
+    // Prefix the document title with the count
+    bool title = true;
+
+    // Draw a badge with the count onto the favicon
+    bool favicon = false;
+
+    // Reset the count, when the tab regains focus or becomes visible
+    bool clear_on_focus = true;
+
+    // Counts above this are displayed as "<max>+"
+    unsigned max = 99;
+
+    // CSS color of the favicon badge
+    std::string badge_color = "#e0301e";
+};
+
+// Set the display options and redisplay the count
+void configure(Options opts);
+
+// Set the count. Zero hides the counter.
+void set(unsigned count);
+
+// Increment the count. Ignored, while the tab is focused and clear_on_focus
+// is set, as the items are being seen.
+void add(unsigned n = 1);
+
+// Reset the count to zero
+void clear();
+
+// Returns the current count
+unsigned count();
+}
@@ -0,0 +1,168 @@
+#include "upload.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All running uploads by ID
+static std::unordered_map<long, Upload*> uploads;
+
+// Event types passed from JS
+enum class UploadEvent { progress, done, cancelled };
+
+static val to_js(const FormField& f)
+{
+    if (auto s = std::get_if<string>(&f)) {
+        return val(*s);
+    }
+    return std::get<File>(f).handle;
+}
+
+Upload::Upload(string url, std::vector<std::pair<string, FormField>> form,
+    std::function<void(const UploadProgress&)> on_progress,
+    std::function<void(const UploadResult&)> on_done)
+    : on_progress(on_progress)
+    , on_done(on_done)
+    , upload_id(id_counter++)
+{
+    auto data = val::global("FormData").new_();
+    for (auto & [ key, field ] : form) {
+        if (auto f = std::get_if<File>(&field)) {
+            data.call<void>("append", key, f->handle, f->name);
+        } else {
+            data.call<void>("append", key, to_js(field));
+        }
+    }
+    start("POST", url, data, {});
+}
+
+Upload::Upload(string method, string url, FormField body,
+    std::map<string, string> headers,
+    std::function<void(const UploadProgress&)> on_progress,
+    std::function<void(const UploadResult&)> on_done)
+    : on_progress(on_progress)
+    , on_done(on_done)
+    , upload_id(id_counter++)
+{
+    start(method, url, to_js(body), headers);
+}
+
+void Upload::start(const string& method, const string& url, val body,
+    const std::map<string, string>& headers)
+{
+    uploads[upload_id] = this;
+
+    EM_ASM({
+        if (window.__bh_uploads) {
+            return;
+        }
+        window.__bh_uploads = {};
+        window.__bh_upload = function(id, method, url, body, headers)
+        {
+            var xhr = new XMLHttpRequest();
+            var start = Date.now();
+            xhr.open(method, url);
+            for (var k in headers) {
+                xhr.setRequestHeader(k, headers[k]);
+            }
+            xhr.upload.onprogress = function(e)
+            {
+                var secs = (Date.now() - start) / 1000;
+                Module._bh_upload_event(id, 0, {
+                    loaded : e.loaded,
+                    total : e.lengthComputable ? e.total : 0,
+                    speed : secs ? e.loaded / secs : 0
+                });
+            };
+            var finish = function(type)
+            {
+                delete window.__bh_uploads[id];
+                Module._bh_upload_event(id, type, {
+                    status : xhr.status,
+                    body : typeof xhr.responseText == "string"
+                        ? xhr.responseText
+                        : ""
+                });
+            };
+            xhr.onload = xhr.onerror = function() { finish(1); };
+            xhr.onabort = function() { finish(2); };
+            window.__bh_uploads[id] = xhr;
+            xhr.send(body);
+        };
+    });
+
+    auto h = val::object();
+    for (auto & [ k, v ] : headers) {
+        h.set(k, v);
+    }
+    val::global("__bh_upload")(upload_id, method, url, body, h);
+}
+
+Upload::~Upload()
+{
+    if (!done) {
+        // Do not call on_done on a destroyed object
+        uploads.erase(upload_id);
+        cancel();
+    }
+}
+
+void Upload::cancel()
+{
+    if (done) {
+        return;
+    }
+    EM_ASM_INT(
+        {
+            var xhr = window.__bh_uploads[$0];
+            if (xhr) {
+                xhr.abort();
+            }
+        },
+        upload_id);
+}
+
+void run_upload_handler(long id, int type, val data)
+{
+    if (!uploads.count(id)) {
+        return;
+    }
+    auto u = uploads.at(id);
+
+    switch (static_cast<UploadEvent>(type)) {
+    case UploadEvent::progress:
+        if (u->on_progress) {
+            UploadProgress p;
+            p.loaded = data["loaded"].as<double>();
+            p.total = data["total"].as<double>();
+            p.percent = p.total ? p.loaded / p.total * 100 : 0;
+            p.speed = data["speed"].as<double>();
+            u->on_progress(p);
+        }
+        break;
+    case UploadEvent::done:
+    case UploadEvent::cancelled:
+        uploads.erase(id);
+        u->done = true;
+        if (u->on_done) {
+            u->on_done({
+                data["status"].as<unsigned short>(),
+                data["body"].as<string>(),
+                static_cast<UploadEvent>(type) == UploadEvent::cancelled,
+            });
+        }
+        break;
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_upload)
+{
+    emscripten::function("_bh_upload_event", &run_upload_handler);
+}
+}
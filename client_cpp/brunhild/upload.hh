@@ -0,0 +1,83 @@
+#pragma once
+
+#include "file.hh"
+#include <functional>
+#include <map>
+#include <string>
+#include <utility>
+#include <variant>
+#include <vector>
+
+namespace brunhild {
+
+// Progress of an upload in progress
+struct UploadProgress {
+    // Bytes sent and total bytes to send
+    double loaded, total;
+
+    // Percentage of bytes sent in the range [0, 100]
+    double percent;
+
+    // Average upload speed since start in bytes per second
+    double speed;
+};
+
+// Result of a finished, failed or cancelled upload
+struct UploadResult {
+    // HTTP status code. 0 on network errors and cancellation.
+    unsigned short status;
+
+    // Response body
+    std::string body;
+
+    // Upload has been cancelled
+    bool cancelled;
+};
+
+// Field of a multipart/form-data body
+typedef std::variant<std::string, File> FormField;
+
+// Uploads a body with XMLHttpRequest and reports progress.
+// Destroying the Upload cancels it without calling on_done.
+class Upload {
+public:
+    // Called on each upload progress event
+    std::function<void(const UploadProgress&)> on_progress;
+
+    // Called once, when the upload has finished, failed or was cancelled
+    std::function<void(const UploadResult&)> on_done;
+
+    // Send fields as a multipart/form-data POST request
+    Upload(std::string url, std::vector<std::pair<std::string, FormField>> form,
+        std::function<void(const UploadProgress&)> on_progress,
+        std::function<void(const UploadResult&)> on_done);
+
+    // Send a raw body with the specified method, like "PUT"
+    Upload(std::string method, std::string url, FormField body,
+        std::map<std::string, std::string> headers,
+        std::function<void(const UploadProgress&)> on_progress,
+        std::function<void(const UploadResult&)> on_done);
+
+    ~Upload();
+
+    Upload(const Upload&) = delete;
+    Upload& operator=(const Upload&) = delete;
+
+    // Cancel the upload. on_done is called with UploadResult::cancelled set.
+    void cancel();
+
+    // Returns, if the upload is still running
+    bool running() const { return !done; }
+
+private:
+    const long upload_id;
+    bool done = false;
+
+    // Start the request with a prepared JS body
+    void start(const std::string& method, const std::string& url,
+        emscripten::val body,
+        const std::map<std::string, std::string>& headers);
+
+    friend void run_upload_handler(long, int, emscripten::val);
+};
+}
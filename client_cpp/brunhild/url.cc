@@ -0,0 +1,104 @@
+#include "url.hh"
+#include <emscripten/val.h>
+
+using std::string;
+
+namespace brunhild::url {
+
+string encode(std::string_view s)
+{
+    static const char hex[] = "0123456789ABCDEF";
+    string out;
+    out.reserve(s.size());
+    for (unsigned char ch : s) {
+        if ((ch >= 'a' && ch <= 'z') || (ch >= 'A' && ch <= 'Z')
+            || (ch >= '0' && ch <= '9') || ch == '-' || ch == '_' || ch == '.'
+            || ch == '~') {
+            out += ch;
+        } else {
+            out += '%';
+            out += hex[ch >> 4];
+            out += hex[ch & 15];
+        }
+    }
+    return out;
+}
+
+static int from_hex(char ch)
+{
+    if (ch >= '0' && ch <= '9') {
+        return ch - '0';
+    }
+    if (ch >= 'a' && ch <= 'f') {
+        return ch - 'a' + 10;
+    }
+    if (ch >= 'A' && ch <= 'F') {
+        return ch - 'A' + 10;
+    }
+    return -1;
+}
+
+string decode(std::string_view s)
+{
+    string out;
+    out.reserve(s.size());
+    for (size_t i = 0; i < s.size(); i++) {
+        const char ch = s[i];
+        if (ch == '+') {
+            out += ' ';
+        } else if (ch == '%' && i + 2 < s.size() && from_hex(s[i + 1]) >= 0
+            && from_hex(s[i + 2]) >= 0) {
+            out += (char)(from_hex(s[i + 1]) << 4 | from_hex(s[i + 2]));
+            i += 2;
+        } else {
+            out += ch;
+        }
+    }
+    return out;
+}
+
+Query::Query(std::string_view s)
+{
+    s = s.substr(0, s.find('#'));
+    if (s.size() && s[0] == '?') {
+        s.remove_prefix(1);
+    }
+    while (s.size()) {
+        const auto end = s.find('&');
+        const auto pair = s.substr(0, end);
+        if (pair.size()) {
+            const auto eq = pair.find('=');
+            if (eq == std::string_view::npos) {
+                (*this)[decode(pair)] = "";
+            } else {
+                (*this)[decode(pair.substr(0, eq))]
+                    = decode(pair.substr(eq + 1));
+            }
+        }
+        if (end == std::string_view::npos) {
+            break;
+        }
+        s.remove_prefix(end + 1);
+    }
+}
+
+string Query::to_string() const
+{
+    string s;
+    for (auto & [ key, val ] : *this) {
+        s += s.empty() ? '?' : '&';
+        s += encode(key);
+        if (val.size()) {
+            s += '=';
+            s += encode(val);
+        }
+    }
+    return s;
+}
+
+Query current_query()
+{
+    return Query(
+        emscripten::val::global("location")["search"].as<string>());
+}
+}
@@ -0,0 +1,79 @@
+#pragma once
+
+#include <map>
+#include <optional>
+#include <sstream>
+#include <string>
+#include <string_view>
+#include <type_traits>
+
+// URL query string parsing and serialization
+namespace brunhild::url {
+
+// Percent-encode a string for use as a query key or value
+std::string encode(std::string_view s);
+
+// Decode a percent-encoded query key or value. "+" is decoded as space.
+std::string decode(std::string_view s);
+
+// Parsed query string with sorted keys. Only the last value of a repeated key
+// is kept.
+class Query : public std::map<std::string, std::string> {
+public:
+    Query() = default;
+
+    // Parse a query string with or without the leading "?". Any URL fragment
+    // starting with "#" is ignored.
+    Query(std::string_view s);
+
+    // Parse a value into T. Returns std::nullopt, if the key is not set or the
+    // value can not be parsed as T.
+    template <class T> std::optional<T> get(const std::string& key) const
+    {
+        auto it = find(key);
+        if (it == end()) {
+            return std::nullopt;
+        }
+        if constexpr (std::is_same_v<T, std::string>) {
+            return it->second;
+        } else if constexpr (std::is_same_v<T, bool>) {
+            // Keys without values, like "?debug", are treated as true
+            const auto& v = it->second;
+            if (v == "" || v == "true" || v == "1") {
+                return true;
+            }
+            if (v == "false" || v == "0") {
+                return false;
+            }
+            return std::nullopt;
+        } else {
+            std::istringstream is(it->second);
+            T val;
+            if (!(is >> val) || !is.eof()) {
+                return std::nullopt;
+            }
+            return val;
+        }
+    }
+
+    // Serialize and set a value
+    template <class T> Query& set(const std::string& key, const T& val)
+    {
+        if constexpr (std::is_same_v<T, bool>) {
+            (*this)[key] = val ? "true" : "false";
+        } else {
+            std::ostringstream os;
+            os << val;
+            (*this)[key] = os.str();
+        }
+        return *this;
+    }
+
+    // Serialize to a query string with a leading "?".
+    // Returns an empty string, if there are no keys.
+    std::string to_string() const;
+};
+
+// Returns the parsed query string of the current page
+Query current_query();
+}
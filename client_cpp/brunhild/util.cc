@@ -1,32 +1,31 @@
+#include "util.hh"
 #include <string>
 
 namespace brunhild {
 
+// Adapts a std::string for appending with write_escaped()
+struct StringWriter {
+    std::string& s;
+
+    StringWriter& operator<<(char ch)
+    {
+        s += ch;
+        return *this;
+    }
+
+    StringWriter& operator<<(const char* str)
+    {
+        s += str;
+        return *this;
+    }
+};
+
 std::string escape(const std::string& s)
 {
     std::string out;
     out.reserve(s.size() * 1.1);
-    for (auto ch : s) {
-        switch (ch) {
-        case '&':
-            out += "&amp;";
-            break;
-        case '\'':
-            out += "&#39;"; // "&#39;" is shorter than "&apos;"
-            break;
-        case '<':
-            out += "&lt;";
-            break;
-        case '>':
-            out += "&gt;";
-            break;
-        case '\"':
-            out += "&#34;"; // "&#34;" is shorter than "&quot;"
-            break;
-        default:
-            out += ch;
-        }
-    }
+    StringWriter w{ out };
+    write_escaped(w, s);
     return out;
 }
 }
@@ -1,7 +1,9 @@
 #pragma once
 
+#include <functional>
 #include <string.h>
 #include <string>
+#include <string_view>
 #include <type_traits>
 #include <vector>
 
@@ -11,6 +13,35 @@ namespace brunhild {
 // HTML
 std::string escape(const std::string& s);
 
+// Write s HTML-escaped to any writer supporting operator<< for characters and
+// C strings, like Rope or std::ostream, without allocating an intermediate
+// string like escape()
+template <class W> W& write_escaped(W& w, std::string_view s)
+{
+    for (auto ch : s) {
+        switch (ch) {
+        case '&':
+            w << "&amp;";
+            break;
+        case '\'':
+            w << "&#39;"; // "&#39;" is shorter than "&apos;"
+            break;
+        case '<':
+            w << "&lt;";
+            break;
+        case '>':
+            w << "&gt;";
+            break;
+        case '\"':
+            w << "&#34;"; // "&#34;" is shorter than "&quot;"
+            break;
+        default:
+            w << ch;
+        }
+    }
+    return w;
+}
+
 // Allows returning the size of a std::string, std::string_view, char or char*
 inline size_t string_size(const std::string& s) { return s.size(); }
 inline size_t string_size(const std::string_view& s) { return s.size(); }
@@ -26,6 +57,9 @@ class Rope {
     friend Rope& operator<<(Rope& r, const char* s);
 
 public:
+    // Receives chunks of a streamed Rope's contents
+    typedef std::function<void(std::string_view)> Sink;
+
     Rope()
     {
         parts.reserve(16);
@@ -34,6 +68,47 @@ public:
         parts.emplace_back().reserve(1 << 10);
     }
 
+    // Creates a Rope streaming its contents to sink in chunks of at least
+    // chunk_size bytes, written at checkpoints. str() then only returns the
+    // contents not yet written.
+    Rope(Sink sink, size_t chunk_size = 16 << 10)
+        : Rope()
+    {
+        this->sink = sink;
+        this->chunk_size = chunk_size;
+    }
+
+    // Write buffered contents to the sink, if streaming and at least
+    // chunk_size bytes are buffered. Called between the children of views and
+    // nodes.
+    void checkpoint()
+    {
+        if (sink && buffered >= chunk_size) {
+            flush();
+        }
+    }
+
+    // Returns the total size of the contents written so far, including those
+    // already written to the sink
+    size_t size() const { return total; }
+
+    // Write all buffered contents to the sink, if streaming
+    void flush()
+    {
+        if (!sink || !buffered) {
+            return;
+        }
+        for (auto& s : parts) {
+            if (s.size()) {
+                sink(s);
+            }
+        }
+        const auto cap = parts.back().capacity();
+        parts.clear();
+        parts.emplace_back().reserve(cap);
+        buffered = 0;
+    }
+
     // Dumps Rope contents to string
     std::string str()
     {
@@ -51,6 +126,14 @@ public:
 
 private:
     std::vector<std::string> parts;
+    Sink sink;
+    size_t chunk_size = 0;
+
+    // Size of contents not yet written to the sink
+    size_t buffered = 0;
+
+    // Size of all contents
+    size_t total = 0;
 
     template <class T> Rope& append(const T& s)
     {
@@ -61,6 +144,8 @@ private:
             last->reserve(last_cap << 1);
         }
         *last += s;
+        buffered += string_size(s);
+        total += string_size(s);
         return *this;
     }
 };
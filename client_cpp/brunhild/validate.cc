@@ -0,0 +1,210 @@
+#include "validate.hh"
+
+#ifndef NDEBUG
+
+#include "aria.hh"
+#include "error.hh"
+#include "log.hh"
+#include "util.hh"
+#include <cctype>
+#include <unordered_map>
+#include <unordered_set>
+#include <vector>
+
+using std::string;
+
+namespace brunhild {
+
+// Log validation error to console and abort rendering
+[[noreturn]] static void fail(
+    const string& view_id, const string& tag, const string& msg)
+{
+    Rope s;
+    s << "invalid HTML in view " << view_id << ": ";
+    if (tag != "") {
+        s << '<' << tag << ">: ";
+    }
+    s << msg;
+    const auto str = s.str();
+    BH_ERROR(str);
+    throw Error(str, view_id);
+}
+
+static bool valid_tag(const string& tag)
+{
+    if (tag.empty() || !isalpha(static_cast<unsigned char>(tag[0]))) {
+        return false;
+    }
+    for (unsigned char ch : tag) {
+        if (!isalnum(ch) && ch != '-') {
+            return false;
+        }
+    }
+    return true;
+}
+
+static bool valid_attr_key(const string& key)
+{
+    if (key.empty()) {
+        return false;
+    }
+    for (char ch : key) {
+        switch (ch) {
+        case ' ':
+        case '\t':
+        case '\n':
+        case '"':
+        case '\'':
+        case '<':
+        case '>':
+        case '/':
+        case '=':
+            return false;
+        }
+    }
+    return true;
+}
+
+static void check_attrs(
+    const Attrs& attrs, const string& tag, const string& view_id)
+{
+    for (auto & [ key, _ ] : attrs) {
+        if (!valid_attr_key(key)) {
+            fail(view_id, tag, "invalid attribute key: " + key);
+        }
+    }
+}
+
+// ARIA attributes, that only apply to elements with one of a set of roles
+static const std::unordered_map<string, std::unordered_set<string>>
+    aria_roles = {
+        { "aria-checked",
+            { "checkbox", "menuitemcheckbox", "menuitemradio", "option",
+                "radio", "switch", "treeitem" } },
+        { "aria-selected",
+            { "columnheader", "gridcell", "option", "row", "rowheader", "tab",
+                "treeitem" } },
+        { "aria-modal", { "dialog", "alertdialog" } },
+        { "aria-level", { "heading", "listitem", "row", "treeitem" } },
+        { "aria-valuenow",
+            { "progressbar", "scrollbar", "separator", "slider",
+                "spinbutton" } },
+        { "aria-valuemin",
+            { "progressbar", "scrollbar", "separator", "slider",
+                "spinbutton" } },
+        { "aria-valuemax",
+            { "progressbar", "scrollbar", "separator", "slider",
+                "spinbutton" } },
+    };
+
+// ARIA attributes, that only accept "true" or "false"
+static const std::unordered_set<string> aria_bools = {
+    "aria-atomic", "aria-busy", "aria-disabled", "aria-expanded",
+    "aria-hidden", "aria-modal", "aria-multiline", "aria-multiselectable",
+    "aria-readonly", "aria-required", "aria-selected",
+};
+
+// Check for invalid roles and obviously invalid combinations of roles and
+// ARIA attributes. Native form controls are exempt from role requirements.
+static void check_aria(const Node& node, const string& view_id)
+{
+    string role;
+    if (node.attrs.count("role")) {
+        role = node.attrs.at("role");
+        if (!valid_role(role)) {
+            fail(view_id, node.tag, "invalid role: " + role);
+        }
+    }
+    const bool native = node.tag == "input" || node.tag == "select"
+        || node.tag == "option" || node.tag == "progress"
+        || node.tag == "meter";
+
+    for (auto & [ key, val ] : node.attrs) {
+        if (key.compare(0, 5, "aria-") != 0) {
+            continue;
+        }
+        if (aria_bools.count(key) && val != "true" && val != "false") {
+            fail(view_id, node.tag,
+                key + " must be \"true\" or \"false\": " + val);
+        }
+        if (aria_roles.count(key) && !native
+            && !aria_roles.at(key).count(role)) {
+            fail(view_id, node.tag,
+                key + " not supported by role: "
+                    + (role != "" ? role : "none set"));
+        }
+        if ((role == "none" || role == "presentation")
+            && (key == "aria-label" || key == "aria-labelledby")) {
+            fail(view_id, node.tag, key + " on presentational element");
+        }
+    }
+}
+
+// Elements, that can only be children of a specific set of parents
+static const std::unordered_map<string, std::unordered_set<string>>
+    allowed_parents = {
+        { "tr", { "table", "thead", "tbody", "tfoot" } },
+        { "td", { "tr" } },
+        { "th", { "tr" } },
+        { "thead", { "table" } },
+        { "tbody", { "table" } },
+        { "tfoot", { "table" } },
+        { "li", { "ul", "ol", "menu" } },
+        { "option", { "select", "datalist", "optgroup" } },
+    };
+
+// Elements, that automatically close an open <p>
+static const std::unordered_set<string> closes_p = {
+    "p", "div", "ul", "ol", "table", "blockquote", "pre", "form", "h1", "h2",
+    "h3", "h4", "h5", "h6", "hr", "section", "header", "footer", "figure",
+};
+
+static void check(const Node& node, const std::vector<const Node*>& ancestors,
+    const string& view_id)
+{
+    if (!valid_tag(node.tag)) {
+        fail(view_id, node.tag, "invalid tag name");
+    }
+    check_attrs(node.attrs, node.tag, view_id);
+    check_aria(node, view_id);
+
+    if (ancestors.size()) {
+        const auto& parent = ancestors.back()->tag;
+        if (allowed_parents.count(node.tag)
+            && !allowed_parents.at(node.tag).count(parent)) {
+            fail(view_id, node.tag, "invalid parent element <" + parent + '>');
+        }
+    }
+    if (closes_p.count(node.tag)) {
+        for (auto a : ancestors) {
+            if (a->tag == "p") {
+                fail(view_id, node.tag, "can not be nested inside <p>");
+            }
+        }
+    }
+
+    if (node.inner_html) {
+        return;
+    }
+    auto next = ancestors;
+    next.push_back(&node);
+    for (auto& ch : node.children) {
+        check(ch, next, view_id);
+    }
+}
+
+void validate(const Node& node, const string& view_id)
+{
+    check(node, {}, view_id);
+}
+
+void validate_attrs(const Attrs& attrs, const string& view_id)
+{
+    if (attrs.count("id")) {
+        fail(view_id, "", "\"id\" set in attrs() is ignored");
+    }
+    check_attrs(attrs, "", view_id);
+}
+}
+
+#endif
@@ -0,0 +1,27 @@
+#pragma once
+
+#include "node.hh"
+#include <string>
+
+namespace brunhild {
+
+// Validate a rendered node tree in debug builds. Checks for invalid tag names,
+// malformed attribute keys, obviously invalid element nesting and ARIA
+// attributes not supported by the element's role. Logs the error with the ID
+// of the offending view to the console and throws.
+// Compiled out, if NDEBUG is defined.
+#ifndef NDEBUG
+void validate(const Node& node, const std::string& view_id);
+#else
+inline void validate(const Node&, const std::string&) {}
+#endif
+
+// Validate attributes returned by a ParentView in debug builds.
+// Additionally rejects an "id" attribute, as it is always overridden with the
+// view's ID.
+#ifndef NDEBUG
+void validate_attrs(const Attrs& attrs, const std::string& view_id);
+#else
+inline void validate_attrs(const Attrs&, const std::string&) {}
+#endif
+}
@@ -1,12 +1,22 @@
 #include "view.hh"
 #include "../src/util.hh"
+#include "context.hh"
+#include "debug.hh"
+#include "error.hh"
 #include "events.hh"
+#include "focus.hh"
+#include "log.hh"
 #include "mutations.hh"
+#include "perf.hh"
+#include "perf_warnings.hh"
 #include <algorithm>
 #include <emscripten.h>
 #include <emscripten/bind.h>
 #include <sstream>
 #include <string_view>
+#include <typeinfo>
+#include <unordered_map>
+#include <unordered_set>
 #include <utility>
 
 using std::string;
@@ -18,9 +28,110 @@ View::View(std::string id)
 {
 }
 
-View::~View() { remove_event_handlers(); }
+// Views waiting for their first flush to be marked as mounted
+static std::unordered_set<View*> pending_mounts;
 
-void View::on(std::string type, std::string selector, Handler handler)
+// Rendered views by ID
+static std::unordered_map<std::string, View*> registered_ids;
+
+// Mounted views receiving on_frame() calls with the timestamp of their last
+// call or -1, if none yet
+static std::unordered_map<View*, double> frame_views;
+
+static DuplicateIDs duplicate_ids = DuplicateIDs::log;
+
+void on_duplicate_ids(DuplicateIDs mode) { duplicate_ids = mode; }
+
+const std::unordered_map<std::string, View*>& rendered_views()
+{
+    return registered_ids;
+}
+
+// Register a rendered view's ID and report any duplicates
+static void register_id(View* v)
+{
+    if (duplicate_ids != DuplicateIDs::ignore && registered_ids.count(v->id)) {
+        auto other = registered_ids.at(v->id);
+        if (other != v) {
+            BH_ERROR("duplicate view ID: ", v->id, " (", typeid(*other).name(),
+                " and ", typeid(*v).name(), ')');
+            if (duplicate_ids == DuplicateIDs::raise) {
+                throw Error("duplicate view ID", v->id);
+            }
+        }
+    }
+    registered_ids[v->id] = v;
+}
+
+static void unregister_id(View* v)
+{
+    auto it = registered_ids.find(v->id);
+    if (it != registered_ids.end() && it->second == v) {
+        registered_ids.erase(it);
+    }
+}
+
+View::~View()
+{
+    unregister_id(this);
+    pending_mounts.erase(this);
+    frame_views.erase(this);
+    debug::forget_renders(id);
+    forget_consumer(this);
+    remove_event_handlers();
+}
+
+void View::schedule_mount()
+{
+    register_id(this);
+    if (pending_mounts.empty()) {
+        defer([]() {
+            // on_mount() may insert more views
+            auto views = std::move(pending_mounts);
+            pending_mounts.clear();
+            for (auto v : views) {
+                v->mount();
+            }
+        });
+    }
+    pending_mounts.insert(this);
+}
+
+void View::mount()
+{
+    perf::Measure m("mount", id);
+    mounted = true;
+    if (autofocus != "") {
+        focus(autofocus);
+    }
+    if (scroll_on_mount) {
+        scroll_into_view(*scroll_on_mount);
+    }
+    if (wants_frames) {
+        frame_views[this] = -1;
+    }
+    on_mount();
+}
+
+void View::unmount()
+{
+    unregister_id(this);
+    pending_mounts.erase(this);
+    frame_views.erase(this);
+    debug::forget_renders(id);
+    if (mounted) {
+        mounted = false;
+        auto hooks = std::move(unmount_hooks);
+        unmount_hooks.clear();
+        for (auto& fn : hooks) {
+            fn();
+        }
+        on_unmount();
+    }
+}
+
+void View::on(std::string type, std::string selector, Handler handler,
+    ListenerOptions opts)
 {
     // Need to prepend root node ID to all selectors
     Rope s;
@@ -56,19 +167,84 @@ void View::on(std::string type, std::string selector, Handler handler)
         s << '#' << id << " *";
     }
 
-    event_handlers.push_back(register_handler(type, handler, s.str()));
+    event_handlers.push_back(register_handler(type, handler, s.str(), opts));
+}
+
+void View::request_frames()
+{
+    wants_frames = true;
+    if (mounted && !frame_views.count(this)) {
+        frame_views[this] = -1;
+    }
+}
+
+void View::cancel_frames()
+{
+    wants_frames = false;
+    frame_views.erase(this);
+}
+
+void run_frame_hooks()
+{
+    if (frame_views.empty()) {
+        return;
+    }
+    const double now = emscripten_get_now();
+
+    // Hooks might request or cancel frames or destroy views
+    std::vector<View*> views;
+    views.reserve(frame_views.size());
+    for (auto & [ v, _ ] : frame_views) {
+        views.push_back(v);
+    }
+    for (auto v : views) {
+        auto it = frame_views.find(v);
+        if (it == frame_views.end()) {
+            continue;
+        }
+        const double dt = it->second < 0 ? 0 : now - it->second;
+        it->second = now;
+        v->on_frame(now, dt);
+    }
 }
 
 emscripten::val View::el()
 {
     using emscripten::val;
 
-    return val::global("document").call<val>("getElementById", id);
+    return val::global("window").call<val>("__bh_get", id);
 }
 
-void View::scroll_into_view() { brunhild::scroll_into_view(id); }
+void View::scroll_into_view(ScrollOptions opts)
+{
+    brunhild::scroll_into_view(id, opts);
+}
 
-void View::remove() { brunhild::remove(id); }
+void View::remove()
+{
+    unmount();
+    brunhild::remove(id);
+}
+
+std::optional<Error> View::try_patch()
+{
+    auto snapshot = save_mutations();
+    auto err = try_run([this]() { patch(); });
+    if (err) {
+        restore_mutations(snapshot);
+    }
+    return err;
+}
+
+std::optional<Error> View::try_html(std::string& out)
+{
+    auto snapshot = save_mutations();
+    auto err = try_run([&]() { out = html(); });
+    if (err) {
+        restore_mutations(snapshot);
+    }
+    return err;
+}
 
 void View::remove_event_handlers()
 {
@@ -93,22 +269,140 @@ void VirtualView::write_html(Rope& s)
     if (!is_initialized) {
         init();
         is_initialized = true;
+        schedule_mount();
     }
     saved.write_html(s);
 }
 
+void VirtualView::mount()
+{
+    saved.apply_props();
+    if (watch_mode) {
+        start_watchdog();
+    }
+    View::mount();
+}
+
+Node VirtualView::render_root(const Node* old)
+{
+    debug::count_render(id, typeid(*this).name());
+    Node node;
+    {
+        perf::Sample s(perf::Phase::render, typeid(*this));
+        node = render();
+    }
+    validate(node, id);
+    perf::check_render(id, typeid(*this), node, old);
+    node.attrs["id"] = id;
+    debug::stamp_type(node.attrs, typeid(*this));
+    return node;
+}
+
 void VirtualView::init()
 {
-    saved = render();
-    saved.attrs["id"] = id;
+    perf::Measure m("render", id);
+    saved = render_root(nullptr);
     ensure_id(saved);
 }
 
 void VirtualView::patch()
 {
-    auto node = render();
-    node.attrs["id"] = id;
-    patch_node(saved, std::move(node));
+    perf::Measure m("diff", id);
+    auto node = render_root(&saved);
+    perf::Sample s(perf::Phase::diff, typeid(*this));
+    diff(std::move(node));
+}
+
+std::optional<Error> VirtualView::try_patch()
+{
+    auto old = saved;
+    auto err = View::try_patch();
+    if (err) {
+        saved = std::move(old);
+    }
+    return err;
+}
+
+TreeStats VirtualView::stats() const
+{
+    auto st = saved.stats();
+    st.patched = patched_nodes;
+    return st;
+}
+
+TreeStats tree_stats()
+{
+    TreeStats st;
+    for (auto & [ _, view ] : rendered_views()) {
+        auto v = dynamic_cast<const VirtualView*>(view);
+        if (v && v->is_mounted()) {
+            st += v->stats();
+        }
+    }
+    return st;
+}
+
+bool VirtualView::add_class(const string& id, const string& name)
+{
+    auto n = saved.find(id);
+    if (!n) {
+        return false;
+    }
+    if (!n->has_class(name)) {
+        n->add_class(name);
+        brunhild::add_class(id, name);
+    }
+    return true;
+}
+
+bool VirtualView::remove_class(const string& id, const string& name)
+{
+    auto n = saved.find(id);
+    if (!n) {
+        return false;
+    }
+    if (n->has_class(name)) {
+        n->remove_class(name);
+        brunhild::remove_class(id, name);
+    }
+    return true;
+}
+
+bool VirtualView::toggle_class(const string& id, const string& name)
+{
+    auto n = saved.find(id);
+    if (!n) {
+        return false;
+    }
+    if (n->has_class(name)) {
+        return remove_class(id, name);
+    }
+    return add_class(id, name);
+}
+
+bool VirtualView::set_data(
+    const string& id, const string& key, const string& val)
+{
+    auto n = saved.find(id);
+    if (!n) {
+        return false;
+    }
+    const auto name = data_attr(key);
+    if (!n->attrs.count(name) || n->attrs.at(name) != val) {
+        n->attrs[name] = val;
+        set_attr(id, name, val);
+    }
+    return true;
+}
+
+// Returns the number of nodes in a subtree
+static size_t count_nodes(const Node& node)
+{
+    size_t n = 1;
+    for (auto& ch : node.children) {
+        n += count_nodes(ch);
+    }
+    return n;
 }
 
 void VirtualView::patch_node(Node& old, Node&& node)
@@ -122,14 +416,20 @@ void VirtualView::patch_node(Node& old, Node&& node)
         old = std::move(node);
         ensure_id(old);
         set_outer_html(old_id, old.html());
+        old.apply_props();
+        patched_nodes += count_nodes(old);
         return;
     }
 
-    old.attrs.patch(std::move(node.attrs));
-    patch_children(old, std::move(node));
+    bool patched = old.attrs.patch(std::move(node.attrs));
+    old.patch_props(std::move(node.props));
+    patched |= patch_children(old, std::move(node));
+    if (patched) {
+        patched_nodes++;
+    }
 }
 
-void VirtualView::patch_children(Node& old, Node&& node)
+bool VirtualView::patch_children(Node& old, Node&& node)
 {
     // HTML string contents can not be addressed by ID and require special
     // handling
@@ -139,8 +439,9 @@ void VirtualView::patch_children(Node& old, Node&& node)
             if (*old.inner_html != *node.inner_html) {
                 set_inner_html(old.attrs.at("id"), *node.inner_html);
                 old.inner_html = move(node.inner_html);
+                return true;
             }
-            return;
+            return false;
         }
 
         Rope s;
@@ -151,12 +452,16 @@ void VirtualView::patch_children(Node& old, Node&& node)
         old.children = move(node.children);
         old.inner_html = std::nullopt;
         set_inner_html(old.attrs.at("id"), s.str());
-        return;
+        for (auto& ch : old.children) {
+            ch.apply_props();
+            patched_nodes += count_nodes(ch);
+        }
+        return true;
     } else if (node.inner_html) {
         set_inner_html(old.attrs.at("id"), *node.inner_html);
         old.children.clear();
         old.inner_html = move(node.inner_html);
-        return;
+        return true;
     }
 
     // Diff existing nodes
@@ -166,12 +471,15 @@ void VirtualView::patch_children(Node& old, Node&& node)
     }
 
     int diff = int(node.children.size()) - int(old.children.size());
+    const bool resized = diff != 0;
     if (diff > 0) { // Append Nodes
         size_t i = old.children.size();
         while (i < node.children.size()) {
             auto& ch = node.children[i++];
             ensure_id(ch);
             append(old.attrs.at("id"), ch.html());
+            ch.apply_props();
+            patched_nodes += count_nodes(ch);
             old.children.push_back(std::move(ch));
         }
     } else { // Remove Nodes from the end
@@ -180,5 +488,6 @@ void VirtualView::patch_children(Node& old, Node&& node)
             old.children.pop_back();
         }
     }
+    return resized;
 }
 }
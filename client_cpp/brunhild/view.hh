@@ -1,18 +1,64 @@
 #pragma once
 
+#include "error.hh"
+#include "debug.hh"
 #include "events.hh"
+#include "flip.hh"
 #include "mutations.hh"
 #include "node.hh"
+#include "perf.hh"
+#include "ref.hh"
+#include "transition.hh"
+#include "validate.hh"
+#include <algorithm>
 #include <emscripten.h>
 #include <emscripten/val.h>
+#include <functional>
 #include <memory>
+#include <optional>
 #include <string>
+#include <type_traits>
 #include <unordered_map>
 #include <unordered_set>
 #include <vector>
 
 namespace brunhild {
 
+// Handling of duplicate view IDs detected, when a view is first rendered
+enum class DuplicateIDs {
+    ignore, // Do nothing
+    log, // Log both offending views to the console
+    raise, // Log both offending views to the console and throw
+};
+
+// Set handling of duplicate view IDs. Defaults to DuplicateIDs::log.
+void on_duplicate_ids(DuplicateIDs);
+
+// Reconciliation of DOM mutations not made by brunhild with the saved state
+// of a VirtualView
+enum class ForeignMutations {
+    // Update the saved subtree of the mutated element to match the DOM.
+    // Mutated children are kept as opaque inner HTML, that is replaced
+    // entirely on the next change.
+    resync,
+
+    // Rerender the mutated element from the saved subtree, reverting the
+    // foreign mutation
+    rerender,
+};
+
+class View;
+
+// Returns all views rendered at least once and not yet removed by ID
+const std::unordered_map<std::string, View*>& rendered_views();
+
+// Returns the merged statistics of the subtrees of all mounted VirtualViews
+TreeStats tree_stats();
+
+// Call View::on_frame() of all mounted views, that requested frames. Called by
+// the render loop on each frame.
+void run_frame_hooks();
+
 // Base class for views.
 // You are not required to use this class for structureing your applications and
 // can freely build your own abstractions on top of the functions in
@@ -37,26 +83,106 @@ public:
     // type: DOM event type (click, hover, ...)
     // selector: any CSS selector the event target should be matched against
     // handler: handler for a matched event
-    void on(std::string type, std::string selector, Handler handler);
+    // opts: options of the listener
+    void on(std::string type, std::string selector, Handler handler,
+        ListenerOptions opts = {});
 
     // Removes the View from the DOM
     virtual void remove();
 
     // Scroll the root element of View into the viewport
-    void scroll_into_view();
+    void scroll_into_view(ScrollOptions opts = {});
+
+    // Returns a handle for reading the root element's geometry and style
+    Ref ref() const { return Ref(id); }
 
     // Patch the view's subtree against the updated subtree.
     // Can only be called after the view has been inserted into the DOM.
     virtual void patch() = 0;
 
+    // Like patch(), but returns any error raised instead of propagating it.
+    // DOM mutations buffered by the failed patch are discarded. VirtualView
+    // also rolls back its saved subtree. Child views of other views might
+    // already have been patched, so the view should be rerendered completely
+    // after an error. See try_run() for caveats.
+    virtual std::optional<Error> try_patch();
+
+    // Like html(), but returns any error raised instead of propagating it.
+    // DOM mutations buffered by the failed render are discarded. See try_run()
+    // for caveats.
+    std::optional<Error> try_html(std::string& out);
+
+    // Called once after the view has been inserted into the DOM and the
+    // mutations inserting it have been flushed
+    virtual void on_mount() {}
+
+    // Called, when the view is removed from the DOM
+    virtual void on_unmount() {}
+
+    // Called on each animation frame before DOM mutations are flushed, while
+    // the view is mounted and has requested frames with request_frames(), so
+    // any mutations are applied in the same frame.
+    // timestamp: current time in milliseconds as returned by
+    // performance.now()
+    // dt: milliseconds since the previous call or 0 on the first call
+    virtual void on_frame(double timestamp, double dt) {}
+
+    // Returns, if the view is currently inserted into the DOM
+    bool is_mounted() const { return mounted; }
+
+    // Run fn once, the next time the view is unmounted. Useful for releasing
+    // resources tied to the view's elements, like observers.
+    void on_next_unmount(std::function<void()> fn)
+    {
+        unmount_hooks.push_back(fn);
+    }
+
 protected:
+    // ID of an element to focus after the view is mounted. Set to the view's
+    // own ID to focus the root element. Empty for none.
+    std::string autofocus;
+
+    // If set, the view's root element is scrolled into the viewport with these
+    // options after the view is mounted
+    std::optional<ScrollOptions> scroll_on_mount;
+
     // Returns the root element of the view
     emscripten::val el();
 
+    // Schedule mount() to be called after the next flush of DOM mutations.
+    // Must be called by subclasses, when the view's HTML is first rendered.
+    void schedule_mount();
+
+    // Mark the view as inserted into the DOM and call on_mount()
+    virtual void mount();
+
+    // Mark the view and any child views as removed from the DOM and call
+    // on_unmount()
+    virtual void unmount();
+
+    // Call unmount() on a child view, that is removed together with this view
+    static void unmount_child(View& v) { v.unmount(); }
+
+    // Start calling on_frame() on each frame, while the view is mounted.
+    // Persists across remounts until cancel_frames() is called.
+    void request_frames();
+
+    // Stop calling on_frame()
+    void cancel_frames();
+
 private:
+    // View has been inserted into the DOM
+    bool mounted = false;
+
+    // View has requested on_frame() calls
+    bool wants_frames = false;
+
     // Registered DOM event handlers
     std::vector<long> event_handlers;
 
+    // Functions to run on the next unmount
+    std::vector<std::function<void()>> unmount_hooks;
+
     void remove_event_handlers();
 };
 
@@ -81,6 +207,48 @@ public:
     // Can only be called after the view has been inserted into the DOM.
     virtual void patch();
 
+    // Like View::try_patch(), but also restores the saved subtree on error
+    std::optional<Error> try_patch();
+
+    // Add a class to an element in the view's subtree without re-rendering the
+    // view. The saved subtree is kept in sync, so the next patch() only
+    // removes the class again, if render() does not produce it.
+    // Returns false, if no element with the ID exists in the subtree.
+    bool add_class(const std::string& id, const std::string& name);
+
+    // Same as add_class(), but removes a class
+    bool remove_class(const std::string& id, const std::string& name);
+
+    // Same as add_class(), but adds the class, if absent, and removes it
+    // otherwise
+    bool toggle_class(const std::string& id, const std::string& name);
+
+    // Set a data-* attribute on an element in the view's subtree without
+    // re-rendering the view. key can be in either camelCase or kebab-case.
+    // Same caveats as add_class() apply.
+    bool set_data(
+        const std::string& id, const std::string& key, const std::string& val);
+
+    // Numeric overload of set_data()
+    template <class T>
+    std::enable_if_t<std::is_arithmetic_v<T>, bool> set_data(
+        const std::string& id, const std::string& key, T val)
+    {
+        return set_data(id, key, std::to_string(val));
+    }
+
+    // Returns statistics of the saved subtree and the number of nodes patched
+    // by the last patch() call
+    TreeStats stats() const;
+
+    // Watch the view's subtree for DOM mutations not made by brunhild, like
+    // by browser extensions, and reconcile the saved subtree with them.
+    // Watching starts, when the view is mounted, and stops on unmount.
+    void watch_foreign_mutations(ForeignMutations mode);
+
+    // Stop watching for foreign DOM mutations
+    void unwatch_foreign_mutations();
+
     // Creates a new View with an optional specific root node ID.
     VirtualView(std::string id = new_id())
         : View(id)
@@ -91,6 +259,9 @@ protected:
     // Initialize view with subtree
     virtual void init();
 
+    // Set element properties of the subtree and mark the view as mounted
+    virtual void mount();
+
     // Contains data about the state of the DOM subtree after the last patch
     // call
     Node saved;
@@ -98,14 +269,41 @@ protected:
     // Ensure the Node and it's subtree all have element IDs defined
     void ensure_id(Node&);
 
+    // Call render(), validate the result and set the root node's ID.
+    // old: previously rendered subtree or NULL on the initial render
+    Node render_root(const Node* old);
+
+    // Patch the saved subtree against an already rendered node and generate
+    // DOM mutations
+    void diff(Node&& node)
+    {
+        patched_nodes = 0;
+        patch_node(saved, std::move(node));
+    }
+
 private:
     bool is_initialized = false;
 
+    // Number of nodes patched by the last diff
+    size_t patched_nodes = 0;
+
+    // Reconciliation mode of foreign DOM mutations, if watched
+    std::optional<ForeignMutations> watch_mode;
+
+    // Start the MutationObserver
+    void start_watchdog();
+
+    // Reconcile a foreign mutation of an element in the subtree
+    void reconcile(const std::string& id, bool children);
+
+    friend void run_watchdog_handler(std::string, std::string, bool);
+
     // Patch an old node against the new one and generate DOM mutations
     void patch_node(Node& old, Node&& node);
 
-    // Patch element's subtree
-    void patch_children(Node& old, Node&& node);
+    // Patch element's subtree. Returns, if the element's contents were
+    // patched.
+    bool patch_children(Node& old, Node&& node);
 };
 
 // Simple constant view that renders a Node with its subtree
@@ -118,11 +316,38 @@ public:
 protected:
     void init()
     {
+        validate(saved, id);
         saved.attrs["id"] = id;
+        debug::stamp_type(saved.attrs, typeid(*this));
         ensure_id(saved);
     }
 };
 
+// View rendering the Node returned by a function. Removes the need to define a
+// VirtualView subclass for trivial fragments.
+class FnView : public VirtualView {
+public:
+    // Function rendering the root node and its subtree
+    typedef std::function<Node()> Render;
+
+    FnView(Render fn, std::string id = new_id())
+        : VirtualView(id)
+        , fn(fn)
+    {
+    }
+
+    Node render() { return fn(); }
+
+private:
+    const Render fn;
+};
+
+// Shorthand for creating a FnView
+inline std::shared_ptr<FnView> component(FnView::Render fn)
+{
+    return std::make_shared<FnView>(fn);
+}
+
 // Utility adapter for the MV* pattern
 template <class M> class ModelView : public VirtualView {
 public:
@@ -136,7 +361,7 @@ public:
                     console.error('model missing on view: ' + UTF8ToString($0));
                 },
                 id.data());
-            throw "model missing";
+            throw Error("model missing", id);
         }
         return render(m);
     }
@@ -155,6 +380,51 @@ protected:
     virtual Node render(M*) = 0;
 };
 
+// VirtualView, that is only re-rendered on patch, when the props it is rendered
+// from have changed since the last render. Useful for views with expensive
+// rendering and mostly static inputs.
+// P: copyable and equality comparable render inputs
+template <class P> class PropsView : public VirtualView {
+public:
+    using VirtualView::VirtualView;
+
+    Node render() final
+    {
+        if (!last) {
+            last = props();
+        }
+        return render(*last);
+    }
+
+    // Patch the view's subtree, if props have changed since the last render
+    void patch()
+    {
+        std::optional<P> p;
+        {
+            perf::Sample s(perf::Phase::state, typeid(*this));
+            p = props();
+        }
+        if (last && *last == *p) {
+            return;
+        }
+        last = std::move(p);
+        VirtualView::patch();
+    }
+
+protected:
+    // Returns the current render inputs of the view
+    virtual P props() = 0;
+
+    // Render the root node and its subtree from props.
+    // The "id" attribute on the root node is ignored and is always set to
+    // View::id.
+    virtual Node render(const P&) = 0;
+
+private:
+    // Props of the last render
+    std::optional<P> last;
+};
+
 // Common functionality of all parent views
 template <class V = View> class ParentView : public View {
 public:
@@ -174,6 +444,7 @@ public:
         if (!is_initialized) {
             init();
             is_initialized = true;
+            View::schedule_mount();
         }
 
         s << '<' << tag;
@@ -181,6 +452,7 @@ public:
         s << '>';
         for (auto v : saved) {
             v->write_html(s);
+            s.checkpoint();
         }
         s << "</" << tag << '>';
     }
@@ -198,7 +470,26 @@ protected:
     virtual void init()
     {
         saved_attrs = attrs();
+        validate_attrs(saved_attrs, id);
         saved_attrs["id"] = id;
+        debug::stamp_type(saved_attrs, typeid(*this));
+    }
+
+    // Diff attributes with their new values and apply patches to the DOM
+    void patch_attrs()
+    {
+        auto new_attrs = attrs();
+        validate_attrs(new_attrs, id);
+        debug::stamp_type(new_attrs, typeid(*this));
+        saved_attrs.patch(std::move(new_attrs));
+    }
+
+    virtual void unmount()
+    {
+        for (auto& v : saved) {
+            View::unmount_child(*v);
+        }
+        View::unmount();
     }
 
 private:
@@ -217,7 +508,13 @@ template <class M, class V> class ListView : public ParentView<V> {
 public:
     virtual void init()
     {
-        for (auto m : get_list()) {
+        auto list = get_list();
+        if (chunk_size && list.size() > chunk_size) {
+            pending.assign(list.begin() + chunk_size, list.end());
+            list.resize(chunk_size);
+            schedule_chunk();
+        }
+        for (auto m : list) {
             saved.push_back(create_child(m));
         }
         ParentView<V>::init();
@@ -228,9 +525,18 @@ public:
     // deep: should patching recurse to the view's child views
     void patch()
     {
-        saved_attrs.patch(attrs());
+        perf::Measure m("diff", View::id);
+        ParentView<V>::patch_attrs();
+
+        // Children not yet rendered by chunks are appended by the diff
+        pending.clear();
 
-        const auto new_list = get_list();
+        std::vector<M*> new_list;
+        {
+            perf::Sample s(perf::Phase::state, typeid(*this));
+            new_list = get_list();
+        }
+        perf::Sample s(perf::Phase::diff, typeid(*this));
         const auto new_set
             = std::unordered_set<M*>(new_list.begin(), new_list.end());
         std::unordered_map<M*, std::shared_ptr<V>> saved_set;
@@ -248,11 +554,21 @@ public:
                 it++;
             } else {
                 // Get rid of views without models
-                v->remove();
+                remove_child(v);
                 it = saved.erase(it);
             }
         }
 
+        // Record children before any moves for FLIP animations
+        std::vector<std::string> flip_ids;
+        bool moved = false;
+        if (flip) {
+            flip_ids.reserve(saved.size());
+            for (auto& v : saved) {
+                flip_ids.push_back(v->id);
+            }
+        }
+
         // Diff and reorder views in the overlaping range
         for (size_t i = 0; i < saved_list.size() && i < new_list.size(); i++) {
             auto m = new_list[i];
@@ -270,6 +586,7 @@ public:
                 } else {
                     move_after(saved[i - 1]->id, v->id);
                 }
+                moved = true;
                 saved_set[m]->patch();
                 saved_set.erase(m);
             } else {
@@ -277,8 +594,9 @@ public:
                 if (!i) {
                     prepend(View::id, v->html());
                 } else {
-                    after(View::id, v->html());
+                    after(saved[i - 1]->id, v->html());
                 }
+                enter_child(*v);
             }
             saved[i] = v;
         }
@@ -286,24 +604,102 @@ public:
         if (saved.size() > new_list.size()) {
             // Remove all unused old views
             for (auto& p : saved_set) {
-                p.second->remove();
+                remove_child(p.second);
             }
             saved.resize(new_list.size());
         } else {
             // Append all missing views
-            for (size_t i = saved.size() - 1; i < new_list.size(); i++) {
-                append(View::id,
-                    saved.emplace_back(create_child(new_list[i]))->html());
+            for (size_t i = saved.size(); i < new_list.size(); i++) {
+                auto& v = saved.emplace_back(create_child(new_list[i]));
+                append(View::id, v->html());
+                enter_child(*v);
             }
         }
+
+        if (flip && moved) {
+            brunhild::flip(flip_ids, *flip);
+        }
     }
 
 protected:
+    // If set, children moved by patch() are animated to their new positions
+    std::optional<FlipOptions> flip;
+
+    // CSS classes to apply to children created and removed by patch()
+    std::optional<Transition> transition;
+
+    // If not 0, only the first chunk_size children are rendered initially and
+    // the rest are appended in chunks of this size, one chunk per frame, to
+    // avoid blocking the page on rendering very large lists. Calling patch()
+    // renders any remaining children at once.
+    size_t chunk_size = 0;
+
     // Returns an ordered list of models to be used to render view contents
     virtual std::vector<M*> get_list() = 0;
 
     // Create a new instance of a child view
     virtual std::shared_ptr<V> create_child(M*) = 0;
+
+    virtual void unmount()
+    {
+        pending.clear();
+        ParentView<V>::unmount();
+    }
+
+private:
+    // Models of children not yet rendered by chunks
+    std::vector<M*> pending;
+
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Append the next chunk of children after the next flush
+    void schedule_chunk()
+    {
+        defer([this, alive = std::weak_ptr<bool>(alive)]() {
+            if (alive.lock()) {
+                render_chunk();
+            }
+        });
+    }
+
+    void render_chunk()
+    {
+        if (pending.empty()) {
+            return;
+        }
+        const auto n = std::min(chunk_size, pending.size());
+        Rope s;
+        for (size_t i = 0; i < n; i++) {
+            auto& v = saved.emplace_back(create_child(pending[i]));
+            v->write_html(s);
+        }
+        pending.erase(pending.begin(), pending.begin() + n);
+        append(View::id, s.str());
+        if (pending.size()) {
+            schedule_chunk();
+        }
+    }
+
+    // Apply the enter transition to a newly inserted child
+    void enter_child(V& v)
+    {
+        if (transition) {
+            transition_enter(v.id, transition->enter);
+        }
+    }
+
+    // Remove a child, after its exit transition
+    void remove_child(std::shared_ptr<V> v)
+    {
+        if (!transition || transition->exit == "") {
+            v->remove();
+            return;
+        }
+        View::unmount_child(*v);
+
+        // Keep the view alive until the element is removed
+        transition_exit(v->id, transition->exit, [v]() {});
+    }
 };
 
 // Combines multiple views as its children. The list and order of the child
@@ -320,7 +716,8 @@ public:
     // deep: should patching recurse to the view's child views
     void patch()
     {
-        saved_attrs.patch(attrs());
+        perf::Measure m("diff", View::id);
+        ParentView<V>::patch_attrs();
         for (auto& v : saved) {
             v->patch();
         }
@@ -0,0 +1,83 @@
+#include "virtual_list.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+
+using std::string;
+
+namespace brunhild {
+
+// All mounted virtual lists by ID
+static std::unordered_map<string, VirtualListBase*> lists;
+
+VirtualListBase::VirtualListBase(string tag, string id)
+    : View(id)
+    , tag(tag)
+    , top_id(new_id())
+    , bottom_id(new_id())
+{
+}
+
+VirtualListBase::~VirtualListBase() { lists.erase(id); }
+
+void VirtualListBase::listen()
+{
+    lists[id] = this;
+    EM_ASM_INT(
+        {
+            var id = UTF8ToString($0);
+            var el = window.__bh_get(id);
+            if (!el) {
+                return;
+            }
+            var frame = 0;
+            var report = function()
+            {
+                frame = 0;
+                Module._bh_virtual_list_scroll(
+                    id, el.scrollTop, el.clientHeight);
+            };
+            var fn = function()
+            {
+                if (!frame) {
+                    frame = requestAnimationFrame(report);
+                }
+            };
+            el.__bh_scroll = fn;
+            el.addEventListener("scroll", fn, { passive : true });
+            window.addEventListener("resize", fn, { passive : true });
+            report();
+        },
+        id.c_str());
+}
+
+void VirtualListBase::unlisten()
+{
+    lists.erase(id);
+    EM_ASM_INT(
+        {
+            var el = window.__bh_get(UTF8ToString($0));
+            if (el && el.__bh_scroll) {
+                el.removeEventListener("scroll", el.__bh_scroll);
+                window.removeEventListener("resize", el.__bh_scroll);
+                delete el.__bh_scroll;
+            }
+        },
+        id.c_str());
+}
+
+void run_virtual_list_handler(string id, double scroll_top, double height)
+{
+    if (!lists.count(id)) {
+        return;
+    }
+    auto l = lists.at(id);
+    l->scroll_top = scroll_top;
+    l->client_height = height;
+    l->on_scroll();
+}
+
+EMSCRIPTEN_BINDINGS(module_virtual_list)
+{
+    emscripten::function("_bh_virtual_list_scroll", &run_virtual_list_handler);
+}
+}
@@ -0,0 +1,346 @@
+#pragma once
+
+#include "mutations.hh"
+#include "perf.hh"
+#include "ref.hh"
+#include "view.hh"
+#include "window.hh"
+#include <algorithm>
+#include <memory>
+#include <string>
+#include <unordered_map>
+#include <unordered_set>
+#include <utility>
+#include <vector>
+
+namespace brunhild {
+
+// Non-template state of VirtualList
+class VirtualListBase : public View {
+public:
+    // Tag of root node
+    const std::string tag;
+
+    VirtualListBase(std::string tag, std::string id);
+    ~VirtualListBase();
+
+protected:
+    // IDs of the spacer elements above and below the rendered children
+    const std::string top_id, bottom_id;
+
+    // Last known scroll offset and height of the root element
+    double scroll_top = 0, client_height = 0;
+
+    // Start listening to scroll events of the root element
+    void listen();
+
+    // Stop listening to scroll events of the root element
+    void unlisten();
+
+    // Called, when the root element has been scrolled or resized
+    virtual void on_scroll() = 0;
+
+    friend void run_virtual_list_handler(std::string, double, double);
+};
+
+// Renders only the visible slice of a large list of models using a delegator
+// method, like ListView. Children out of view are replaced with spacer
+// elements, so the scrollbar keeps the size of the full list.
+// The root element must be a scroll container with a constrained height, as
+// set through attrs() or CSS.
+// M: model
+// V: ModelView<M>
+template <class M, class V> class VirtualList : public VirtualListBase {
+public:
+    // item_height: fixed height of each child in CSS pixels or the estimated
+    // height, if measure is set
+    // measure: measure the actual height of children after rendering
+    // overscan: number of extra children to render above and below the
+    // visible slice
+    VirtualList(double item_height, bool measure = false, unsigned overscan = 5,
+        std::string tag = "div", std::string id = new_id())
+        : VirtualListBase(tag, id)
+        , item_height(item_height)
+        , measure(measure)
+        , overscan(overscan)
+    {
+    }
+
+    ~VirtualList() { *alive = false; }
+
+    // Same as html(), but writes to a stream to reduce allocations
+    void write_html(Rope& s)
+    {
+        if (!is_initialized) {
+            init();
+            is_initialized = true;
+            View::schedule_mount();
+        }
+
+        s << '<' << tag;
+        saved_attrs.write_html(s);
+        s << "><div id=\"" << top_id << "\" style=\"height:" << top_height
+          << "px\"></div>";
+        for (auto & [ _, v ] : rendered) {
+            v->write_html(s);
+        }
+        s << "<div id=\"" << bottom_id << "\" style=\"height:" << bottom_height
+          << "px\"></div></" << tag << '>';
+    }
+
+    // Patches the attributes of the root element, refetches the model list
+    // and rerenders the visible slice
+    void patch()
+    {
+        perf::Measure m("diff", View::id);
+        auto new_attrs = attrs();
+        validate_attrs(new_attrs, View::id);
+        new_attrs["id"] = View::id;
+        debug::stamp_type(new_attrs, typeid(*this));
+        saved_attrs.patch(std::move(new_attrs));
+
+        {
+            perf::Sample s(perf::Phase::state, typeid(*this));
+            list = get_list();
+        }
+        perf::Sample s(perf::Phase::diff, typeid(*this));
+        update(true);
+    }
+
+    // Scroll the root element to show the child at index i at the top
+    void scroll_to_index(size_t i)
+    {
+        if (i >= list.size()) {
+            return;
+        }
+        compute_offsets();
+        Ref(View::id).set_scroll_top(offsets[i]);
+    }
+
+protected:
+    // Returns the attributes of the root element
+    virtual Attrs attrs() { return {}; };
+
+    // Returns an ordered list of models to be used to render view contents
+    virtual std::vector<M*> get_list() = 0;
+
+    // Create a new instance of a child view
+    virtual std::shared_ptr<V> create_child(M*) = 0;
+
+    void mount()
+    {
+        View::mount();
+        listen();
+        if (measure) {
+            measure_rendered();
+        }
+    }
+
+    void unmount()
+    {
+        unlisten();
+        for (auto & [ _, v ] : rendered) {
+            View::unmount_child(*v);
+        }
+        View::unmount();
+    }
+
+    void on_scroll()
+    {
+        perf::Sample s(perf::Phase::diff, typeid(*this));
+        update(false);
+    }
+
+private:
+    const double item_height;
+    const bool measure;
+    const unsigned overscan;
+    bool is_initialized = false;
+
+    // Guards deferred functions against running after destruction
+    std::shared_ptr<bool> alive = std::make_shared<bool>(true);
+
+    // Last rendered attributes
+    Attrs saved_attrs;
+
+    // Full list of models
+    std::vector<M*> list;
+
+    // Currently rendered children in list order
+    std::vector<std::pair<M*, std::shared_ptr<V>>> rendered;
+
+    // Measured child heights
+    std::unordered_map<M*, double> heights;
+
+    // Offset of each child from the top of the list. Has one more element
+    // than list, containing the total height.
+    std::vector<double> offsets;
+
+    double top_height = 0, bottom_height = 0;
+
+    void init()
+    {
+        saved_attrs = attrs();
+        validate_attrs(saved_attrs, View::id);
+        saved_attrs["id"] = View::id;
+        debug::stamp_type(saved_attrs, typeid(*this));
+
+        list = get_list();
+
+        // Not inserted yet, so assume the root is as high as the viewport
+        client_height = viewport_size().height;
+        compute_offsets();
+        const auto[first, last] = visible_range();
+        for (size_t i = first; i < last; i++) {
+            rendered.push_back({ list[i], create_child(list[i]) });
+        }
+        top_height = offsets[first];
+        bottom_height = offsets.back() - offsets[last];
+    }
+
+    double height_of(M* m)
+    {
+        if (measure && heights.count(m)) {
+            return heights.at(m);
+        }
+        return item_height;
+    }
+
+    void compute_offsets()
+    {
+        offsets.resize(list.size() + 1);
+        offsets[0] = 0;
+        for (size_t i = 0; i < list.size(); i++) {
+            offsets[i + 1] = offsets[i] + height_of(list[i]);
+        }
+    }
+
+    // Returns the range of children to render including overscan
+    std::pair<size_t, size_t> visible_range()
+    {
+        auto first = std::upper_bound(
+                         offsets.begin(), offsets.end() - 1, scroll_top)
+            - offsets.begin();
+        auto last = std::lower_bound(offsets.begin(), offsets.end() - 1,
+                        scroll_top + client_height)
+            - offsets.begin();
+        if (first) {
+            first--;
+        }
+        first = first > (long)overscan ? first - overscan : 0;
+        return { first, std::min(list.size(), (size_t)last + overscan + 1) };
+    }
+
+    // Rerender the visible slice, reusing any children still in it.
+    // patch_children: patch reused children
+    void update(bool patch_children)
+    {
+        compute_offsets();
+        const auto[first, last] = visible_range();
+        std::unordered_set<M*> new_set(
+            list.begin() + first, list.begin() + last);
+
+        // Remove children no longer in the slice
+        std::unordered_map<M*, std::shared_ptr<V>> existing;
+        std::vector<M*> retained;
+        for (auto & [ m, v ] : rendered) {
+            if (new_set.count(m)) {
+                existing[m] = v;
+                retained.push_back(m);
+            } else {
+                v->remove();
+            }
+        }
+
+        // Scrolling never reorders children. If the models were reordered,
+        // rerender the entire slice instead of moving elements.
+        size_t j = 0;
+        for (size_t i = first; i < last && j < retained.size(); i++) {
+            if (existing.count(list[i])) {
+                if (list[i] != retained[j]) {
+                    break;
+                }
+                j++;
+            }
+        }
+        if (j != retained.size()) {
+            for (auto & [ _, v ] : existing) {
+                v->remove();
+            }
+            existing.clear();
+        }
+
+        // Insert new children in consecutive runs after the preceding element
+        std::vector<std::pair<M*, std::shared_ptr<V>>> next;
+        next.reserve(last - first);
+        std::string prev = top_id;
+        Rope buf;
+        bool buffered = false;
+        bool inserted = false;
+        for (size_t i = first; i < last; i++) {
+            auto m = list[i];
+            if (existing.count(m)) {
+                auto& v = existing.at(m);
+                if (buffered) {
+                    after(prev, buf.str());
+                    buf = Rope();
+                    buffered = false;
+                }
+                if (patch_children) {
+                    v->patch();
+                }
+                prev = v->id;
+                next.push_back({ m, v });
+            } else {
+                auto v = create_child(m);
+                v->write_html(buf);
+                buffered = inserted = true;
+                next.push_back({ m, v });
+            }
+        }
+        if (buffered) {
+            after(prev, buf.str());
+        }
+        rendered = std::move(next);
+
+        set_spacer(top_id, top_height, offsets[first]);
+        set_spacer(bottom_id, bottom_height, offsets.back() - offsets[last]);
+
+        if (measure && inserted) {
+            defer([this, alive = alive]() {
+                if (*alive) {
+                    measure_rendered();
+                }
+            });
+        }
+    }
+
+    void set_spacer(const std::string& id, double& saved, double height)
+    {
+        if (saved != height) {
+            saved = height;
+            set_attr(id, "style", "height:" + std::to_string(height) + "px");
+        }
+    }
+
+    // Measure the heights of all rendered children and adjust the slice, if
+    // they differ from the estimate
+    void measure_rendered()
+    {
+        if (!is_mounted()) {
+            return;
+        }
+        bool changed = false;
+        for (auto & [ m, v ] : rendered) {
+            const auto size = Ref(v->id).offset_size();
+            if (size && (!heights.count(m) || heights.at(m) != size->height)) {
+                heights[m] = size->height;
+                changed = true;
+            }
+        }
+        if (changed) {
+            update(false);
+        }
+    }
+};
+}
@@ -0,0 +1,85 @@
+#include "visibility.hh"
+#include "mutations.hh"
+#include "timer.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <map>
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// Registered handlers by ID
+static std::map<long, std::function<void(bool)>> handlers;
+
+static bool should_pause = true;
+
+// Render loop and timers are currently paused
+static bool paused = false;
+
+bool page_visible()
+{
+    return (bool)EM_ASM_INT({ return document.hidden ? 0 : 1; });
+}
+
+long on_visibility_change(std::function<void(bool)> handler)
+{
+    const long id = id_counter++;
+    handlers[id] = handler;
+    return id;
+}
+
+void remove_visibility_handler(long id) { handlers.erase(id); }
+
+static void pause()
+{
+    if (paused) {
+        return;
+    }
+    paused = true;
+    emscripten_pause_main_loop();
+    pause_timers();
+}
+
+static void resume()
+{
+    if (!paused) {
+        return;
+    }
+    paused = false;
+    resume_timers();
+
+    // Catch up on any changes buffered while hidden
+    flush();
+    emscripten_resume_main_loop();
+}
+
+void pause_when_hidden(bool pause_)
+{
+    should_pause = pause_;
+    if (!should_pause) {
+        resume();
+    } else if (!page_visible()) {
+        pause();
+    }
+}
+
+static void visibility_changed(bool visible)
+{
+    if (visible) {
+        resume();
+    } else if (should_pause) {
+        pause();
+    }
+
+    auto copy = handlers; // Handler might invalidate this iterator
+    for (auto & [ _, h ] : copy) {
+        h(visible);
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_visibility)
+{
+    emscripten::function("_bh_visibility_changed", &visibility_changed);
+}
+}
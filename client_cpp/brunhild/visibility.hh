@@ -0,0 +1,23 @@
+#pragma once
+
+#include <functional>
+
+// Page Visibility API integration. While the page is hidden, the render loop
+// and pausable timers are paused by default. Pending mutations are flushed at
+// once, when the page becomes visible again.
+namespace brunhild {
+
+// Returns, if the page is currently visible
+bool page_visible();
+
+// Register a handler called with the new visibility state, whenever it
+// changes. Returns handler ID.
+long on_visibility_change(std::function<void(bool)> handler);
+
+// Remove a visibility change handler by ID
+void remove_visibility_handler(long id);
+
+// Set, if the render loop and pausable timers should be paused, while the
+// page is hidden. Defaults to true.
+void pause_when_hidden(bool pause);
+}
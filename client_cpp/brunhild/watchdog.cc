@@ -0,0 +1,168 @@
+// Reconciliation of foreign DOM mutations with VirtualView state
+
+#include "view.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+
+using emscripten::val;
+using std::string;
+
+namespace brunhild {
+
+void VirtualView::watch_foreign_mutations(ForeignMutations mode)
+{
+    const bool started = watch_mode.has_value();
+    watch_mode = mode;
+    if (!started && is_mounted()) {
+        start_watchdog();
+    }
+}
+
+void VirtualView::unwatch_foreign_mutations()
+{
+    watch_mode = std::nullopt;
+    EM_ASM_INT(
+        {
+            var s = window.__bh_watchdogs;
+            var id = UTF8ToString($0);
+            if (s && s[id]) {
+                s[id].disconnect();
+                delete s[id];
+            }
+        },
+        id.c_str());
+}
+
+void VirtualView::start_watchdog()
+{
+    EM_ASM_INT(
+        {
+            if (!window.__bh_watchdogs) {
+                window.__bh_watchdogs = {};
+            }
+            var id = UTF8ToString($0);
+            var root = window.__bh_get(id);
+            if (!root || window.__bh_watchdogs[id]) {
+                return;
+            }
+
+            var mo = new MutationObserver(function(records) {
+                // Deduplicate by element and merge mutation types
+                var mutated = {};
+                for (var i = 0; i < records.length; i++) {
+                    var r = records[i];
+                    var t = r.target.nodeType == 1 ? r.target
+                                                   : r.target.parentElement;
+                    var el = t && t.closest ? t.closest("[id]") : null;
+                    if (!el || !root.contains(el)) {
+                        continue;
+                    }
+                    mutated[el.id] = mutated[el.id] || r.type != "attributes";
+                }
+                for (var el_id in mutated) {
+                    Module._bh_foreign_mutation(id, el_id, mutated[el_id]);
+                }
+            });
+            mo.observe(root, {
+                subtree : true,
+                childList : true,
+                attributes : true,
+                characterData : true
+            });
+            window.__bh_watchdogs[id] = mo;
+        },
+        id.c_str());
+
+    on_next_unmount([this]() {
+        if (watch_mode) {
+            const auto mode = *watch_mode;
+            unwatch_foreign_mutations();
+            watch_mode = mode; // Restart on the next mount
+        }
+    });
+}
+
+void VirtualView::reconcile(const string& el_id, bool children)
+{
+    auto n = saved.find(el_id);
+    if (!n) {
+        return;
+    }
+
+    auto el = val::global("__bh_get")(el_id);
+    if (el.isNull()) {
+        return;
+    }
+    Attrs attrs;
+    const auto list = el["attributes"];
+    const unsigned len = list["length"].as<unsigned>();
+    for (unsigned i = 0; i < len; i++) {
+        const auto a = list[i];
+        attrs[a["name"].as<string>()] = a["value"].as<string>();
+    }
+
+    if (*watch_mode == ForeignMutations::rerender) {
+        for (auto & [ key, _ ] : attrs) {
+            if (!n->attrs.count(key)) {
+                remove_attr(el_id, key);
+            }
+        }
+        for (auto & [ key, v ] : n->attrs) {
+            if (!attrs.count(key) || attrs.at(key) != v) {
+                set_attr(el_id, key, v);
+            }
+        }
+        if (children) {
+            Rope s;
+            if (n->inner_html) {
+                s << *n->inner_html;
+            } else {
+                for (auto& ch : n->children) {
+                    ch.write_html(s);
+                }
+            }
+            set_inner_html(el_id, s.str());
+
+            // Reapply properties of the reinserted children
+            defer([view_id = id, el_id]() {
+                auto& views = rendered_views();
+                auto it = views.find(view_id);
+                if (it == views.end()) {
+                    return;
+                }
+                auto v = dynamic_cast<VirtualView*>(it->second);
+                if (auto n = v ? v->saved.find(el_id) : nullptr) {
+                    for (auto& ch : n->children) {
+                        ch.apply_props();
+                    }
+                }
+            });
+        }
+        return;
+    }
+
+    n->attrs = std::move(attrs);
+    if (children) {
+        n->children.clear();
+        n->inner_html = el["innerHTML"].as<string>();
+    }
+}
+
+void run_watchdog_handler(string view_id, string el_id, bool children)
+{
+    auto& views = rendered_views();
+    auto it = views.find(view_id);
+    if (it == views.end()) {
+        return;
+    }
+    auto v = dynamic_cast<VirtualView*>(it->second);
+    if (v && v->watch_mode) {
+        v->reconcile(el_id, children);
+    }
+}
+
+EMSCRIPTEN_BINDINGS(module_watchdog)
+{
+    emscripten::function("_bh_foreign_mutation", &run_watchdog_handler);
+}
+}
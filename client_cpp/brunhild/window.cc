@@ -0,0 +1,109 @@
+#include "window.hh"
+#include <emscripten.h>
+#include <emscripten/bind.h>
+#include <unordered_map>
+
+namespace brunhild {
+
+static long id_counter = 0;
+
+// All active subscriptions by ID
+static std::unordered_map<long, WindowSubscription*> subscriptions;
+
+WindowSubscription::WindowSubscription(
+    const char* type, RateLimit limit, unsigned interval)
+    : sub_id(id_counter++)
+{
+    subscriptions[sub_id] = this;
+    EM_ASM_INT(
+        {
+            if (!window.__bh_window_subs) {
+                window.__bh_window_subs = {};
+            }
+            var type = UTF8ToString($0);
+            var id = $1;
+            var limit = $2;
+            var interval = $3;
+            var sub = { type : type, timer : 0, frame : 0, last : 0 };
+
+            var run = function()
+            {
+                sub.timer = sub.frame = 0;
+                sub.last = Date.now();
+                if (type == "resize") {
+                    Module._bh_window_event(
+                        id, window.innerWidth, window.innerHeight);
+                } else {
+                    Module._bh_window_event(id, window.scrollX, window.scrollY);
+                }
+            };
+
+            sub.fn = function()
+            {
+                switch (limit) {
+                case 0: // Frame
+                    if (!sub.frame) {
+                        sub.frame = requestAnimationFrame(run);
+                    }
+                    break;
+                case 1: // Throttle
+                    if (sub.timer) {
+                        break;
+                    }
+                    var wait = interval - (Date.now() - sub.last);
+                    if (wait <= 0) {
+                        run();
+                    } else {
+                        sub.timer = setTimeout(run, wait);
+                    }
+                    break;
+                case 2: // Debounce
+                    clearTimeout(sub.timer);
+                    sub.timer = setTimeout(run, interval);
+                    break;
+                }
+            };
+            window.__bh_window_subs[id] = sub;
+            window.addEventListener(type, sub.fn, { passive : true });
+        },
+        type, sub_id, (int)limit, interval);
+}
+
+WindowSubscription::~WindowSubscription()
+{
+    subscriptions.erase(sub_id);
+    EM_ASM_INT(
+        {
+            var sub = window.__bh_window_subs[$0];
+            window.removeEventListener(sub.type, sub.fn, { passive : true });
+            clearTimeout(sub.timer);
+            cancelAnimationFrame(sub.frame);
+            delete window.__bh_window_subs[$0];
+        },
+        sub_id);
+}
+
+void run_window_handler(long id, double a, double b)
+{
+    if (subscriptions.count(id)) {
+        subscriptions.at(id)->run(a, b);
+    }
+}
+
+Size viewport_size()
+{
+    return { EM_ASM_DOUBLE({ return window.innerWidth; }),
+        EM_ASM_DOUBLE({ return window.innerHeight; }) };
+}
+
+ScrollOffset scroll_offset()
+{
+    return { EM_ASM_DOUBLE({ return window.scrollX; }),
+        EM_ASM_DOUBLE({ return window.scrollY; }) };
+}
+
+EMSCRIPTEN_BINDINGS(module_window)
+{
+    emscripten::function("_bh_window_event", &run_window_handler);
+}
+}
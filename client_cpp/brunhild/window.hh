@@ -0,0 +1,84 @@
+#pragma once
+
+#include "ref.hh"
+#include <functional>
+
+namespace brunhild {
+
+// Limits the rate of window event handler invocation
+enum class RateLimit {
+    frame, // At most once per animation frame
+    throttle, // At most once per interval, including a trailing call
+    debounce, // Once after no events have fired for the interval
+};
+
+// Scroll offset of the window in CSS pixels
+struct ScrollOffset {
+    double x, y;
+};
+
+// Base class for rate limited window event subscriptions.
+// Unsubscribes on destruction.
+class WindowSubscription {
+public:
+    virtual ~WindowSubscription();
+
+    WindowSubscription(const WindowSubscription&) = delete;
+    WindowSubscription& operator=(const WindowSubscription&) = delete;
+
+protected:
+    // Subscribe to a window event. interval is in milliseconds and ignored
+    // for RateLimit::frame.
+    WindowSubscription(const char* type, RateLimit limit, unsigned interval);
+
+    // Receives current window dimensions or scroll offsets
+    virtual void run(double a, double b) = 0;
+
+private:
+    const long sub_id;
+
+    friend void run_window_handler(long, double, double);
+};
+
+// Subscription to window resizes. Receives the new viewport size from
+// window.innerWidth and window.innerHeight.
+class OnResize : public WindowSubscription {
+public:
+    OnResize(std::function<void(Size)> handler,
+        RateLimit limit = RateLimit::frame, unsigned interval = 100)
+        : WindowSubscription("resize", limit, interval)
+        , handler(handler)
+    {
+    }
+
+protected:
+    void run(double w, double h) { handler({ w, h }); }
+
+private:
+    std::function<void(Size)> handler;
+};
+
+// Subscription to window scrolling. Receives the new scroll offsets from
+// window.scrollX and window.scrollY.
+class OnScroll : public WindowSubscription {
+public:
+    OnScroll(std::function<void(ScrollOffset)> handler,
+        RateLimit limit = RateLimit::frame, unsigned interval = 100)
+        : WindowSubscription("scroll", limit, interval)
+        , handler(handler)
+    {
+    }
+
+protected:
+    void run(double x, double y) { handler({ x, y }); }
+
+private:
+    std::function<void(ScrollOffset)> handler;
+};
+
+// Returns the current viewport size
+Size viewport_size();
+
+// Returns the current window scroll offsets
+ScrollOffset scroll_offset();
+}
@@ -1,3 +1,4 @@
+#include "../../brunhild/history.hh"
 #include "../connection/connection.hh"
 #include "../connection/sync.hh"
 #include "../db.hh"
@@ -12,6 +13,9 @@
 
 void init_navigation()
 {
+    // Return to the same scroll position on back/forward navigation
+    brunhild::history::track_window_scroll();
+
     EM_ASM({
         history.scrollRestoration = 'manual';
 
@@ -79,6 +83,9 @@ static void try_navigate_page(std::string href, bool need_push)
         return;
     }
 
+    // Snapshot UI state of the page navigated away from
+    brunhild::history::save();
+
     // TODO: Reset postform
     page = next_state;
     posts.clear();
@@ -94,8 +101,9 @@ static void try_navigate_page(std::string href, bool need_push)
         = new WaitGroup(2, [ full_href = location_origin + href, need_push ]() {
               render_page();
               if (need_push) {
-                  EM_ASM({ history.pushState(null, null, UTF8ToString($0)); },
-                      full_href.c_str());
+                  brunhild::history::push(full_href);
+              } else {
+                  brunhild::history::restore();
               }
           });
     load_post_ids(wg);
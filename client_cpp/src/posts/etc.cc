@@ -100,7 +100,7 @@ Node render_link(string_view url, string_view text, bool new_tab)
     Node n({
         "a",
         {
-            { "rel", "noreferrer" }, { "href", brunhild::escape(string(url)) },
+            { "rel", "noreferrer" }, { "href", string(url) },
         },
         string(text), true,
     });
@@ -44,10 +44,11 @@ Node PostView::render_figcaption()
     // File name + download link
     auto& ext = file_extentions.at(img.file_type);
     ostringstream name, url;
-    name << escape(img.name) << '.' << ext;
+    name << img.name << '.' << ext;
     url << "/assets/images/src/" << img.sha1 << '.' << ext;
     n.children.push_back({ "a",
-        { { "href", url.str() }, { "download", name.str() } }, name.str() });
+        { { "href", url.str() }, { "download", name.str() } }, name.str(),
+        true });
 
     n.stringify_subtree();
     return n;
@@ -59,7 +59,7 @@ static Node format_noembed(const Provider prov, const string href)
             {
                 "a",
                 {
-                    { "rel", "noreferrer" }, { "href", brunhild::escape(href) },
+                    { "rel", "noreferrer" }, { "href", href },
                     { "class", "embed" }, { "target", "_blank" },
                 },
                 s.str(),
@@ -1,4 +1,5 @@
 #include "state.hh"
+#include "../brunhild/url.hh"
 #include "lang.hh"
 #include "options/options.hh"
 #include "page/page.hh"
@@ -164,18 +165,6 @@ static unsigned parse_uint(const std::string_view s)
     return result;
 }
 
-// Find a numeric query parameter and parse it.
-// Returns 0, if none found.
-static unsigned find_query_param(std::string_view query, const char* param)
-{
-    size_t i = query.find(param);
-    if (i == string::npos) {
-        return 0;
-    }
-    i += strlen(param) + 1;
-    return parse_uint(query.substr(i, query.find_first_of('&', i)));
-}
-
 Page::Page(const string& href)
 {
     const auto i_query = href.find('?');
@@ -204,11 +193,11 @@ Page::Page(const string& href)
 
     // Parse query string
     if (i_query != string::npos) {
-        const auto query = view.substr(i_query, i_hash);
+        const brunhild::url::Query query(view.substr(i_query));
         if (thread) {
-            last_100 = find_query_param(query, "last") == 100;
+            last_100 = query.get<unsigned>("last") == 100u;
         } else if (!catalog) {
-            page = find_query_param(query, "page");
+            page = query.get<unsigned>("page").value_or(0);
         }
     }
 
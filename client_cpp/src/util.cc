@@ -1,4 +1,5 @@
 #include "util.hh"
+#include "../brunhild/url.hh"
 #include "lang.hh"
 #include <emscripten.h>
 #include <locale>
@@ -127,7 +128,8 @@ Node render_expand_link(string board, unsigned long id)
 Node render_last_100_link(string board, unsigned long id)
 {
     std::ostringstream s;
-    s << '/' << board << '/' << id << "?last=100#bottom";
+    s << '/' << board << '/' << id
+      << brunhild::url::Query().set("last", 100).to_string() << "#bottom";
     return render_button(s.str(), lang.ui.at("last") + " 100");
 }
 
@@ -0,0 +1,12 @@
+#pragma once
+
+// Host stand-in for Embind. Bindings are compiled, but never registered.
+
+#include "val.h"
+
+#define EMSCRIPTEN_BINDINGS(name)                                              \
+    [[maybe_unused]] static void _bh_host_bindings_##name()
+
+namespace emscripten {
+template <class F> void function(const char*, F) {}
+}
@@ -0,0 +1,28 @@
+#pragma once
+
+// Host stand-in for the Emscripten runtime API used by brunhild. JS snippets
+// are not executed and evaluate to 0.
+
+#include <chrono>
+
+#define EM_ASM(...) ((void)0)
+#define EM_ASM_INT(...) emscripten_host_asm()
+#define EM_ASM_DOUBLE(...) double(emscripten_host_asm())
+#define EMSCRIPTEN_KEEPALIVE
+
+// Result of a skipped JS snippet. Pointer-sized, as wasm32 code casts results
+// to pointers.
+inline long emscripten_host_asm() { return 0; }
+
+inline double emscripten_get_now()
+{
+    using namespace std::chrono;
+    return duration<double, std::milli>(
+        steady_clock::now().time_since_epoch())
+        .count();
+}
+
+inline void emscripten_set_main_loop(void (*)(), int, int) {}
+inline void emscripten_pause_main_loop() {}
+inline void emscripten_resume_main_loop() {}
+inline void emscripten_cancel_main_loop() {}
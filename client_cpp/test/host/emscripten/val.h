@@ -0,0 +1,55 @@
+#pragma once
+
+// Host stand-in for emscripten::val. There is no JS environment, so every
+// value is null and all operations on it are no-ops returning null or
+// default-constructed values.
+
+#include <string>
+#include <utility>
+#include <vector>
+
+namespace emscripten {
+
+class val {
+public:
+    val() = default;
+
+    template <class T> explicit val(T&&) {}
+
+    static val global(const char* = nullptr) { return val(); }
+    static val object() { return val(); }
+    static val array() { return val(); }
+    template <class T> static val array(const T&) { return val(); }
+    static val null() { return val(); }
+    static val undefined() { return val(); }
+    static val module_property(const char*) { return val(); }
+    static val u8string(const char*) { return val(); }
+
+    template <class... A> val operator()(A&&...) const { return val(); }
+
+    template <class R = val, class... A> R call(const char*, A&&...) const
+    {
+        return R();
+    }
+
+    template <class... A> val new_(A&&...) const { return val(); }
+    template <class K> val operator[](const K&) const { return val(); }
+    template <class K, class V> void set(const K&, const V&) {}
+    template <class K> void delete_(const K&) {}
+    template <class T> T as() const { return T(); }
+
+    bool isNull() const { return true; }
+    bool isUndefined() const { return false; }
+    bool isNumber() const { return false; }
+    bool isString() const { return false; }
+    bool isTrue() const { return false; }
+    bool isFalse() const { return false; }
+    bool strictlyEquals(const val&) const { return false; }
+    bool instanceof(const val&) const { return false; }
+    bool operator==(const val&) const { return false; }
+    bool operator!=(const val&) const { return true; }
+    val typeOf() const { return val(); }
+};
+
+template <class T> std::vector<T> vecFromJSArray(const val&) { return {}; }
+}
@@ -0,0 +1,35 @@
+#include "../brunhild/error.hh"
+#include "../brunhild/headless.hh"
+#include "test.hh"
+#include <iostream>
+#include <utility>
+#include <vector>
+
+static std::vector<std::pair<std::string, std::function<void()>>>& tests()
+{
+    static std::vector<std::pair<std::string, std::function<void()>>> t;
+    return t;
+}
+
+bool register_test(std::string name, std::function<void()> fn)
+{
+    tests().push_back({ std::move(name), std::move(fn) });
+    return true;
+}
+
+int main()
+{
+    int failed = 0;
+    for (auto & [ name, fn ] : tests()) {
+        brunhild::headless::reset();
+        if (auto err = brunhild::try_run(fn)) {
+            std::cerr << "FAIL " << name << ": " << err->what() << '\n';
+            failed++;
+        } else {
+            std::cerr << "ok   " << name << '\n';
+        }
+    }
+    std::cerr << tests().size() - failed << '/' << tests().size()
+              << " tests passed\n";
+    return failed ? 1 : 0;
+}
@@ -0,0 +1,23 @@
+#pragma once
+
+#include "../brunhild/error.hh"
+#include <functional>
+#include <string>
+
+// Register a test case. Test cases throw brunhild::Error on failure.
+#define TEST(name)                                                             \
+    static void test_##name();                                                 \
+    static const bool test_##name##_registered                                 \
+        = register_test(#name, test_##name);                                   \
+    static void test_##name()
+
+// Throw brunhild::Error with the source location, if cond is false
+#define CHECK(cond)                                                            \
+    if (!(cond)) {                                                             \
+        throw brunhild::Error(                                                 \
+            std::string(__FILE__ ":") + std::to_string(__LINE__)               \
+            + ": check failed: " #cond);                                       \
+    }
+
+// Register a test case to be run by main(). Returns true.
+bool register_test(std::string name, std::function<void()> fn);
@@ -0,0 +1,42 @@
+#include "../brunhild/testing.hh"
+#include "../brunhild/error.hh"
+#include "test.hh"
+
+using namespace brunhild;
+
+static Node card()
+{
+    return Node("div", { { "id", "card" }, { "class", "card" } },
+        {
+            Node("h2", "Title & more", true),
+            Node("img", { { "src", "/a.png" }, { "alt", "\"quoted\"" } }),
+        });
+}
+
+TEST(normalize_sorts_attributes)
+{
+    CHECK(testing::normalize("<a id=\"x\" href=\"/\">a</a>")
+        == "<a href=\"/\" id=\"x\">a</a>");
+}
+
+TEST(assert_renders)
+{
+    auto n = card();
+    BH_ASSERT_RENDERS(n,
+        "<div class=\"card\" id=\"card\"><h2>Title &amp; more</h2>"
+        "<img alt=\"&#34;quoted&#34;\" src=\"/a.png\"></div>");
+
+    bool failed = false;
+    try {
+        BH_ASSERT_RENDERS(n, "<div></div>");
+    } catch (Error&) {
+        failed = true;
+    }
+    CHECK(failed);
+}
+
+TEST(assert_snapshot)
+{
+    auto n = card();
+    BH_ASSERT_SNAPSHOT(n, "snapshots/card.html");
+}
@@ -0,0 +1,211 @@
+#include "../brunhild/headless.hh"
+#include "../brunhild/mutations.hh"
+#include "../brunhild/schedule.hh"
+#include "../brunhild/view.hh"
+#include "test.hh"
+#include <memory>
+#include <stdexcept>
+#include <vector>
+
+using namespace brunhild;
+using namespace brunhild::headless;
+
+// Insert a view into the in-memory document and run its mount hooks
+static void mount(View& v)
+{
+    reset(v.html());
+    flush();
+}
+
+namespace {
+class Counter : public VirtualView {
+public:
+    int count = 0;
+    std::string title;
+    bool fail = false;
+
+    Counter()
+        : VirtualView("counter")
+    {
+    }
+
+    Node render()
+    {
+        if (fail) {
+            throw std::runtime_error("render failed");
+        }
+        Attrs attrs = { { "class", count ? "active" : "idle" } };
+        if (title != "") {
+            attrs["title"] = title;
+        }
+        return Node("div", attrs,
+            { Node("span", { { "id", "value" } }, std::to_string(count)) });
+    }
+};
+
+struct Item {
+    std::string id;
+};
+
+class ItemView : public VirtualView {
+public:
+    ItemView(Item* item)
+        : VirtualView("item-" + item->id)
+        , item(item)
+    {
+    }
+
+    Item* get_model() { return item; }
+
+    Node render() { return Node("li", item->id); }
+
+private:
+    Item* item;
+};
+
+class List : public ListView<Item, ItemView> {
+public:
+    std::vector<Item*> items;
+
+    List()
+        : ListView("ul", "list")
+    {
+    }
+
+protected:
+    std::vector<Item*> get_list() { return items; }
+
+    std::shared_ptr<ItemView> create_child(Item* item)
+    {
+        return std::make_shared<ItemView>(item);
+    }
+};
+}
+
+// Returns the expected inner HTML of a List
+static std::string list_html(const std::vector<std::string>& ids)
+{
+    std::string s;
+    for (auto& id : ids) {
+        s += "<li id=\"item-" + id + "\">" + id + "</li>";
+    }
+    return s;
+}
+
+TEST(patch_text)
+{
+    Counter v;
+    mount(v);
+    assert_inner_html("value", "0");
+
+    clear_operations();
+    v.count = 2;
+    v.patch();
+    flush();
+    assert_outer_html("counter",
+        "<div class=\"active\" id=\"counter\"><span id=\"value\">2</span>"
+        "</div>");
+
+    // Class and text changed
+    CHECK(operations().size() == 2);
+}
+
+TEST(escape_attributes)
+{
+    Counter v;
+    v.title = "\"a\" & <b>";
+    mount(v);
+    CHECK(get("counter")->attrs.at("title") == v.title);
+
+    v.title = "it's";
+    v.patch();
+    flush();
+    CHECK(get("counter")->attrs.at("title") == "it's");
+    assert_outer_html("counter",
+        "<div class=\"idle\" id=\"counter\" title=\"it&#39;s\">"
+        "<span id=\"value\">0</span></div>");
+}
+
+TEST(add_class)
+{
+    Counter v;
+    mount(v);
+    CHECK(v.add_class("value", "big"));
+    flush();
+    CHECK(get("value")->attrs.at("class") == "big");
+}
+
+TEST(try_patch_rollback)
+{
+    Counter v;
+    mount(v);
+    const auto html = get("counter")->outer_html();
+
+    v.count = 1;
+    v.fail = true;
+    CHECK(v.try_patch());
+    flush();
+    CHECK(get("counter")->outer_html() == html);
+
+    v.fail = false;
+    v.patch();
+    flush();
+    assert_inner_html("value", "1");
+}
+
+TEST(list_append)
+{
+    Item a{ "a" }, b{ "b" }, c{ "c" };
+    List l;
+    l.items = { &a, &b };
+    mount(l);
+    assert_inner_html("list", list_html({ "a", "b" }));
+
+    l.items = { &a, &b, &c };
+    l.patch();
+    flush();
+    assert_inner_html("list", list_html({ "a", "b", "c" }));
+}
+
+TEST(list_reorder)
+{
+    Item a{ "a" }, b{ "b" }, c{ "c" };
+    List l;
+    l.items = { &a, &b, &c };
+    mount(l);
+
+    l.items = { &c, &a, &b };
+    l.patch();
+    flush();
+    assert_inner_html("list", list_html({ "c", "a", "b" }));
+
+    l.items = { &c, &b };
+    l.patch();
+    flush();
+    assert_inner_html("list", list_html({ "c", "b" }));
+    assert_missing("item-a");
+}
+
+TEST(user_input_priority)
+{
+    Counter v;
+    mount(v);
+
+    // Marked during the flush, but still diffed in the current frame
+    defer([&v]() {
+        v.count = 1;
+        mark_dirty(v, Priority::user_input);
+    });
+    flush();
+    assert_inner_html("value", "1");
+
+    // Normal priority updates marked during the flush wait for the next one
+    defer([&v]() {
+        v.count = 2;
+        mark_dirty(v);
+    });
+    flush();
+    assert_inner_html("value", "1");
+    flush();
+    assert_inner_html("value", "2");
+}